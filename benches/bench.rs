@@ -4,12 +4,14 @@ use ark_ff::PrimeField;
 use ark_poly::{
     univariate::DensePolynomial, DenseUVPolynomial, EvaluationDomain, Radix2EvaluationDomain,
 };
+use ark_std::UniformRand;
 use criterion::{
     criterion_group, criterion_main, measurement::Measurement, BenchmarkGroup, BenchmarkId,
     Criterion,
 };
 use merlin::Transcript;
 use poly_multiproof::{
+    lagrange::LagrangeInterpContext,
     method1,
     method1::precompute as m1_precomp,
     method2,
@@ -124,6 +126,389 @@ fn verify_benchmark(c: &mut Criterion) {
     }
 }
 
+/// Verifies with every number of commitments up to 16, around where the gamma combination of
+/// the commitments switches from horner's rule to an msm
+fn verify_few_commits_benchmark(c: &mut Criterion) {
+    let mut group = c.benchmark_group("verify_few_commits");
+    let m1 = M1::new(WIDTH, 16.into(), &mut thread_rng()).unwrap();
+    #[cfg(feature = "blst")]
+    let m1_blst = M1Blst::new_from_affine(&m1.powers_of_g1, &m1.powers_of_g2);
+    let grid = TestGrid::<Fr>::gen_grid(WIDTH, 16).trim_pts(16);
+    let commits = grid
+        .coeffs
+        .iter()
+        .map(|c| m1.commit(c).unwrap())
+        .collect::<Vec<_>>();
+    let m1_pc =
+        M1Pc::from_inner(m1.clone(), vec![grid.points.clone()]).expect("Failed to construct m1_pc");
+    #[cfg(feature = "blst")]
+    let m1_blst_pc = M1BlstPc::from_inner(m1_blst.clone(), vec![grid.points.clone()])
+        .expect("Failed to construct m1_blst_pc");
+    for n_poly in 1..=16 {
+        let subgrid = grid.trim_poly(n_poly);
+        run_verify(
+            &m1_pc,
+            &subgrid,
+            &mut group,
+            &commits,
+            "m1_pc".to_string(),
+            n_poly,
+        );
+        #[cfg(feature = "blst")]
+        run_verify(
+            &m1_blst_pc,
+            &subgrid,
+            &mut group,
+            &commits,
+            "m1blst_pc".to_string(),
+            n_poly,
+        );
+    }
+}
+
+/// Verifies with either side of 32 commitments, where the gamma powers stop fitting in verify's
+/// stack buffer and spill to the heap
+fn verify_gamma_powers_benchmark(c: &mut Criterion) {
+    let mut group = c.benchmark_group("verify_gamma_powers");
+    let m1 = M1::new(WIDTH, 16.into(), &mut thread_rng()).unwrap();
+    let m2 = M2::new_from_powers(&m1.powers_of_g1, &m1.powers_of_g2).unwrap();
+    let grid = TestGrid::<Fr>::gen_grid(WIDTH, 48).trim_pts(16);
+    let commits = grid
+        .coeffs
+        .iter()
+        .map(|c| m1.commit(c).unwrap())
+        .collect::<Vec<_>>();
+    for n_poly in [8, 16, 32, 33, 48] {
+        let subgrid = grid.trim_poly(n_poly);
+        let m1_open = m1
+            .open(
+                &mut Transcript::new(b"bench"),
+                &subgrid.evals,
+                &subgrid.coeffs,
+                &subgrid.points,
+            )
+            .unwrap();
+        let m2_open = m2
+            .open(
+                &mut Transcript::new(b"bench"),
+                &subgrid.evals,
+                &subgrid.coeffs,
+                &subgrid.points,
+            )
+            .unwrap();
+        group.bench_with_input(BenchmarkId::new("m1", n_poly), &n_poly, |b, _i| {
+            b.iter(|| {
+                m1.verify(
+                    &mut Transcript::new(b"bench"),
+                    &commits[..n_poly],
+                    &subgrid.points,
+                    &subgrid.evals,
+                    &m1_open,
+                )
+                .unwrap()
+            })
+        });
+        group.bench_with_input(BenchmarkId::new("m2", n_poly), &n_poly, |b, _i| {
+            b.iter(|| {
+                m2.verify(
+                    &mut Transcript::new(b"bench"),
+                    &commits[..n_poly],
+                    &subgrid.points,
+                    &subgrid.evals,
+                    &m2_open,
+                )
+                .unwrap()
+            })
+        });
+    }
+}
+
+/// Verifying one commitment at point sets of growing size, with the precomputed lagrange basis
+/// and g2 zeros against rebuilding them per call. With the precomputes, the only part that grows
+/// with the point set is the msm of the interpolation.
+fn verify_point_set_size_benchmark(c: &mut Criterion) {
+    let mut group = c.benchmark_group("verify_point_set_size");
+    let m1 = M1::new(WIDTH, 256.into(), &mut thread_rng()).unwrap();
+    let grid = TestGrid::<Fr>::gen_grid(WIDTH, 1);
+    let commits = vec![m1.commit(&grid.coeffs[0]).unwrap()];
+    for n_pts in [4, 16, 64, 256] {
+        let subgrid = grid.trim_pts(n_pts);
+        let m1_pc = M1Pc::from_inner(m1.clone(), vec![subgrid.points.clone()])
+            .expect("Failed to construct m1_pc");
+        let open = m1_pc
+            .open(
+                &mut Transcript::new(b"bench"),
+                &subgrid.evals,
+                &subgrid.coeffs,
+                0,
+            )
+            .unwrap();
+        group.bench_with_input(BenchmarkId::new("m1_pc", n_pts), &n_pts, |b, _i| {
+            b.iter(|| {
+                m1_pc
+                    .verify(
+                        &mut Transcript::new(b"bench"),
+                        &commits,
+                        0,
+                        &subgrid.evals,
+                        &open,
+                    )
+                    .unwrap()
+            })
+        });
+        group.bench_with_input(BenchmarkId::new("m1", n_pts), &n_pts, |b, _i| {
+            b.iter(|| {
+                m1.verify(
+                    &mut Transcript::new(b"bench"),
+                    &commits,
+                    &subgrid.points,
+                    &subgrid.evals,
+                    &open,
+                )
+                .unwrap()
+            })
+        });
+    }
+}
+
+/// The cost of the curve and subgroup checks `verify` does over `verify_unchecked`
+fn verify_checks_benchmark(c: &mut Criterion) {
+    let mut group = c.benchmark_group("verify_checks");
+    let m1 = M1::new(WIDTH, 16.into(), &mut thread_rng()).unwrap();
+    let grid = TestGrid::<Fr>::gen_grid(WIDTH, HEIGHT).trim_pts(16);
+    let commits = grid
+        .coeffs
+        .iter()
+        .map(|c| m1.commit(c).unwrap())
+        .collect::<Vec<_>>();
+    let m1_pc = M1Pc::from_inner(m1, vec![grid.points.clone()]).expect("Failed to construct m1_pc");
+    for n_poly in (HEIGHT_STEP..=HEIGHT).step_by(HEIGHT_STEP) {
+        let subgrid = grid.trim_poly(n_poly);
+        let open = m1_pc
+            .open(
+                &mut Transcript::new(b"bench"),
+                &subgrid.evals,
+                &subgrid.coeffs,
+                0,
+            )
+            .unwrap();
+        group.bench_with_input(BenchmarkId::new("checked", n_poly), &n_poly, |b, _i| {
+            b.iter(|| {
+                assert!(m1_pc
+                    .verify(
+                        &mut Transcript::new(b"bench"),
+                        &commits[..n_poly],
+                        0,
+                        &subgrid.evals,
+                        &open
+                    )
+                    .unwrap());
+            })
+        });
+        group.bench_with_input(BenchmarkId::new("unchecked", n_poly), &n_poly, |b, _i| {
+            b.iter(|| {
+                assert!(m1_pc
+                    .verify_unchecked(
+                        &mut Transcript::new(b"bench"),
+                        &commits[..n_poly],
+                        0,
+                        &subgrid.evals,
+                        &open
+                    )
+                    .unwrap());
+            })
+        });
+    }
+}
+
+/// Opening many polynomials at one point, with the direct division by $X - z$ against the general
+/// path through the vanishing polynomial
+fn shared_point_benchmark(c: &mut Criterion) {
+    let mut group = c.benchmark_group("shared_point");
+    let m1 = M1::new(WIDTH, 16.into(), &mut thread_rng()).unwrap();
+    let grid = TestGrid::<Fr>::gen_grid(WIDTH, HEIGHT).trim_pts(1);
+    let z = grid.points[0];
+    for n_poly in (HEIGHT_STEP..=HEIGHT).step_by(HEIGHT_STEP * 4) {
+        let subgrid = grid.trim_poly(n_poly);
+        let evals_at_z = subgrid.evals.iter().map(|e| e[0]).collect::<Vec<_>>();
+        group.bench_with_input(BenchmarkId::new("shared", n_poly), &n_poly, |b, _i| {
+            b.iter(|| {
+                m1.open_shared_point(
+                    &mut Transcript::new(b"bench"),
+                    z,
+                    &subgrid.coeffs,
+                    &evals_at_z,
+                )
+                .unwrap()
+            })
+        });
+        group.bench_with_input(BenchmarkId::new("general", n_poly), &n_poly, |b, _i| {
+            b.iter(|| {
+                m1.open(
+                    &mut Transcript::new(b"bench"),
+                    &subgrid.evals,
+                    &subgrid.coeffs,
+                    &subgrid.points,
+                )
+                .unwrap()
+            })
+        });
+    }
+}
+
+/// Verifying against the same evals repeatedly, with and without the interpolation cached
+fn cached_gamma_ris_benchmark(c: &mut Criterion) {
+    let mut group = c.benchmark_group("cached_gamma_ris");
+    let m1 = M1::new(WIDTH, WIDTH.into(), &mut thread_rng()).unwrap();
+    let grid = TestGrid::<Fr>::gen_grid(WIDTH, HEIGHT);
+    let commits = grid
+        .coeffs
+        .iter()
+        .map(|c| m1.commit(c).unwrap())
+        .collect::<Vec<_>>();
+    for n_pts in [16, 64, 128] {
+        let subgrid = grid.trim_pts(n_pts);
+        let open = m1
+            .open(
+                &mut Transcript::new(b"bench"),
+                &subgrid.evals,
+                &subgrid.coeffs,
+                &subgrid.points,
+            )
+            .unwrap();
+        let gamma_ris = m1
+            .gamma_ris(&Transcript::new(b"bench"), &subgrid.points, &subgrid.evals)
+            .unwrap();
+        group.bench_with_input(BenchmarkId::new("interp", n_pts), &n_pts, |b, _i| {
+            b.iter(|| {
+                m1.verify(
+                    &mut Transcript::new(b"bench"),
+                    &commits,
+                    &subgrid.points,
+                    &subgrid.evals,
+                    &open,
+                )
+                .unwrap()
+            })
+        });
+        group.bench_with_input(BenchmarkId::new("cached", n_pts), &n_pts, |b, _i| {
+            b.iter(|| {
+                m1.verify_with_gamma_ris(
+                    &mut Transcript::new(b"bench"),
+                    &commits,
+                    &subgrid.points,
+                    &subgrid.evals,
+                    &open,
+                    &gamma_ris,
+                )
+                .unwrap()
+            })
+        });
+    }
+}
+
+/// Verifying small heterogeneous batches one by one against one multi-pairing, which picks
+/// `DEFAULT_BATCH_THRESHOLD`
+fn heterogeneous_threshold_benchmark(c: &mut Criterion) {
+    use method2::VerifyItem;
+    let mut group = c.benchmark_group("heterogeneous_threshold");
+    let m2 = M2::new(256, None, &mut thread_rng()).unwrap();
+    let grid = TestGrid::<Fr>::gen_grid(256, 4).trim_pts(8);
+    let commits = grid
+        .coeffs
+        .iter()
+        .map(|c| m2.commit(c).unwrap())
+        .collect::<Vec<_>>();
+    let opens = (0..4)
+        .map(|i| {
+            m2.open(
+                &mut Transcript::new(b"bench"),
+                &grid.evals[i..i + 1],
+                &grid.coeffs[i..i + 1],
+                &grid.points,
+            )
+            .unwrap()
+        })
+        .collect::<Vec<_>>();
+    let items = (0..4)
+        .map(|i| VerifyItem {
+            transcript: Transcript::new(b"bench"),
+            commits: &commits[i..i + 1],
+            points: &grid.points,
+            evals: &grid.evals[i..i + 1],
+            proof: &opens[i],
+        })
+        .collect::<Vec<_>>();
+    for n_items in 1..=4 {
+        for (name, threshold) in [("separate", usize::MAX), ("batched", 0)] {
+            group.bench_with_input(BenchmarkId::new(name, n_items), &n_items, |b, _i| {
+                b.iter(|| {
+                    m2.verify_heterogeneous_with_threshold(
+                        &items[..n_items],
+                        &mut thread_rng(),
+                        threshold,
+                    )
+                    .unwrap()
+                })
+            });
+        }
+    }
+}
+
+fn lagrange_benchmark(c: &mut Criterion) {
+    let mut group = c.benchmark_group("lagrange");
+    for n_pts in [32, 64, 128, 256, 512] {
+        let points = (0..n_pts)
+            .map(|_| Fr::rand(&mut thread_rng()))
+            .collect::<Vec<_>>();
+        let evals = vec![(0..n_pts)
+            .map(|_| Fr::rand(&mut thread_rng()))
+            .collect::<Vec<_>>()];
+        let scalars = [Fr::from(1u64)];
+        group.bench_with_input(BenchmarkId::new("points", n_pts), &n_pts, |b, _i| {
+            b.iter(|| {
+                LagrangeInterpContext::new_from_points(&points)
+                    .unwrap()
+                    .lagrange_interp_linear_combo(&evals, &scalars)
+                    .unwrap()
+            })
+        });
+    }
+}
+
+/// Interpolating through the lagrange basis against a subproduct tree, on either side of the
+/// point count where `new_from_points` switches between them
+#[cfg(feature = "debug")]
+fn lagrange_strategy_benchmark(c: &mut Criterion) {
+    use poly_multiproof::lagrange::InterpStrategy;
+    let mut group = c.benchmark_group("lagrange_strategy");
+    for n_pts in [64, 96, 128, 192, 256] {
+        let points = (0..n_pts)
+            .map(|_| Fr::rand(&mut thread_rng()))
+            .collect::<Vec<_>>();
+        let evals = vec![(0..n_pts)
+            .map(|_| Fr::rand(&mut thread_rng()))
+            .collect::<Vec<_>>()];
+        let scalars = [Fr::from(1u64)];
+        for (name, strategy) in [
+            ("naive", InterpStrategy::Naive),
+            ("tree", InterpStrategy::SubproductTree),
+        ] {
+            group.bench_with_input(BenchmarkId::new(name, n_pts), &n_pts, |b, _i| {
+                b.iter(|| {
+                    LagrangeInterpContext::new_from_points_with_strategy(&points, strategy)
+                        .unwrap()
+                        .lagrange_interp_linear_combo(&evals, &scalars)
+                        .unwrap()
+                })
+            });
+        }
+    }
+}
+
+#[cfg(not(feature = "debug"))]
+fn lagrange_strategy_benchmark(_c: &mut Criterion) {}
+
 fn open_benchmark(c: &mut Criterion) {
     let mut group = c.benchmark_group("open");
     let m1 = M1::new(WIDTH, WIDTH.into(), &mut thread_rng()).unwrap();
@@ -208,5 +593,120 @@ impl<F: PrimeField> TestGrid<F> {
     }
 }
 
-criterion_group!(benches, open_benchmark, verify_benchmark);
+/// Committing to every row of a grid one at a time against `commit_many`, which reuses its scalar
+/// buffers across rows
+#[cfg(feature = "blst")]
+fn commit_many_benchmark(c: &mut Criterion) {
+    let mut group = c.benchmark_group("commit_many");
+    let m1 = M1::new(WIDTH, 16.into(), &mut thread_rng()).unwrap();
+    let m1_blst = M1Blst::new_from_affine(&m1.powers_of_g1, &m1.powers_of_g2);
+    let grid = TestGrid::<Fr>::gen_grid(WIDTH, HEIGHT);
+    group.sample_size(10);
+    group.bench_function("m1blst_commit_each", |b| {
+        b.iter(|| {
+            grid.coeffs
+                .iter()
+                .map(|c| m1_blst.commit(c).unwrap())
+                .collect::<Vec<_>>()
+        })
+    });
+    group.bench_function("m1blst_commit_many", |b| {
+        b.iter(|| m1_blst.commit_many(&grid.coeffs).unwrap())
+    });
+    // Only the msms, with the scalars serialized outside the loop
+    let prepared = m1_blst.prepare(grid.coeffs.clone());
+    group.bench_function("m1blst_commit_prepared", |b| {
+        b.iter(|| prepared.commit_all().unwrap())
+    });
+}
+
+#[cfg(not(feature = "blst"))]
+fn commit_many_benchmark(_c: &mut Criterion) {}
+
+/// Committing with fixed base tables of increasing size
+#[cfg(feature = "blst")]
+fn precompute_level_benchmark(c: &mut Criterion) {
+    let mut group = c.benchmark_group("precompute_level");
+    let m1 = M1::new(WIDTH, 16.into(), &mut thread_rng()).unwrap();
+    let grid = TestGrid::<Fr>::gen_grid(WIDTH, 1);
+    for level in [0, 4, 6] {
+        let m1_blst =
+            M1Blst::new_with_precompute_level(&m1.powers_of_g1, &m1.powers_of_g2, level).unwrap();
+        group.bench_with_input(BenchmarkId::new("m1blst_commit", level), &level, |b, _i| {
+            b.iter(|| m1_blst.commit(&grid.coeffs[0]).unwrap())
+        });
+    }
+}
+
+#[cfg(not(feature = "blst"))]
+fn precompute_level_benchmark(_c: &mut Criterion) {}
+
+/// Committing and opening with the blst and arkworks msm backends on the same powers
+#[cfg(feature = "blst")]
+fn msm_backend_benchmark(c: &mut Criterion) {
+    use poly_multiproof::msm::ArkworksMsm;
+    use std::sync::Arc;
+    let mut group = c.benchmark_group("msm_backend");
+    let m1 = M1::new(WIDTH, 16.into(), &mut thread_rng()).unwrap();
+    let ark = ArkworksMsm::<Bls12_381>::new(m1.powers_of_g1.clone(), m1.powers_of_g2.clone());
+    let backends = [
+        (
+            "blst",
+            M1Blst::new_from_affine(&m1.powers_of_g1, &m1.powers_of_g2),
+        ),
+        (
+            "arkworks",
+            M1Blst::new_with_backend(&m1.powers_of_g1, &m1.powers_of_g2, Arc::new(ark)),
+        ),
+    ];
+    let grid = TestGrid::<Fr>::gen_grid(WIDTH, 1).trim_pts(16);
+    for (name, m1_blst) in &backends {
+        group.bench_function(BenchmarkId::new("commit", name), |b| {
+            b.iter(|| m1_blst.commit(&grid.coeffs[0]).unwrap())
+        });
+        group.bench_function(BenchmarkId::new("open", name), |b| {
+            b.iter(|| {
+                m1_blst
+                    .open(
+                        &mut Transcript::new(b"bench"),
+                        &grid.evals,
+                        &grid.coeffs,
+                        &grid.points,
+                    )
+                    .unwrap()
+            })
+        });
+    }
+}
+
+#[cfg(not(feature = "blst"))]
+fn msm_backend_benchmark(_c: &mut Criterion) {}
+
+/// Erasure extending the columns of a full grid. Compare with and without the `parallel` feature.
+fn extend_rows_benchmark(c: &mut Criterion) {
+    let mut group = c.benchmark_group("extend_rows");
+    let grid = TestGrid::<Fr>::gen_grid(WIDTH, HEIGHT);
+    group.bench_function("extend_rows", |b| {
+        b.iter(|| poly_multiproof::grid::extend_rows(&grid.evals).unwrap())
+    });
+}
+
+criterion_group!(
+    benches,
+    open_benchmark,
+    verify_benchmark,
+    verify_few_commits_benchmark,
+    verify_gamma_powers_benchmark,
+    verify_point_set_size_benchmark,
+    verify_checks_benchmark,
+    commit_many_benchmark,
+    precompute_level_benchmark,
+    msm_backend_benchmark,
+    shared_point_benchmark,
+    heterogeneous_threshold_benchmark,
+    lagrange_benchmark,
+    lagrange_strategy_benchmark,
+    cached_gamma_ris_benchmark,
+    extend_rows_benchmark
+);
 criterion_main!(benches);
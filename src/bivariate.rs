@@ -0,0 +1,214 @@
+//! Experimental bivariate KZG commitments, so a whole 2D grid can be committed to as a single
+//! point instead of one commitment per row.
+//!
+//! A polynomial $f(X, Y) = \sum_{i, j} f_{i, j} X^i Y^j$ is given by its coefficients, where
+//! `coeffs[i][j]` is $f_{i, j}$. The SRS holds $g_1^{x^i y^j}$ for every $i <$ `x_coeffs` and
+//! $j <$ `y_coeffs`, laid out row by row so that $g_1^{x^i y^j}$ is at index
+//! `i * y_coeffs + j`, along with $g_2$, $g_2^x$ and $g_2^y$.
+//!
+//! To open at $(a, b)$ to $v = f(a, b)$, the prover writes
+//! $f(X, Y) - v = (X - a) q_1(X, Y) + (Y - b) q_2(Y)$ and sends $\pi_1 = g_1^{q_1(x, y)}$ and
+//! $\pi_2 = g_1^{q_2(y)}$. The verifier checks
+//! $e(C - g_1^v, g_2) = e(\pi_1, g_2^{x - a}) \cdot e(\pi_2, g_2^{y - b})$.
+
+use ark_ec::{pairing::Pairing, AffineRepr, CurveGroup};
+use ark_ff::{One, Zero};
+use ark_poly::{univariate::DensePolynomial, DenseUVPolynomial, Polynomial};
+use ark_std::{rand::RngCore, UniformRand};
+
+use crate::{curve_msm, gen_curve_powers, gen_powers, poly_div_q_r, Commitment, Error};
+
+#[derive(Clone, Debug)]
+pub struct BivariateSetup<E: Pairing> {
+    pub powers_of_g1: Vec<E::G1Affine>,
+    pub g2: E::G2Affine,
+    pub g2x: E::G2Affine,
+    pub g2y: E::G2Affine,
+    x_coeffs: usize,
+    y_coeffs: usize,
+}
+
+#[derive(Clone, Debug)]
+pub struct Proof<E: Pairing>(E::G1Affine, E::G1Affine);
+
+impl<E: Pairing> BivariateSetup<E> {
+    /// Makes a setup for polynomials with up to `x_coeffs` coefficients in $X$ and `y_coeffs`
+    /// coefficients in $Y$
+    pub fn new(x_coeffs: usize, y_coeffs: usize, rng: &mut impl RngCore) -> Result<Self, Error> {
+        let x = E::ScalarField::rand(rng);
+        let y = E::ScalarField::rand(rng);
+        let x_powers = gen_powers(x, x_coeffs);
+        let y_powers = gen_powers(y, y_coeffs);
+        let xy_powers = x_powers
+            .iter()
+            .flat_map(|xi| y_powers.iter().map(move |yj| *xi * yj))
+            .collect::<Vec<_>>();
+
+        let powers_of_g1 = gen_curve_powers::<E::G1>(&xy_powers, rng);
+        let g2s = gen_curve_powers::<E::G2>(&[E::ScalarField::one(), x, y], rng);
+
+        Ok(Self {
+            powers_of_g1,
+            g2: g2s[0],
+            g2x: g2s[1],
+            g2y: g2s[2],
+            x_coeffs,
+            y_coeffs,
+        })
+    }
+
+    /// Copies `coeffs` into rows of exactly `y_coeffs` coefficients each
+    fn padded_rows(
+        &self,
+        coeffs: &[impl AsRef<[E::ScalarField]>],
+    ) -> Result<Vec<Vec<E::ScalarField>>, Error> {
+        if coeffs.len() > self.x_coeffs {
+            return Err(Error::PolynomialTooLarge {
+                n_coeffs: coeffs.len(),
+                expected_max: self.x_coeffs,
+            });
+        }
+        coeffs
+            .iter()
+            .map(|row| {
+                let row = row.as_ref();
+                if row.len() > self.y_coeffs {
+                    return Err(Error::PolynomialTooLarge {
+                        n_coeffs: row.len(),
+                        expected_max: self.y_coeffs,
+                    });
+                }
+                let mut padded = row.to_vec();
+                padded.resize(self.y_coeffs, E::ScalarField::zero());
+                Ok(padded)
+            })
+            .collect()
+    }
+
+    pub fn commit(&self, coeffs: &[impl AsRef<[E::ScalarField]>]) -> Result<Commitment<E>, Error> {
+        let flat = self.padded_rows(coeffs)?.concat();
+        Ok(Commitment(
+            curve_msm::<E::G1>(&self.powers_of_g1, &flat)?.into_affine(),
+        ))
+    }
+
+    /// Opens the polynomial at $(a, b)$, returning $f(a, b)$ and a proof of it
+    pub fn open(
+        &self,
+        coeffs: &[impl AsRef<[E::ScalarField]>],
+        a: E::ScalarField,
+        b: E::ScalarField,
+    ) -> Result<(E::ScalarField, Proof<E>), Error> {
+        let rows = self.padded_rows(coeffs)?;
+
+        // Synthetic division by (X - a), treating each row as a coefficient in Y.
+        // The running horner value is the next row of q_1, and finally f(a, Y).
+        let mut q1 = vec![Vec::new(); rows.len().saturating_sub(1)];
+        let mut f_a = vec![E::ScalarField::zero(); self.y_coeffs];
+        for i in (0..rows.len()).rev() {
+            for j in 0..self.y_coeffs {
+                f_a[j] = f_a[j] * a + rows[i][j];
+            }
+            if i > 0 {
+                q1[i - 1] = f_a.clone();
+            }
+        }
+
+        // f(a, Y) - v = (Y - b) q_2(Y)
+        let f_a = DensePolynomial::from_coefficients_vec(f_a);
+        let v = f_a.evaluate(&b);
+        let y_minus_b = DensePolynomial::from_coefficients_vec(vec![-b, E::ScalarField::one()]);
+        let (q2, _) = poly_div_q_r((&f_a).into(), (&y_minus_b).into())?;
+
+        let pi_1 = curve_msm::<E::G1>(&self.powers_of_g1, &q1.concat())?.into_affine();
+        let pi_2 = curve_msm::<E::G1>(&self.powers_of_g1, &q2)?.into_affine();
+        Ok((v, Proof(pi_1, pi_2)))
+    }
+
+    /// Checks that `proof` opens `commit` to `v` at $(a, b)$, erroring with
+    /// [`Error::EmptyPowers`] if the setup has no g1 powers
+    pub fn verify(
+        &self,
+        commit: &Commitment<E>,
+        a: E::ScalarField,
+        b: E::ScalarField,
+        v: E::ScalarField,
+        proof: &Proof<E>,
+    ) -> Result<bool, Error> {
+        let g1 = self.powers_of_g1.first().ok_or(Error::EmptyPowers)?;
+        let lhs = commit.0.into_group() - *g1 * v;
+        let x_minus_a = self.g2x.into_group() - self.g2 * a;
+        let y_minus_b = self.g2y.into_group() - self.g2 * b;
+        Ok(
+            E::pairing(lhs, self.g2)
+                == E::multi_pairing([proof.0, proof.1], [x_minus_a, y_minus_b]),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::BivariateSetup;
+    use crate::{test_rng, Error};
+    use ark_bls12_381::{Bls12_381, Fr};
+    use ark_ff::{Field, Zero};
+    use ark_std::UniformRand;
+
+    #[test]
+    fn test_open_works() {
+        let s = BivariateSetup::<Bls12_381>::new(8, 6, &mut test_rng()).unwrap();
+        let coeffs = (0..8)
+            .map(|_| {
+                (0..6)
+                    .map(|_| Fr::rand(&mut test_rng()))
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        let commit = s.commit(&coeffs).unwrap();
+        let a = Fr::rand(&mut test_rng());
+        let b = Fr::rand(&mut test_rng());
+
+        let mut expected = Fr::zero();
+        for (i, row) in coeffs.iter().enumerate() {
+            for (j, c) in row.iter().enumerate() {
+                expected += *c * a.pow([i as u64]) * b.pow([j as u64]);
+            }
+        }
+
+        let (v, proof) = s.open(&coeffs, a, b).unwrap();
+        assert_eq!(expected, v);
+        assert_eq!(Ok(true), s.verify(&commit, a, b, v, &proof));
+        assert_eq!(
+            Ok(false),
+            s.verify(&commit, a, b, v + Fr::from(1u64), &proof)
+        );
+    }
+
+    #[test]
+    fn test_too_large_errors() {
+        let s = BivariateSetup::<Bls12_381>::new(2, 2, &mut test_rng()).unwrap();
+        assert!(s.commit(&[vec![Fr::zero(); 3]]).is_err());
+        assert!(s.commit(&vec![vec![Fr::zero(); 2]; 3]).is_err());
+    }
+
+    #[test]
+    fn test_empty_setup_errors() {
+        use super::Proof;
+        use crate::Commitment;
+        use ark_bls12_381::G1Affine;
+        use ark_ec::AffineRepr;
+
+        let s = BivariateSetup::<Bls12_381>::new(0, 6, &mut test_rng()).unwrap();
+        let proof = Proof(G1Affine::zero(), G1Affine::zero());
+        assert_eq!(
+            Err(Error::EmptyPowers),
+            s.verify(
+                &Commitment(G1Affine::zero()),
+                Fr::zero(),
+                Fr::zero(),
+                Fr::zero(),
+                &proof
+            )
+        );
+    }
+}
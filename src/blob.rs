@@ -0,0 +1,184 @@
+//! Fixed size blobs of field elements, as in EIP-4844, where a blob is the evaluations of a
+//! polynomial over the size `N` roots of unity.
+//!
+//! Following EIP-4844, each element is serialized as 32 big endian bytes and must be less than the
+//! modulus, and the evaluations are listed over the domain in bit-reversed order, i.e. the $i$th
+//! element is the evaluation at $\omega^{\mathrm{rev}(i)}$. Commitments are serialized as 48 byte
+//! compressed points in the zcash encoding.
+
+use ark_bls12_381::{Bls12_381, Fr, G1Affine};
+use ark_ff::{BigInt, BigInteger, PrimeField};
+use ark_poly::{EvaluationDomain, GeneralEvaluationDomain};
+use ark_serialize::CanonicalSerialize;
+
+use crate::{traits::EvalCommitter, Error};
+
+/// The number of bytes each field element takes up in a blob
+pub const BYTES_PER_FIELD_ELEMENT: usize = 32;
+
+/// An EIP-4844 blob
+pub type Eip4844Blob = Blob<4096>;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Blob<const N: usize>(pub Box<[Fr; N]>);
+
+impl<const N: usize> Blob<N> {
+    /// Reads a blob from `N` big endian field elements, erroring if any isn't less than the modulus
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        if bytes.len() != N * BYTES_PER_FIELD_ELEMENT {
+            return Err(Error::BlobIncorrectSize {
+                n: bytes.len(),
+                expected: N * BYTES_PER_FIELD_ELEMENT,
+            });
+        }
+        let elems = bytes
+            .chunks_exact(BYTES_PER_FIELD_ELEMENT)
+            .enumerate()
+            .map(|(index, chunk)| {
+                let mut limbs = [0u64; 4];
+                for (limb, be) in limbs.iter_mut().zip(chunk.rchunks_exact(8)) {
+                    *limb = u64::from_be_bytes(be.try_into().expect("chunk is 8 bytes"));
+                }
+                Fr::from_bigint(BigInt::new(limbs)).ok_or(Error::NonCanonicalScalar { index })
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+        Ok(Blob(
+            elems
+                .into_boxed_slice()
+                .try_into()
+                .expect("blob has N elements"),
+        ))
+    }
+
+    /// Writes the blob as `N` big endian field elements
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.0
+            .iter()
+            .flat_map(|x| x.into_bigint().to_bytes_be())
+            .collect()
+    }
+
+    /// The evaluations in natural order, so the $i$th is the evaluation at $\omega^i$. `N` must be
+    /// a power of two.
+    pub fn evals_natural(&self) -> Vec<Fr> {
+        (0..N).map(|i| self.0[bit_reverse(i, N)]).collect()
+    }
+
+    /// Commits to the blob's polynomial, returning the compressed commitment. `N` must be a power
+    /// of two.
+    pub fn commit(&self, committer: &impl EvalCommitter<Bls12_381>) -> Result<[u8; 48], Error> {
+        let domain = GeneralEvaluationDomain::<Fr>::new(N)
+            .filter(|d| d.size() == N)
+            .ok_or(Error::BlobIncorrectSize {
+                n: N * BYTES_PER_FIELD_ELEMENT,
+                expected: N.next_power_of_two() * BYTES_PER_FIELD_ELEMENT,
+            })?;
+        let commit = committer.commit_lagrange(&self.evals_natural(), &domain)?;
+        Ok(compress_g1(&commit.0))
+    }
+}
+
+/// Reverses the low `log2(n)` bits of `i`, for `n` a power of two
+fn bit_reverse(i: usize, n: usize) -> usize {
+    if n <= 1 {
+        return i;
+    }
+    i.reverse_bits() >> (usize::BITS - n.trailing_zeros())
+}
+
+/// The zcash encoding of a G1 point, which is arkworks' compressed serialization for bls12-381
+fn compress_g1(p: &G1Affine) -> [u8; 48] {
+    let mut out = [0u8; 48];
+    p.serialize_compressed(&mut out[..])
+        .expect("A compressed G1 point is 48 bytes");
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Blob, Eip4844Blob, BYTES_PER_FIELD_ELEMENT};
+    use crate::{
+        gen_curve_powers_with_generator, gen_powers, method1::M1NoPrecomp, test_rng, Error,
+    };
+    use ark_bls12_381::{Fr, G1Projective, G2Projective};
+    use ark_ec::Group;
+    use ark_ff::One;
+    use ark_std::UniformRand;
+
+    fn from_hex(s: &str) -> Vec<u8> {
+        (0..s.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&s[i..i + 2], 16).unwrap())
+            .collect()
+    }
+
+    /// A setup on the standard generators, like the EIP-4844 trusted setup
+    fn standard_setup(n: usize) -> M1NoPrecomp<ark_bls12_381::Bls12_381> {
+        let powers = gen_powers(Fr::rand(&mut test_rng()), n);
+        M1NoPrecomp {
+            powers_of_g1: gen_curve_powers_with_generator(&powers, G1Projective::generator()),
+            powers_of_g2: gen_curve_powers_with_generator(&powers[..2], G2Projective::generator()),
+        }
+    }
+
+    #[test]
+    fn test_bytes_roundtrip() {
+        let blob = Blob::<16>(Box::new([(); 16].map(|_| Fr::rand(&mut test_rng()))));
+        let bytes = blob.to_bytes();
+        assert_eq!(16 * BYTES_PER_FIELD_ELEMENT, bytes.len());
+        assert_eq!(Ok(blob), Blob::<16>::from_bytes(&bytes));
+
+        let mut small = vec![0u8; 16 * BYTES_PER_FIELD_ELEMENT];
+        small[31] = 7;
+        assert_eq!(Fr::from(7u64), Blob::<16>::from_bytes(&small).unwrap().0[0]);
+
+        // The modulus itself isn't canonical
+        let mut bad = bytes.clone();
+        bad[32..64].copy_from_slice(&from_hex(
+            "73eda753299d7d483339d80809a1d80553bda402fffe5bfeffffffff00000001",
+        ));
+        assert_eq!(
+            Err(Error::NonCanonicalScalar { index: 1 }),
+            Blob::<16>::from_bytes(&bad)
+        );
+        assert!(Blob::<16>::from_bytes(&bytes[1..]).is_err());
+    }
+
+    #[test]
+    fn test_known_commitments() {
+        let s = standard_setup(4096);
+        let zero = Eip4844Blob::from_bytes(&vec![0u8; 4096 * BYTES_PER_FIELD_ELEMENT]).unwrap();
+        let mut expected = [0u8; 48];
+        expected[0] = 0xc0;
+        assert_eq!(expected, zero.commit(&s).unwrap());
+
+        // A constant blob is the constant polynomial, so commits to the generator
+        let ones = Eip4844Blob(Box::new([Fr::one(); 4096]));
+        assert_eq!(
+            from_hex(
+                "97f1d3a73197d7942695638c4fa9ac0fc3688c4f9774b905a14e3a3f171bac586c55e83ff97a1aeffb3af00adb22c6bb"
+            ),
+            ones.commit(&s).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_bit_reversed_order() {
+        use crate::traits::Committer;
+        use ark_poly::{EvaluationDomain, GeneralEvaluationDomain};
+        let s = standard_setup(16);
+        let domain = GeneralEvaluationDomain::<Fr>::new(16).unwrap();
+        let coeffs = (0..16)
+            .map(|_| Fr::rand(&mut test_rng()))
+            .collect::<Vec<_>>();
+        let natural = domain.fft(&coeffs);
+        let blob = Blob::<16>(Box::new(core::array::from_fn(|i| {
+            natural[super::bit_reverse(i, 16)]
+        })));
+        assert_eq!(natural, blob.evals_natural());
+        assert_eq!(
+            super::compress_g1(&s.commit(&coeffs).unwrap().0),
+            blob.commit(&s).unwrap()
+        );
+    }
+}
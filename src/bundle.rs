@@ -0,0 +1,146 @@
+//! Everything a verifier needs for one opening in a single serializable object, so the
+//! commitments, points, evals and proof travel together and can't be mismatched or reordered on
+//! the way from `open` to `verify`.
+
+use ark_ec::pairing::Pairing;
+use ark_serialize::{
+    CanonicalDeserialize, CanonicalSerialize, Compress, Read, SerializationError, Valid, Validate,
+    Write,
+};
+use merlin::Transcript;
+
+use crate::{traits::PolyMultiProofNoPrecomp, Commitment, Error};
+
+/// Everything a verifier needs to check an opening, serializable as one blob
+pub struct VerifyBundle<E: Pairing> {
+    /// The commitments to the opened polynomials
+    pub commits: Vec<Commitment<E>>,
+    /// The points the polynomials were opened at
+    pub points: Vec<E::ScalarField>,
+    /// The evals of each committed polynomial at `points`, in the same order as `commits`
+    pub evals: Vec<Vec<E::ScalarField>>,
+    /// The compressed serialization of the proof
+    pub proof: Vec<u8>,
+}
+
+impl<E: Pairing> VerifyBundle<E> {
+    /// Bundles an opening, serializing `proof` in compressed form
+    pub fn new(
+        commits: Vec<Commitment<E>>,
+        points: Vec<E::ScalarField>,
+        evals: Vec<Vec<E::ScalarField>>,
+        proof: &impl CanonicalSerialize,
+    ) -> Result<Self, Error> {
+        let mut proof_bytes = Vec::new();
+        proof.serialize_compressed(&mut proof_bytes)?;
+        Ok(Self {
+            commits,
+            points,
+            evals,
+            proof: proof_bytes,
+        })
+    }
+
+    /// Deserializes the proof, checking it's a valid point, and verifies it against the rest of
+    /// the bundle with `verifier`
+    pub fn verify<V>(&self, verifier: &V, transcript: &mut Transcript) -> Result<bool, Error>
+    where
+        V: PolyMultiProofNoPrecomp<E>,
+        V::Proof: CanonicalDeserialize,
+    {
+        let proof = V::Proof::deserialize_compressed(self.proof.as_slice())?;
+        verifier.verify(transcript, &self.commits, &self.points, &self.evals, &proof)
+    }
+}
+
+impl<E: Pairing> CanonicalSerialize for VerifyBundle<E> {
+    fn serialize_with_mode<W: Write>(
+        &self,
+        mut writer: W,
+        compress: Compress,
+    ) -> Result<(), SerializationError> {
+        self.commits.serialize_with_mode(&mut writer, compress)?;
+        self.points.serialize_with_mode(&mut writer, compress)?;
+        self.evals.serialize_with_mode(&mut writer, compress)?;
+        self.proof.serialize_with_mode(&mut writer, compress)
+    }
+
+    fn serialized_size(&self, compress: Compress) -> usize {
+        self.commits.serialized_size(compress)
+            + self.points.serialized_size(compress)
+            + self.evals.serialized_size(compress)
+            + self.proof.serialized_size(compress)
+    }
+}
+
+impl<E: Pairing> Valid for VerifyBundle<E> {
+    fn check(&self) -> Result<(), SerializationError> {
+        Commitment::<E>::batch_check(self.commits.iter())
+    }
+}
+
+impl<E: Pairing> CanonicalDeserialize for VerifyBundle<E> {
+    fn deserialize_with_mode<R: Read>(
+        mut reader: R,
+        compress: Compress,
+        validate: Validate,
+    ) -> Result<Self, SerializationError> {
+        Ok(Self {
+            commits: Vec::deserialize_with_mode(&mut reader, compress, validate)?,
+            points: Vec::deserialize_with_mode(&mut reader, compress, validate)?,
+            evals: Vec::deserialize_with_mode(&mut reader, compress, validate)?,
+            proof: Vec::deserialize_with_mode(&mut reader, compress, validate)?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::VerifyBundle;
+    use crate::{
+        method1::M1NoPrecomp,
+        method2::M2NoPrecomp,
+        test_rng,
+        traits::{Committer, PolyMultiProofNoPrecomp},
+    };
+    use ark_bls12_381::{Bls12_381, Fr};
+    use ark_poly::{univariate::DensePolynomial, DenseUVPolynomial, Polynomial};
+    use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+    use ark_std::UniformRand;
+    use merlin::Transcript;
+
+    #[test]
+    fn test_bundle_roundtrip() {
+        let s = M1NoPrecomp::<Bls12_381>::new(64, 8.into(), &mut test_rng()).unwrap();
+        let points = (0..8)
+            .map(|_| Fr::rand(&mut test_rng()))
+            .collect::<Vec<_>>();
+        let polys = (0..3)
+            .map(|_| DensePolynomial::<Fr>::rand(40, &mut test_rng()))
+            .collect::<Vec<_>>();
+        let evals: Vec<Vec<_>> = polys
+            .iter()
+            .map(|p| points.iter().map(|x| p.evaluate(x)).collect())
+            .collect();
+        let coeffs = polys.iter().map(|p| p.coeffs.clone()).collect::<Vec<_>>();
+        let commits = s.commit_many(&coeffs).unwrap();
+        let open = s
+            .open(&mut Transcript::new(b"testing"), &evals, &coeffs, &points)
+            .unwrap();
+        let bundle = VerifyBundle::new(commits, points, evals, &open).unwrap();
+
+        let mut bytes = Vec::new();
+        bundle.serialize_compressed(&mut bytes).unwrap();
+        assert_eq!(bundle.compressed_size(), bytes.len());
+        let mut read = VerifyBundle::<Bls12_381>::deserialize_compressed(&bytes[..]).unwrap();
+        assert_eq!(Ok(true), read.verify(&s, &mut Transcript::new(b"testing")));
+
+        // A proof for the other method doesn't even deserialize
+        let m2 = M2NoPrecomp::<Bls12_381>::new(64, 8.into(), &mut test_rng()).unwrap();
+        assert!(read.verify(&m2, &mut Transcript::new(b"testing")).is_err());
+
+        read.evals[1][2] += Fr::from(1u64);
+        assert_eq!(Ok(false), read.verify(&s, &mut Transcript::new(b"testing")));
+        assert!(VerifyBundle::<Bls12_381>::deserialize_compressed(&bytes[1..]).is_err());
+    }
+}
@@ -0,0 +1,161 @@
+//! **Test only. This destroys soundness.**
+//!
+//! Fixed challenges for producing test vectors and comparing proofs byte for byte against another
+//! implementation that fixes its challenges the same way. A prover who knows the challenges in
+//! advance can forge proofs for any evals, so nothing produced under [`DeterministicTranscript`]
+//! proves anything. The module, and the override in `get_challenge`, only exist with the
+//! `insecure-deterministic-transcript` feature, which must only ever be enabled by test vector
+//! generators and never in anything that verifies proofs.
+
+use std::cell::RefCell;
+
+use ark_ff::PrimeField;
+
+thread_local! {
+    static FIXED: RefCell<Option<Vec<(&'static [u8], u64)>>> = RefCell::new(None);
+}
+
+/// Replaces the challenges drawn from the transcript with fixed values while running a closure.
+/// Challenges whose label has no fixed value are still drawn from the transcript.
+///
+/// The override only applies to the thread calling [`DeterministicTranscript::run`].
+#[derive(Clone, Debug)]
+pub struct DeterministicTranscript {
+    challenges: Vec<(&'static [u8], u64)>,
+}
+
+impl Default for DeterministicTranscript {
+    /// `gamma = 2` and `z = 3`
+    fn default() -> Self {
+        Self::new(2, 3)
+    }
+}
+
+impl DeterministicTranscript {
+    /// Fixes every gamma challenge the crate draws to `gamma` and every evaluation point
+    /// challenge to `z`
+    pub fn new(gamma: u64, z: u64) -> Self {
+        Self {
+            challenges: vec![
+                (b"open gamma", gamma),
+                (b"open row gamma", gamma),
+                (b"open z", z),
+                (b"equality z", z),
+            ],
+        }
+    }
+
+    /// Fixes the challenge drawn with `label` to `value`
+    pub fn with_challenge(mut self, label: &'static [u8], value: u64) -> Self {
+        match self.challenges.iter_mut().find(|(l, _)| *l == label) {
+            Some(c) => c.1 = value,
+            None => self.challenges.push((label, value)),
+        }
+        self
+    }
+
+    /// Runs `f` with the challenges fixed, restoring whatever was fixed before afterwards, even
+    /// if `f` panics
+    pub fn run<T>(&self, f: impl FnOnce() -> T) -> T {
+        struct Restore(Option<Vec<(&'static [u8], u64)>>);
+        impl Drop for Restore {
+            fn drop(&mut self) {
+                FIXED.with(|fixed| *fixed.borrow_mut() = self.0.take());
+            }
+        }
+
+        let _restore = Restore(FIXED.with(|fixed| fixed.replace(Some(self.challenges.clone()))));
+        f()
+    }
+}
+
+/// The fixed challenge for `label`, if one is being run
+pub(crate) fn fixed_challenge<F: PrimeField>(label: &[u8]) -> Option<F> {
+    FIXED.with(|fixed| {
+        fixed
+            .borrow()
+            .as_ref()?
+            .iter()
+            .find(|(l, _)| *l == label)
+            .map(|(_, v)| F::from(*v))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::DeterministicTranscript;
+    use crate::{
+        method1::M1NoPrecomp,
+        method2::M2NoPrecomp,
+        test_rng,
+        traits::{Committer, PolyMultiProofNoPrecomp},
+    };
+    use ark_bls12_381::{Bls12_381, Fr};
+    use ark_poly::{univariate::DensePolynomial, DenseUVPolynomial, Polynomial};
+    use ark_serialize::CanonicalSerialize;
+    use ark_std::UniformRand;
+    use merlin::Transcript;
+
+    #[test]
+    fn test_fixed_challenges() {
+        let m1 = M1NoPrecomp::<Bls12_381>::new(64, 8.into(), &mut test_rng()).unwrap();
+        let m2 = M2NoPrecomp::<Bls12_381>::new(64, 8.into(), &mut test_rng()).unwrap();
+        let points = (0..8)
+            .map(|_| Fr::rand(&mut test_rng()))
+            .collect::<Vec<_>>();
+        let polys = (0..3)
+            .map(|_| DensePolynomial::<Fr>::rand(40, &mut test_rng()))
+            .collect::<Vec<_>>();
+        let evals: Vec<Vec<_>> = polys
+            .iter()
+            .map(|p| points.iter().map(|x| p.evaluate(x)).collect())
+            .collect();
+        let coeffs = polys.iter().map(|p| p.coeffs.clone()).collect::<Vec<_>>();
+        let commits = m1.commit_many(&coeffs).unwrap();
+
+        let proof_bytes = |label: &'static [u8]| {
+            let mut bytes = Vec::new();
+            m1.open(&mut Transcript::new(label), &evals, &coeffs, &points)
+                .unwrap()
+                .serialize_compressed(&mut bytes)
+                .unwrap();
+            m2.open(&mut Transcript::new(label), &evals, &coeffs, &points)
+                .unwrap()
+                .serialize_compressed(&mut bytes)
+                .unwrap();
+            bytes
+        };
+        assert_ne!(proof_bytes(b"a"), proof_bytes(b"b"));
+
+        let fixed = DeterministicTranscript::default();
+        let (a, b) = fixed.run(|| (proof_bytes(b"a"), proof_bytes(b"b")));
+        assert_eq!(a, b);
+        // The override is gone after running
+        assert_ne!(proof_bytes(b"a"), proof_bytes(b"b"));
+
+        // Proofs made under the fixed challenges still verify under them, whatever the transcript
+        let open = fixed.run(|| {
+            m1.open(&mut Transcript::new(b"a"), &evals, &coeffs, &points)
+                .unwrap()
+        });
+        assert_eq!(
+            Ok(true),
+            fixed.run(|| m1.verify(&mut Transcript::new(b"b"), &commits, &points, &evals, &open))
+        );
+    }
+
+    #[test]
+    fn test_with_challenge() {
+        let label: &[u8] = b"open gamma";
+        let fixed = DeterministicTranscript::default().with_challenge(b"open gamma", 5);
+        assert_eq!(
+            Some(Fr::from(5u64)),
+            fixed.run(|| super::fixed_challenge::<Fr>(label))
+        );
+        assert_eq!(
+            Some(Fr::from(3u64)),
+            fixed.run(|| super::fixed_challenge::<Fr>(b"open z"))
+        );
+        assert_eq!(None, super::fixed_challenge::<Fr>(label));
+    }
+}
@@ -0,0 +1,160 @@
+//! Proving that two commitments, under two different setups, are to the same polynomial. This is
+//! useful when migrating committed data from one setup to another.
+//!
+//! Both commitments are absorbed into the transcript, and a challenge point $z$ is squeezed.
+//! The polynomial is opened at $z$ under each setup, and the verifier checks that both openings
+//! are valid for the same evaluation. Since $z$ is chosen after the commitments are fixed, two
+//! different polynomials of degree at most $d$ agree at $z$ with probability at most $d / |F|$.
+
+use ark_ec::pairing::Pairing;
+use ark_poly::{univariate::DensePolynomial, DenseUVPolynomial, Polynomial};
+use merlin::Transcript;
+
+use crate::{
+    get_challenge, get_field_size,
+    traits::{Committer, PolyMultiProofNoPrecomp},
+    transcribe_bound_commits, Commitment, Error,
+};
+
+/// An opening of two commitments to the same evaluation at a challenge point
+#[derive(Clone, Debug)]
+pub struct EqualityProof<E: Pairing, PA, PB> {
+    pub eval: E::ScalarField,
+    pub proof_a: PA,
+    pub proof_b: PB,
+}
+
+/// Commits to `poly` under both `a` and `b`, returning both commitments and a proof that they
+/// are to the same polynomial
+pub fn prove_equal<E, A, B>(
+    transcript: &mut Transcript,
+    a: &A,
+    b: &B,
+    poly: &[E::ScalarField],
+) -> Result<
+    (
+        Commitment<E>,
+        Commitment<E>,
+        EqualityProof<E, A::Proof, B::Proof>,
+    ),
+    Error,
+>
+where
+    E: Pairing,
+    A: PolyMultiProofNoPrecomp<E> + Committer<E>,
+    B: PolyMultiProofNoPrecomp<E> + Committer<E>,
+{
+    let commit_a = a.commit(poly)?;
+    let commit_b = b.commit(poly)?;
+    let z = equality_challenge(transcript, &commit_a, &commit_b)?;
+
+    let eval = DensePolynomial::from_coefficients_slice(poly).evaluate(&z);
+    let evals = [[eval]];
+    let proof_a = a.open(transcript, &evals, &[poly], &[z])?;
+    let proof_b = b.open(transcript, &evals, &[poly], &[z])?;
+    Ok((
+        commit_a,
+        commit_b,
+        EqualityProof {
+            eval,
+            proof_a,
+            proof_b,
+        },
+    ))
+}
+
+/// Verifies that `commit_a` under `a` and `commit_b` under `b` are to the same polynomial
+pub fn verify_equal<E, A, B>(
+    transcript: &mut Transcript,
+    a: &A,
+    b: &B,
+    commit_a: &Commitment<E>,
+    commit_b: &Commitment<E>,
+    proof: &EqualityProof<E, A::Proof, B::Proof>,
+) -> Result<bool, Error>
+where
+    E: Pairing,
+    A: PolyMultiProofNoPrecomp<E>,
+    B: PolyMultiProofNoPrecomp<E>,
+{
+    let z = equality_challenge(transcript, commit_a, commit_b)?;
+    let evals = [[proof.eval]];
+    let valid_a = a.verify(
+        transcript,
+        std::slice::from_ref(commit_a),
+        &[z],
+        &evals,
+        &proof.proof_a,
+    )?;
+    let valid_b = b.verify(
+        transcript,
+        std::slice::from_ref(commit_b),
+        &[z],
+        &evals,
+        &proof.proof_b,
+    )?;
+    Ok(valid_a && valid_b)
+}
+
+fn equality_challenge<E: Pairing>(
+    transcript: &mut Transcript,
+    commit_a: &Commitment<E>,
+    commit_b: &Commitment<E>,
+) -> Result<E::ScalarField, Error> {
+    transcribe_bound_commits(
+        transcript,
+        &[Commitment::<E>(commit_a.0), Commitment(commit_b.0)],
+    )?;
+    let field_size_bytes = get_field_size::<E::ScalarField>();
+    Ok(get_challenge(transcript, b"equality z", field_size_bytes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{prove_equal, verify_equal};
+    use crate::{method1::M1NoPrecomp, test_rng, traits::PolyMultiProofNoPrecomp};
+    use ark_bls12_381::{Bls12_381, Fr};
+    use ark_std::UniformRand;
+    use merlin::Transcript;
+
+    #[test]
+    fn test_equality_across_setups() {
+        let a = M1NoPrecomp::<Bls12_381>::new(32, 1.into(), &mut test_rng()).unwrap();
+        let b = M1NoPrecomp::<Bls12_381>::new(32, 1.into(), &mut test_rng()).unwrap();
+        let poly = (0..32)
+            .map(|_| Fr::rand(&mut test_rng()))
+            .collect::<Vec<_>>();
+        let other = (0..32)
+            .map(|_| Fr::rand(&mut test_rng()))
+            .collect::<Vec<_>>();
+
+        let (commit_a, commit_b, proof) =
+            prove_equal(&mut Transcript::new(b"testing"), &a, &b, &poly).unwrap();
+        assert_ne!(commit_a.0, commit_b.0);
+        assert_eq!(
+            Ok(true),
+            verify_equal(
+                &mut Transcript::new(b"testing"),
+                &a,
+                &b,
+                &commit_a,
+                &commit_b,
+                &proof
+            )
+        );
+
+        let (_, other_b, _) =
+            prove_equal(&mut Transcript::new(b"testing"), &a, &b, &other).unwrap();
+        assert_eq!(
+            Ok(false),
+            verify_equal(
+                &mut Transcript::new(b"testing"),
+                &a,
+                &b,
+                &commit_a,
+                &other_b,
+                &proof
+            )
+        );
+    }
+}
@@ -0,0 +1,939 @@
+//! Helpers for data availability grids, where each row is committed to and the row commitments
+//! are erasure coded along with the rows. The row commitments can also be merkleized into a
+//! single root, with inclusion proofs for each row.
+
+use std::{marker::PhantomData, ops::Range};
+
+use ark_ec::{pairing::Pairing, AffineRepr, CurveGroup};
+use ark_ff::{FftField, Field, PrimeField, Zero};
+use ark_poly::{EvaluationDomain, Radix2EvaluationDomain};
+use ark_serialize::CanonicalSerialize;
+use ark_std::{rand::RngCore, UniformRand};
+use merlin::Transcript;
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+use crate::{
+    gen_powers, get_field_size,
+    traits::{Committer, PolyMultiProof},
+    Commitment, Error,
+};
+
+/// Given commitments to the rows $f_0, \ldots, f_{n-1}$ of a grid, returns the $2n$ commitments to
+/// the rows of the column-wise erasure extended grid, so the original commitments end up at the
+/// even indices.
+///
+/// This is an ifft over the size $n$ domain followed by an fft over the size $2n$ domain, done
+/// directly on the group elements. When `commits.len()` isn't a power of two, it's padded with
+/// [`Commitment::zero`], i.e. zero rows, first. With the `parallel` feature the butterflies of
+/// each fft layer are run on separate threads.
+pub fn extend_commitments<E: Pairing>(
+    commits: &[Commitment<E>],
+) -> Result<Vec<Commitment<E>>, Error> {
+    extend_commitments_projective::<E>(commits.iter().map(|c| c.0.into_group()).collect())
+}
+
+/// Same as [`extend_commitments`], but takes the commitments as projective points, such as from
+/// [`Committer::commit_projective`](crate::traits::Committer::commit_projective), so they're only
+/// normalized once after extending
+pub fn extend_commitments_projective<E: Pairing>(
+    mut points: Vec<E::G1>,
+) -> Result<Vec<Commitment<E>>, Error> {
+    let n = points.len();
+    let too_large = || Error::PolynomialTooLarge {
+        n_coeffs: n,
+        expected_max: 1 << (<E::ScalarField as FftField>::TWO_ADICITY - 1),
+    };
+    let domain_h = Radix2EvaluationDomain::<E::ScalarField>::new(n).ok_or_else(too_large)?;
+    let domain_2h =
+        Radix2EvaluationDomain::<E::ScalarField>::new(2 * domain_h.size()).ok_or_else(too_large)?;
+
+    points.resize(domain_h.size(), E::G1::zero());
+    group_ifft_in_place(&domain_h, &mut points);
+    points.resize(domain_2h.size(), E::G1::zero());
+    group_fft_in_place(&domain_2h, &mut points);
+
+    Ok(E::G1::normalize_batch(&points)
+        .into_iter()
+        .map(Commitment)
+        .collect())
+}
+
+/// Erasure extends the grid with rows `rows` column-wise, returning the $2n$ rows whose columns
+/// are the evaluations over the size $2n$ domain of the interpolations of the original columns, so
+/// the original rows end up at the even indices. This is the scalar counterpart of
+/// [`extend_commitments`], with rows padded the same way when `rows.len()` isn't a power of two.
+///
+/// Every column is independent, so with the `parallel` feature the columns are extended on
+/// separate threads, after transposing the grid so each thread owns a whole column.
+pub fn extend_rows<F: FftField>(rows: &[impl AsRef<[F]> + Sync]) -> Result<Vec<Vec<F>>, Error> {
+    let width = rows
+        .first()
+        .ok_or(Error::NoPolynomialsGiven)?
+        .as_ref()
+        .len();
+    if let Some((poly, r)) = rows
+        .iter()
+        .enumerate()
+        .find(|(_, r)| r.as_ref().len() != width)
+    {
+        return Err(Error::EvalsIncorrectSize {
+            poly,
+            n: r.as_ref().len(),
+            expected: width,
+        });
+    }
+    let too_large = || Error::PolynomialTooLarge {
+        n_coeffs: rows.len(),
+        expected_max: 1 << (F::TWO_ADICITY - 1),
+    };
+    let domain_h = Radix2EvaluationDomain::<F>::new(rows.len()).ok_or_else(too_large)?;
+    let domain_2h = Radix2EvaluationDomain::<F>::new(2 * domain_h.size()).ok_or_else(too_large)?;
+
+    let column = |j: usize| {
+        let mut col = Vec::with_capacity(domain_2h.size());
+        col.extend(rows.iter().map(|r| r.as_ref()[j]));
+        col.resize(domain_h.size(), F::zero());
+        domain_h.ifft_in_place(&mut col);
+        domain_2h.fft_in_place(&mut col);
+        col
+    };
+    #[cfg(feature = "parallel")]
+    let cols = (0..width).into_par_iter().map(column).collect::<Vec<_>>();
+    #[cfg(not(feature = "parallel"))]
+    let cols = (0..width).map(column).collect::<Vec<_>>();
+
+    let row = |i: usize| cols.iter().map(|c| c[i]).collect::<Vec<_>>();
+    #[cfg(feature = "parallel")]
+    let extended = (0..domain_2h.size()).into_par_iter().map(row).collect();
+    #[cfg(not(feature = "parallel"))]
+    let extended = (0..domain_2h.size()).map(row).collect();
+    Ok(extended)
+}
+
+/// Checks that `extended` is the erasure extension of `original` by a factor of `expansion`,
+/// i.e. the evaluations over the domain of size `expansion` times the padded `original.len()` of
+/// the group polynomial of degree below the padded length whose evaluations over the smaller
+/// domain are `original`, padded with [`Commitment::zero`]. For an `expansion` of 2 this is the
+/// output of [`extend_commitments`].
+///
+/// Only the commitments are needed, and no pairing. With $n$ the padded length and $N$ the
+/// extended one, the $N$ commitments are the coefficients $c_0, \ldots, c_{N-1}$ of a polynomial
+/// after an ifft, which is low degree iff $c_k = 0$ for every $k \geq n$. For random $\lambda_k$,
+/// $\sum_{k \geq n} \lambda_k c_k$ is a single msm over the commitments with the ifft of the
+/// $\lambda_k$ as scalars, and is folded together with random multiples of
+/// `extended[expansion * i] - original[i]`. If the extension is invalid, the combination is a
+/// nonzero linear form in the random scalars, so it's the identity with probability at most
+/// $1 / |\mathbb{F}|$.
+pub fn verify_commitment_extension<E: Pairing>(
+    original: &[Commitment<E>],
+    extended: &[Commitment<E>],
+    expansion: usize,
+    rng: &mut impl RngCore,
+) -> Result<bool, Error> {
+    if original.is_empty() {
+        return Err(Error::NoPolynomialsGiven);
+    }
+    let invalid_expansion = || Error::InvalidExpansionFactor { expansion };
+    if !expansion.is_power_of_two() {
+        return Err(invalid_expansion());
+    }
+    let n = original.len().next_power_of_two();
+    let n_extended = n.checked_mul(expansion).ok_or_else(invalid_expansion)?;
+    let domain_ext =
+        Radix2EvaluationDomain::<E::ScalarField>::new(n_extended).ok_or_else(invalid_expansion)?;
+    if extended.len() != n_extended {
+        return Err(Error::CommitsIncorrectSize {
+            n: extended.len(),
+            expected: n_extended,
+        });
+    }
+
+    let mut scalars = vec![E::ScalarField::zero(); n_extended];
+    scalars[n..]
+        .iter_mut()
+        .for_each(|l| *l = E::ScalarField::rand(rng));
+    domain_ext.ifft_in_place(&mut scalars);
+    let mut bases = extended.iter().map(|c| c.0).collect::<Vec<_>>();
+    for i in 0..n {
+        let rho = E::ScalarField::rand(rng);
+        scalars[expansion * i] += rho;
+        // The padding rows are zero, so only the given ones need subtracting
+        if let Some(c) = original.get(i) {
+            bases.push(c.0);
+            scalars.push(-rho);
+        }
+    }
+    Ok(crate::curve_msm::<E::G1>(&bases, &scalars)?.is_zero())
+}
+
+fn group_ifft_in_place<G: CurveGroup>(
+    domain: &Radix2EvaluationDomain<G::ScalarField>,
+    a: &mut [G],
+) {
+    radix2_group_fft(a, domain.group_gen_inv);
+    let size_inv = domain.size_inv;
+    #[cfg(feature = "parallel")]
+    a.par_iter_mut().for_each(|x| *x *= size_inv);
+    #[cfg(not(feature = "parallel"))]
+    a.iter_mut().for_each(|x| *x *= size_inv);
+}
+
+fn group_fft_in_place<G: CurveGroup>(domain: &Radix2EvaluationDomain<G::ScalarField>, a: &mut [G]) {
+    radix2_group_fft(a, domain.group_gen);
+}
+
+/// Iterative decimation-in-time fft, `a.len()` must be a power of two with `omega` a primitive
+/// root of unity of that order
+fn radix2_group_fft<G: CurveGroup>(a: &mut [G], omega: G::ScalarField) {
+    let n = a.len();
+    debug_assert!(n.is_power_of_two());
+    if n > 1 {
+        let shift = usize::BITS - n.trailing_zeros();
+        for i in 0..n {
+            let j = i.reverse_bits() >> shift;
+            if i < j {
+                a.swap(i, j);
+            }
+        }
+    }
+
+    let mut m = 1;
+    while m < n {
+        let w_m = omega.pow([(n / (2 * m)) as u64]);
+        let twiddles = gen_powers(w_m, m);
+        // Every butterfly within a layer is independent
+        #[cfg(feature = "parallel")]
+        a.par_chunks_mut(2 * m).for_each(|chunk| {
+            let (lo, hi) = chunk.split_at_mut(m);
+            lo.par_iter_mut()
+                .zip(hi.par_iter_mut())
+                .zip(twiddles.par_iter())
+                .for_each(|((x, y), w)| butterfly(x, y, w));
+        });
+        #[cfg(not(feature = "parallel"))]
+        a.chunks_mut(2 * m).for_each(|chunk| {
+            let (lo, hi) = chunk.split_at_mut(m);
+            lo.iter_mut()
+                .zip(hi.iter_mut())
+                .zip(twiddles.iter())
+                .for_each(|((x, y), w)| butterfly(x, y, w));
+        });
+        m *= 2;
+    }
+}
+
+fn butterfly<G: CurveGroup>(x: &mut G, y: &mut G, w: &G::ScalarField) {
+    let t = *y * w;
+    *y = *x - t;
+    *x += t;
+}
+
+/// The hash used to merkleize row commitments. Leaves and inner nodes are hashed separately so
+/// that a leaf can't be passed off as a node.
+pub trait RowHasher {
+    fn hash_leaf(&self, data: &[u8]) -> [u8; 32];
+    fn hash_node(&self, left: &[u8; 32], right: &[u8; 32]) -> [u8; 32];
+}
+
+/// A [`RowHasher`] built on a merlin transcript, for when no other hash is mandated
+#[derive(Clone, Copy, Debug, Default)]
+pub struct TranscriptHasher;
+
+impl RowHasher for TranscriptHasher {
+    fn hash_leaf(&self, data: &[u8]) -> [u8; 32] {
+        let mut t = Transcript::new(b"pmp merkle leaf");
+        t.append_message(b"data", data);
+        let mut out = [0u8; 32];
+        t.challenge_bytes(b"digest", &mut out);
+        out
+    }
+
+    fn hash_node(&self, left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
+        let mut t = Transcript::new(b"pmp merkle node");
+        t.append_message(b"left", left);
+        t.append_message(b"right", right);
+        let mut out = [0u8; 32];
+        t.challenge_bytes(b"digest", &mut out);
+        out
+    }
+}
+
+/// A merkle tree over the compressed row commitments of a grid, so a light client holding only
+/// the root can check that a row commitment belongs to the grid. The leaves are padded with zero
+/// digests up to a power of two.
+#[derive(Clone, Debug)]
+pub struct CommitmentTree {
+    /// Each layer of digests, from the leaves up to the root
+    layers: Vec<Vec<[u8; 32]>>,
+    /// The number of rows, the leaves past it are padding
+    n_rows: usize,
+}
+
+/// The sibling digests on the path from a row's leaf to the root
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct InclusionProof {
+    pub index: usize,
+    pub siblings: Vec<[u8; 32]>,
+}
+
+impl CommitmentTree {
+    pub fn new<E: Pairing>(
+        commits: &[Commitment<E>],
+        hasher: &impl RowHasher,
+    ) -> Result<Self, Error> {
+        if commits.is_empty() {
+            return Err(Error::NoPolynomialsGiven);
+        }
+        let mut leaves = commits
+            .iter()
+            .map(|c| commitment_leaf(c, hasher))
+            .collect::<Result<Vec<_>, Error>>()?;
+        leaves.resize(commits.len().next_power_of_two(), [0u8; 32]);
+
+        let mut layers = vec![leaves];
+        while layers[layers.len() - 1].len() > 1 {
+            let next = layers[layers.len() - 1]
+                .chunks(2)
+                .map(|pair| hasher.hash_node(&pair[0], &pair[1]))
+                .collect();
+            layers.push(next);
+        }
+        Ok(Self {
+            layers,
+            n_rows: commits.len(),
+        })
+    }
+
+    pub fn root(&self) -> [u8; 32] {
+        self.layers[self.layers.len() - 1][0]
+    }
+
+    /// Proves that the commitment at row `index` is in the tree, or `None` if it is out of range.
+    /// The padding leaves aren't rows, so there are no proofs for them.
+    pub fn prove(&self, index: usize) -> Option<InclusionProof> {
+        if index >= self.n_rows {
+            return None;
+        }
+        let siblings = self.layers[..self.layers.len() - 1]
+            .iter()
+            .enumerate()
+            .map(|(depth, layer)| layer[(index >> depth) ^ 1])
+            .collect();
+        Some(InclusionProof { index, siblings })
+    }
+}
+
+/// Checks that `commit` is the row at `proof.index` of the tree with root `root`
+pub fn verify_inclusion<E: Pairing>(
+    root: &[u8; 32],
+    commit: &Commitment<E>,
+    proof: &InclusionProof,
+    hasher: &impl RowHasher,
+) -> Result<bool, Error> {
+    let mut digest = commitment_leaf(commit, hasher)?;
+    for (depth, sibling) in proof.siblings.iter().enumerate() {
+        digest = if (proof.index >> depth) & 1 == 0 {
+            hasher.hash_node(&digest, sibling)
+        } else {
+            hasher.hash_node(sibling, &digest)
+        };
+    }
+    // Any index bits above the tree height would name a different row
+    let height = u32::try_from(proof.siblings.len()).unwrap_or(u32::MAX);
+    Ok(proof.index.checked_shr(height).unwrap_or(0) == 0 && &digest == root)
+}
+
+/// Checks that `commit` is row `row` of the tree with root `root`, then that `proof` opens it to
+/// `evals` at point set `point_set_index`. This takes a light client from a published root to a
+/// valid cell in one call. Both checks must pass, and the opening isn't checked if the inclusion
+/// fails. An inclusion proof for any other row is `Ok(false)`, even if it's valid.
+#[allow(clippy::too_many_arguments)]
+pub fn verify_with_inclusion<E: Pairing, P: PolyMultiProof<E>>(
+    pmp: &P,
+    transcript: &mut Transcript,
+    root: &[u8; 32],
+    inclusion: &InclusionProof,
+    hasher: &impl RowHasher,
+    commit: &Commitment<E>,
+    row: usize,
+    point_set_index: usize,
+    evals: &[E::ScalarField],
+    proof: &P::Proof,
+) -> Result<bool, Error> {
+    if inclusion.index != row || !verify_inclusion(root, commit, inclusion, hasher)? {
+        return Ok(false);
+    }
+    pmp.verify(
+        transcript,
+        std::slice::from_ref(commit),
+        point_set_index,
+        &[evals],
+        proof,
+    )
+}
+
+/// Packs `data` into scalars a chunk at a time, big endian, with chunks one byte shorter than a
+/// serialized scalar so each one is below the modulus. The last chunk is zero padded on the right
+/// to a full chunk, so a partial chunk lands in the same bytes of its scalar as a full one.
+pub fn bytes_to_scalars<F: PrimeField>(data: &[u8]) -> Vec<F> {
+    let chunk_size = get_field_size::<F>() - 1;
+    data.chunks(chunk_size)
+        .map(|chunk| {
+            let mut padded = chunk.to_vec();
+            padded.resize(chunk_size, 0);
+            F::from_be_bytes_mod_order(&padded)
+        })
+        .collect()
+}
+
+/// The point sets a [`GridCommitter`] opens cells at: the elements of the size `width` domain,
+/// split in order into sets of `cell_width`. The [`PolyMultiProof`] given to the committer must
+/// be made with these.
+pub fn grid_point_sets<F: FftField>(width: usize, cell_width: usize) -> Result<Vec<Vec<F>>, Error> {
+    let domain = grid_domain::<F>(width, cell_width)?;
+    Ok(domain
+        .elements()
+        .collect::<Vec<_>>()
+        .chunks(cell_width)
+        .map(|c| c.to_vec())
+        .collect())
+}
+
+fn grid_domain<F: FftField>(
+    width: usize,
+    cell_width: usize,
+) -> Result<Radix2EvaluationDomain<F>, Error> {
+    let invalid = Error::InvalidGridWidth { width, cell_width };
+    if cell_width == 0 || width % cell_width != 0 {
+        return Err(invalid);
+    }
+    match Radix2EvaluationDomain::<F>::new(width) {
+        Some(domain) if domain.size() == width => Ok(domain),
+        _ => Err(invalid),
+    }
+}
+
+/// A grid committed to with [`GridCommitter::commit_data`]. Verifiers only need `commits`.
+#[derive(Debug)]
+pub struct GridCommitment<E: Pairing> {
+    /// The commitment to each row of the extended grid
+    pub commits: Vec<Commitment<E>>,
+    /// Each row of the extended grid, as evaluations over the size `width` domain
+    pub rows: Vec<Vec<E::ScalarField>>,
+    /// Each row of the extended grid, as coefficients
+    pub polys: Vec<Vec<E::ScalarField>>,
+}
+
+impl<E: Pairing> GridCommitment<E> {
+    /// The evaluations in the cell at `rows` and `cols`, or `None` if it's outside the grid
+    pub fn cell(&self, rows: Range<usize>, cols: Range<usize>) -> Option<Vec<&[E::ScalarField]>> {
+        self.rows
+            .get(rows)?
+            .iter()
+            .map(|r| r.get(cols.clone()))
+            .collect()
+    }
+}
+
+/// Commits to data too large for a single polynomial, as in the `data_availability_grid`
+/// example: the data is packed with [`bytes_to_scalars`] into the rows of a `width` by `height`
+/// grid, zero padded, and the columns are erasure extended with [`extend_rows`] so the grid has
+/// twice as many rows, rounded up to a power of two. Each row is committed to.
+///
+/// A cell is a range of rows over `cell_width` consecutive columns starting at a multiple of
+/// `cell_width`, opened at the matching point set from [`grid_point_sets`].
+pub struct GridCommitter<'a, E: Pairing, P> {
+    pmp: &'a P,
+    width: usize,
+    height: usize,
+    cell_width: usize,
+    _pairing: PhantomData<E>,
+}
+
+impl<'a, E: Pairing, P: PolyMultiProof<E> + Committer<E>> GridCommitter<'a, E, P> {
+    /// `pmp` must have been made with [`grid_point_sets`] of `width` and `cell_width`, and fit
+    /// polynomials with `width` coefficients
+    pub fn new(pmp: &'a P, width: usize, height: usize, cell_width: usize) -> Result<Self, Error> {
+        grid_domain::<E::ScalarField>(width, cell_width)?;
+        if height == 0 {
+            return Err(Error::NoPolynomialsGiven);
+        }
+        if !pmp.fits(width) {
+            return Err(Error::PolynomialTooLarge {
+                n_coeffs: width,
+                expected_max: pmp.max_coeffs(),
+            });
+        }
+        Ok(Self {
+            pmp,
+            width,
+            height,
+            cell_width,
+            _pairing: PhantomData,
+        })
+    }
+
+    /// The most bytes [`GridCommitter::commit_data`] takes
+    pub fn max_bytes(&self) -> usize {
+        self.width * self.height * (get_field_size::<E::ScalarField>() - 1)
+    }
+
+    /// The number of rows in the extended grid
+    pub fn extended_height(&self) -> usize {
+        2 * self.height.next_power_of_two()
+    }
+
+    /// Lays `data` out in the grid, extends it, and commits to every row. Errors with
+    /// [`Error::PolynomialTooLarge`] if `data` packs into more scalars than the grid holds.
+    pub fn commit_data(&self, data: &[u8]) -> Result<GridCommitment<E>, Error> {
+        let scalars = bytes_to_scalars::<E::ScalarField>(data);
+        if scalars.len() > self.width * self.height {
+            return Err(Error::PolynomialTooLarge {
+                n_coeffs: scalars.len(),
+                expected_max: self.width * self.height,
+            });
+        }
+        let mut rows = scalars
+            .chunks(self.width)
+            .map(|row| {
+                let mut row = row.to_vec();
+                row.resize(self.width, E::ScalarField::zero());
+                row
+            })
+            .collect::<Vec<_>>();
+        rows.resize(self.height, vec![E::ScalarField::zero(); self.width]);
+
+        let rows = extend_rows(&rows)?;
+        let domain = grid_domain::<E::ScalarField>(self.width, self.cell_width)?;
+        let polys = rows.iter().map(|r| domain.ifft(r)).collect::<Vec<_>>();
+        // The original rows are at the even indices, the rest follow from their commitments
+        let original = polys.iter().step_by(2).collect::<Vec<_>>();
+        let commits = extend_commitments(&self.pmp.commit_many(&original)?)?;
+        Ok(GridCommitment {
+            commits,
+            rows,
+            polys,
+        })
+    }
+
+    /// Opens the cell at `rows` and `cols` of `grid`
+    pub fn open_cell(
+        &self,
+        transcript: &mut Transcript,
+        grid: &GridCommitment<E>,
+        rows: Range<usize>,
+        cols: Range<usize>,
+    ) -> Result<P::Proof, Error> {
+        let point_set = self.check_cell(grid.commits.len(), &rows, &cols)?;
+        let evals = grid
+            .cell(rows.clone(), cols)
+            .ok_or(Error::PolyIndexOutOfRange {
+                index: rows.end - 1,
+                n: grid.rows.len(),
+            })?;
+        self.pmp
+            .open(transcript, &evals, &grid.polys[rows], point_set)
+    }
+
+    /// Verifies a proof from [`GridCommitter::open_cell`], where `commits` are the commitments of
+    /// the whole grid and `evals` the cell's evaluations, one row each
+    pub fn verify_cell(
+        &self,
+        transcript: &mut Transcript,
+        commits: &[Commitment<E>],
+        rows: Range<usize>,
+        cols: Range<usize>,
+        evals: &[impl AsRef<[E::ScalarField]>],
+        proof: &P::Proof,
+    ) -> Result<bool, Error> {
+        let point_set = self.check_cell(commits.len(), &rows, &cols)?;
+        self.pmp
+            .verify(transcript, &commits[rows], point_set, evals, proof)
+    }
+
+    /// Checks the cell is inside a grid with `n_commits` rows, returning its point set index
+    fn check_cell(
+        &self,
+        n_commits: usize,
+        rows: &Range<usize>,
+        cols: &Range<usize>,
+    ) -> Result<usize, Error> {
+        let n_rows = self.extended_height();
+        if n_commits != n_rows {
+            return Err(Error::CommitsIncorrectSize {
+                n: n_commits,
+                expected: n_rows,
+            });
+        }
+        if rows.is_empty() {
+            return Err(Error::NoPolynomialsGiven);
+        }
+        if rows.end > n_rows {
+            return Err(Error::PolyIndexOutOfRange {
+                index: rows.end - 1,
+                n: n_rows,
+            });
+        }
+        if cols.start % self.cell_width != 0
+            || cols.end != cols.start + self.cell_width
+            || cols.end > self.width
+        {
+            return Err(Error::CellNotAligned {
+                start: cols.start,
+                end: cols.end,
+            });
+        }
+        Ok(cols.start / self.cell_width)
+    }
+}
+
+fn commitment_leaf<E: Pairing>(
+    commit: &Commitment<E>,
+    hasher: &impl RowHasher,
+) -> Result<[u8; 32], Error> {
+    let mut bytes = Vec::new();
+    commit.serialize_compressed(&mut bytes)?;
+    Ok(hasher.hash_leaf(&bytes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        extend_commitments, extend_rows, verify_inclusion, verify_with_inclusion, CommitmentTree,
+        TranscriptHasher,
+    };
+    use crate::{test_rng, Commitment, Error};
+    use ark_bls12_381::{Bls12_381, Fr, G1Projective};
+    use ark_ec::CurveGroup;
+    use ark_poly::{EvaluationDomain, GeneralEvaluationDomain};
+    use ark_std::{UniformRand, Zero};
+
+    #[test]
+    fn test_matches_sequential_fft() {
+        for n in [1, 2, 16, 12] {
+            let points = (0..n)
+                .map(|_| G1Projective::rand(&mut test_rng()))
+                .collect::<Vec<_>>();
+            let commits = points
+                .iter()
+                .map(|p| Commitment::<Bls12_381>(p.into_affine()))
+                .collect::<Vec<_>>();
+
+            let domain_h = GeneralEvaluationDomain::<Fr>::new(n).unwrap();
+            let domain_2h = GeneralEvaluationDomain::<Fr>::new(2 * domain_h.size()).unwrap();
+            let mut expected = points.clone();
+            expected.resize(domain_h.size(), G1Projective::zero());
+            domain_h.ifft_in_place(&mut expected);
+            domain_2h.fft_in_place(&mut expected);
+
+            let extended = extend_commitments(&commits).unwrap();
+            assert_eq!(expected.len(), extended.len());
+            for (e, c) in expected.iter().zip(extended.iter()) {
+                assert_eq!(e.into_affine(), c.0);
+            }
+            for (i, c) in commits.iter().enumerate() {
+                assert_eq!(c.0, extended[2 * i].0);
+            }
+        }
+    }
+
+    #[test]
+    fn test_extend_rows_matches_sequential() {
+        let width = 5;
+        for n in [1, 4, 6] {
+            let rows = (0..n)
+                .map(|_| (0..width).map(|_| Fr::rand(&mut test_rng())).collect())
+                .collect::<Vec<Vec<_>>>();
+
+            let domain_h = GeneralEvaluationDomain::<Fr>::new(n).unwrap();
+            let domain_2h = GeneralEvaluationDomain::<Fr>::new(2 * domain_h.size()).unwrap();
+            let mut expected = vec![vec![Fr::zero(); width]; domain_2h.size()];
+            for j in 0..width {
+                let mut col = rows.iter().map(|r| r[j]).collect::<Vec<_>>();
+                col.resize(domain_h.size(), Fr::zero());
+                domain_h.ifft_in_place(&mut col);
+                domain_2h.fft_in_place(&mut col);
+                for (i, x) in col.into_iter().enumerate() {
+                    expected[i][j] = x;
+                }
+            }
+
+            let extended = extend_rows(&rows).unwrap();
+            assert_eq!(expected, extended);
+            for (i, r) in rows.iter().enumerate() {
+                assert_eq!(r, &extended[2 * i]);
+            }
+        }
+
+        let ragged = vec![vec![Fr::zero(); 3], vec![Fr::zero(); 2]];
+        assert_eq!(
+            Err(Error::EvalsIncorrectSize {
+                poly: 1,
+                n: 2,
+                expected: 3
+            }),
+            extend_rows(&ragged)
+        );
+        assert_eq!(
+            Err(Error::NoPolynomialsGiven),
+            extend_rows(&Vec::<Vec<Fr>>::new())
+        );
+    }
+
+    #[test]
+    fn test_verify_commitment_extension() {
+        use super::{group_fft_in_place, verify_commitment_extension};
+        use crate::Error;
+        use ark_poly::Radix2EvaluationDomain;
+        let mut rng = test_rng();
+        for n in [1, 5, 8] {
+            let commits = (0..n)
+                .map(|_| Commitment::<Bls12_381>(G1Projective::rand(&mut test_rng()).into_affine()))
+                .collect::<Vec<_>>();
+            let mut extended = extend_commitments(&commits).unwrap();
+            assert_eq!(
+                Ok(true),
+                verify_commitment_extension(&commits, &extended, 2, &mut rng)
+            );
+            let last = extended.len() - 1;
+            extended[last] = Commitment(G1Projective::rand(&mut test_rng()).into_affine());
+            assert_eq!(
+                Ok(false),
+                verify_commitment_extension(&commits, &extended, 2, &mut rng)
+            );
+        }
+
+        // A degree 4 group polynomial evaluated over a domain 4 times as large
+        let mut points = (0..4)
+            .map(|_| G1Projective::rand(&mut test_rng()))
+            .collect::<Vec<_>>();
+        points.resize(16, G1Projective::zero());
+        group_fft_in_place(&Radix2EvaluationDomain::<Fr>::new(16).unwrap(), &mut points);
+        let extended = points
+            .iter()
+            .map(|p| Commitment::<Bls12_381>(p.into_affine()))
+            .collect::<Vec<_>>();
+        let original = extended
+            .iter()
+            .step_by(4)
+            .map(|c| Commitment(c.0))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            Ok(true),
+            verify_commitment_extension(&original, &extended, 4, &mut rng)
+        );
+        assert_eq!(
+            Err(Error::InvalidExpansionFactor { expansion: 3 }),
+            verify_commitment_extension(&original, &extended[..12], 3, &mut rng)
+        );
+        let mut wrong_original = original.iter().map(|c| Commitment(c.0)).collect::<Vec<_>>();
+        wrong_original.swap(0, 1);
+        assert_eq!(
+            Ok(false),
+            verify_commitment_extension(&wrong_original, &extended, 4, &mut rng)
+        );
+        assert!(verify_commitment_extension(&original, &extended[..8], 4, &mut rng).is_err());
+    }
+
+    #[test]
+    fn test_inclusion_proofs() {
+        let commits = (0..5)
+            .map(|_| Commitment::<Bls12_381>(G1Projective::rand(&mut test_rng()).into_affine()))
+            .collect::<Vec<_>>();
+        let tree = CommitmentTree::new(&commits, &TranscriptHasher).unwrap();
+        let root = tree.root();
+        for (i, c) in commits.iter().enumerate() {
+            let proof = tree.prove(i).unwrap();
+            assert_eq!(3, proof.siblings.len());
+            assert_eq!(
+                Ok(true),
+                verify_inclusion(&root, c, &proof, &TranscriptHasher)
+            );
+            // Wrong row
+            let wrong = &commits[(i + 1) % commits.len()];
+            assert_eq!(
+                Ok(false),
+                verify_inclusion(&root, wrong, &proof, &TranscriptHasher)
+            );
+        }
+        let mut proof = tree.prove(2).unwrap();
+        proof.index = 3;
+        assert_eq!(
+            Ok(false),
+            verify_inclusion(&root, &commits[2], &proof, &TranscriptHasher)
+        );
+        proof.index = 2 + 8;
+        assert_eq!(
+            Ok(false),
+            verify_inclusion(&root, &commits[2], &proof, &TranscriptHasher)
+        );
+        assert!(tree.prove(5).is_none());
+        assert!(tree.prove(8).is_none());
+    }
+
+    #[test]
+    fn test_verify_with_inclusion() {
+        use crate::{
+            method1::precompute::M1Precomp,
+            traits::{Committer, PolyMultiProof},
+        };
+        use ark_poly::{univariate::DensePolynomial, DenseUVPolynomial, Polynomial};
+        use merlin::Transcript;
+
+        let points = (0..4)
+            .map(|_| Fr::rand(&mut test_rng()))
+            .collect::<Vec<_>>();
+        let s = M1Precomp::<Bls12_381>::new(32, vec![points.clone()], &mut test_rng()).unwrap();
+        let polys = (0..3)
+            .map(|_| DensePolynomial::<Fr>::rand(20, &mut test_rng()))
+            .collect::<Vec<_>>();
+        let commits = polys
+            .iter()
+            .map(|p| s.commit(&p.coeffs).unwrap())
+            .collect::<Vec<_>>();
+        let tree = CommitmentTree::new(&commits, &TranscriptHasher).unwrap();
+        let root = tree.root();
+
+        let row = 1;
+        let evals = points
+            .iter()
+            .map(|x| polys[row].evaluate(x))
+            .collect::<Vec<_>>();
+        let open = s
+            .open(
+                &mut Transcript::new(b"testing"),
+                &[&evals],
+                &[&polys[row].coeffs],
+                0,
+            )
+            .unwrap();
+        let inclusion = tree.prove(row).unwrap();
+        let verify = |inclusion: &super::InclusionProof, expected_row: usize, evals: &[Fr]| {
+            verify_with_inclusion(
+                &s,
+                &mut Transcript::new(b"testing"),
+                &root,
+                inclusion,
+                &TranscriptHasher,
+                &commits[row],
+                expected_row,
+                0,
+                evals,
+                &open,
+            )
+        };
+        assert_eq!(Ok(true), verify(&inclusion, row, &evals));
+
+        // A valid inclusion and opening, but for a different row than the one asked for
+        assert_eq!(Ok(false), verify(&inclusion, row + 1, &evals));
+
+        // A tampered inclusion proof, with a valid opening
+        let mut tampered = inclusion.clone();
+        tampered.siblings[0][0] ^= 1;
+        assert_eq!(Ok(false), verify(&tampered, row, &evals));
+
+        // A valid inclusion, but the opening doesn't match
+        let mut bad_evals = evals.clone();
+        bad_evals[0] += Fr::from(1u64);
+        assert_eq!(Ok(false), verify(&inclusion, row, &bad_evals));
+    }
+
+    #[test]
+    fn test_grid_committer() {
+        use super::{bytes_to_scalars, grid_point_sets, GridCommitter};
+        use crate::{method1::precompute::M1Precomp, traits::PolyMultiProof};
+        use ark_ff::PrimeField;
+        use ark_std::rand::RngCore;
+        use merlin::Transcript;
+        use std::ops::Range;
+
+        let (width, height, cell_width) = (16, 3, 4);
+        let pmp = M1Precomp::<Bls12_381>::new(
+            width,
+            grid_point_sets(width, cell_width).unwrap(),
+            &mut test_rng(),
+        )
+        .unwrap();
+        let gc = GridCommitter::new(&pmp, width, height, cell_width).unwrap();
+        assert_eq!(8, gc.extended_height());
+
+        // Less than a full grid, ending in a partial 5 byte chunk
+        let mut data = vec![0u8; 31 * 20 + 5];
+        test_rng().fill_bytes(&mut data);
+        let scalars = bytes_to_scalars::<Fr>(&data);
+        assert_eq!(21, scalars.len());
+        let mut last = data[31 * 20..].to_vec();
+        last.resize(31, 0);
+        assert_eq!(Fr::from_be_bytes_mod_order(&last), scalars[20]);
+
+        let mut grid = gc.commit_data(&data).unwrap();
+        assert_eq!(8, grid.commits.len());
+        assert_eq!(scalars[16..], grid.rows[2][..5]);
+
+        let open_verify = |rows: Range<usize>, cols: Range<usize>| -> Result<bool, Error> {
+            let proof = gc.open_cell(
+                &mut Transcript::new(b"testing"),
+                &grid,
+                rows.clone(),
+                cols.clone(),
+            )?;
+            let evals = grid.cell(rows.clone(), cols.clone()).unwrap();
+            gc.verify_cell(
+                &mut Transcript::new(b"testing"),
+                &grid.commits,
+                rows,
+                cols,
+                &evals,
+                &proof,
+            )
+        };
+        assert_eq!(Ok(true), open_verify(0..8, 0..4));
+        assert_eq!(Ok(true), open_verify(2..5, 12..16));
+
+        let proof = gc
+            .open_cell(&mut Transcript::new(b"testing"), &grid, 1..3, 4..8)
+            .unwrap();
+        let mut evals = grid
+            .cell(1..3, 4..8)
+            .unwrap()
+            .iter()
+            .map(|r| r.to_vec())
+            .collect::<Vec<_>>();
+        evals[1][2] += Fr::from(1u64);
+        assert_eq!(
+            Ok(false),
+            gc.verify_cell(
+                &mut Transcript::new(b"testing"),
+                &grid.commits,
+                1..3,
+                4..8,
+                &evals,
+                &proof,
+            )
+        );
+
+        assert_eq!(
+            Err(Error::CellNotAligned { start: 2, end: 6 }),
+            open_verify(0..2, 2..6)
+        );
+        assert_eq!(
+            Err(Error::PolyIndexOutOfRange { index: 8, n: 8 }),
+            open_verify(0..9, 0..4)
+        );
+        // The rows no longer match the commitments, so the cell can't be read
+        grid.rows.truncate(4);
+        assert_eq!(
+            Err(Error::PolyIndexOutOfRange { index: 5, n: 4 }),
+            gc.open_cell(&mut Transcript::new(b"testing"), &grid, 2..6, 0..4)
+                .map(|_| ())
+        );
+        assert_eq!(
+            Err(Error::PolynomialTooLarge {
+                n_coeffs: 49,
+                expected_max: 48
+            }),
+            gc.commit_data(&vec![1u8; 31 * 48 + 1]).map(|_| ())
+        );
+    }
+}
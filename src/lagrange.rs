@@ -1,21 +1,134 @@
 use std::ops::Mul;
 
-use ark_ff::FftField;
-use ark_poly::{univariate::DensePolynomial, DenseUVPolynomial, Polynomial};
+use ark_ff::{batch_inversion, FftField, Field};
+use ark_poly::{
+    univariate::DensePolynomial, DenseUVPolynomial, EvaluationDomain, GeneralEvaluationDomain,
+    Polynomial, Radix2EvaluationDomain,
+};
 
 use crate::cfg_iter;
 #[cfg(feature = "parallel")]
 use rayon::prelude::*;
 
-use crate::Error;
+use crate::{as_coset_domain, multi_eval::SubproductTree, Error};
+
+/// From this many arbitrary points, interpolating through a subproduct tree beats the naive
+/// lagrange basis, which has the lower constant but is quadratic. See the `lagrange_strategy`
+/// bench.
+const SUBPRODUCT_TREE_THRESHOLD: usize = 128;
 
 #[derive(Debug, Clone)]
-pub(crate) struct LagrangeInterpContext<F: FftField> {
-    lag_polys: Vec<DensePolynomial<F>>,
+pub struct LagrangeInterpContext<F: FftField> {
+    inner: Interp<F>,
+    /// The barycentric weights $w_j = 1 / \prod_{k \neq j} (x_j - x_k)$
+    weights: Vec<F>,
+}
+
+#[derive(Debug, Clone)]
+enum Interp<F: FftField> {
+    /// The lagrange basis polynomials for arbitrary points
+    Points(Vec<DensePolynomial<F>>),
+    /// The subproduct tree of many arbitrary points, interpolating in $O(n \log^2 n)$
+    Tree(SubproductTree<F>),
+    /// The points are exactly the elements of the domain, so interpolation is an ifft
+    Domain(GeneralEvaluationDomain<F>),
+}
+
+/// How a [`LagrangeInterpContext`] interpolates
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InterpStrategy {
+    /// With the precomputed lagrange basis, quadratic in the number of points
+    Naive,
+    /// Through a subproduct tree of the points
+    SubproductTree,
+    /// With an ifft over the domain the points form
+    Fft,
 }
 
 impl<F: FftField> LagrangeInterpContext<F> {
+    /// Makes a context for interpolating over the elements of `domain`, in the order given by
+    /// `domain.elements()`. This skips computing the lagrange basis and interpolates with an ifft.
+    pub fn new_from_domain(domain: &GeneralEvaluationDomain<F>) -> Self {
+        // For a coset sH of size n, \prod_{k \neq j} (x_j - x_k) = n x_j^{n - 1} = n s^n / x_j
+        let scale = (domain.size_as_field_element() * domain.coset_offset_pow_size())
+            .inverse()
+            .expect("Domain size and offset are nonzero");
+        Self {
+            inner: Interp::Domain(*domain),
+            weights: domain.elements().map(|x| x * scale).collect(),
+        }
+    }
+
+    /// Same as [`Self::new_from_domain`], for a radix 2 domain such as a grid's row domain
+    pub fn new_from_radix2_domain(domain: &Radix2EvaluationDomain<F>) -> Self {
+        Self::new_from_domain(&GeneralEvaluationDomain::Radix2(*domain))
+    }
+
+    /// The barycentric weights $w_j = 1 / \prod_{k \neq j} (x_j - x_k)$ of the points, in the
+    /// same order as the points. These are not normalized, see [`barycentric_evaluate`].
+    pub fn weights(&self) -> &[F] {
+        &self.weights
+    }
+
+    /// Which of the interpolation algorithms this context picked for its points
+    #[cfg(feature = "debug")]
+    pub fn strategy(&self) -> InterpStrategy {
+        match &self.inner {
+            Interp::Points(_) => InterpStrategy::Naive,
+            Interp::Tree(_) => InterpStrategy::SubproductTree,
+            Interp::Domain(_) => InterpStrategy::Fft,
+        }
+    }
+
+    fn n_points(&self) -> usize {
+        self.weights.len()
+    }
+
+    /// Makes a context for interpolating over `points`. If the points are a coset of a
+    /// multiplicative subgroup, in domain order, this is the same as [`Self::new_from_domain`] on
+    /// that coset. Otherwise, small sets get the lagrange basis and larger ones a subproduct tree.
     pub fn new_from_points(points: &[F]) -> Result<Self, Error> {
+        if let Some(coset) = as_coset_domain(points) {
+            return Ok(Self::new_from_domain(&coset));
+        }
+        if points.len() >= SUBPRODUCT_TREE_THRESHOLD {
+            return Self::new_from_points_tree(points);
+        }
+        Self::new_from_points_general(points)
+    }
+
+    /// Same as [`Self::new_from_points`], but interpolates with `strategy` whatever the number
+    /// of points, for comparing the strategies. [`InterpStrategy::Fft`] still needs the points to
+    /// be a coset, so for it this is just [`Self::new_from_points`].
+    #[cfg(feature = "debug")]
+    pub fn new_from_points_with_strategy(
+        points: &[F],
+        strategy: InterpStrategy,
+    ) -> Result<Self, Error> {
+        match strategy {
+            InterpStrategy::Naive => Self::new_from_points_general(points),
+            InterpStrategy::SubproductTree => Self::new_from_points_tree(points),
+            InterpStrategy::Fft => Self::new_from_points(points),
+        }
+    }
+
+    fn new_from_points_tree(points: &[F]) -> Result<Self, Error> {
+        let tree = SubproductTree::new(points);
+        // The weights are 1 / m'(x_j) for the vanishing polynomial m
+        let mut weights = Vec::with_capacity(points.len());
+        tree.evaluate(&derivative(tree.vanishing_poly()), &mut weights);
+        // A repeated point gives a zero denominator, which batch inversion would skip
+        if weights.iter().any(|d| d.is_zero()) {
+            return Err(Error::DivisorIsZero);
+        }
+        batch_inversion(&mut weights);
+        Ok(Self {
+            inner: Interp::Tree(tree),
+            weights,
+        })
+    }
+
+    fn new_from_points_general(points: &[F]) -> Result<Self, Error> {
         // Generate the non-normalized lagrange polynomials. These are zero on all points other
         // than the target point, and some nonzero value on that point.
         let non_normalized_polys: Vec<_> = cfg_iter!(points)
@@ -35,18 +148,23 @@ impl<F: FftField> LagrangeInterpContext<F> {
             })
             .collect();
 
+        // Evaluate each at its target, and divide by the result so that the polynomial
+        // evaluates to 1 at the target. The divisions share a single batch inversion.
+        let mut denoms: Vec<F> = cfg_iter!(non_normalized_polys)
+            .map(|(i, non_normed)| non_normed.evaluate(&points[i]))
+            .collect();
+        // A repeated point gives a zero denominator, which batch inversion would skip
+        if denoms.iter().any(|d| d.is_zero()) {
+            return Err(Error::DivisorIsZero);
+        }
+        batch_inversion(&mut denoms);
         let lag_polys = cfg_iter!(non_normalized_polys)
-            .map(|(i, non_normed)| {
-                // Evaluate at target, divide by result
-                // so that the polynomial evaluates to 1 at the target
-                non_normed
-                    .evaluate(&points[i])
-                    .inverse()
-                    .ok_or(Error::DivisorIsZero)
-                    .map(|v| non_normed * v)
-            })
-            .collect::<Result<Vec<_>, Error>>()?;
-        Ok(Self { lag_polys })
+            .map(|(i, non_normed)| non_normed * denoms[i])
+            .collect::<Vec<_>>();
+        Ok(Self {
+            inner: Interp::Points(lag_polys),
+            weights: denoms,
+        })
     }
 
     /// Given evals $((y_{1, 1}, \ldots y_{1_k}), \ldots (y_{l, 1}, \ldots y_{l, k}))$, points
@@ -58,7 +176,7 @@ impl<F: FftField> LagrangeInterpContext<F> {
         evals: &[impl AsRef<[F]>],
         scalars: &[F],
     ) -> Result<DensePolynomial<F>, Error> {
-        let mut targets = vec![F::zero(); self.lag_polys.len()];
+        let mut targets = vec![F::zero(); self.n_points()];
         for i in 0..evals.len() {
             let eval = evals[i].as_ref();
             for j in 0..eval.len() {
@@ -68,11 +186,281 @@ impl<F: FftField> LagrangeInterpContext<F> {
             }
         }
         // Now we just interpolate to targets
-        targets
-            .into_iter()
+        match &self.inner {
+            Interp::Points(lag_polys) => {
+                if lag_polys.is_empty() {
+                    return Err(Error::NoPointsGiven);
+                }
+                // Accumulate in place rather than allocating a scaled copy of each basis poly
+                let mut coeffs = vec![F::zero(); lag_polys.len()];
+                for (lag_poly, target) in lag_polys.iter().zip(targets) {
+                    for (c, l) in coeffs.iter_mut().zip(lag_poly.coeffs.iter()) {
+                        *c += target * l;
+                    }
+                }
+                Ok(DensePolynomial::from_coefficients_vec(coeffs))
+            }
+            Interp::Tree(tree) => {
+                for (t, w) in targets.iter_mut().zip(&self.weights) {
+                    *t *= w;
+                }
+                Ok(tree.linear_combination(&targets))
+            }
+            Interp::Domain(domain) => {
+                domain.ifft_in_place(&mut targets);
+                Ok(DensePolynomial::from_coefficients_vec(targets))
+            }
+        }
+    }
+}
+
+fn derivative<F: Field>(poly: &DensePolynomial<F>) -> DensePolynomial<F> {
+    let coeffs = poly
+        .coeffs
+        .iter()
+        .enumerate()
+        .skip(1)
+        .map(|(i, c)| F::from(i as u64) * c)
+        .collect();
+    DensePolynomial::from_coefficients_vec(coeffs)
+}
+
+/// Evaluates the unique polynomial of degree less than `points.len()` through `(points[j],
+/// evals[j])` at `z`, without computing its coefficients. `weights` are the barycentric weights
+/// $w_j = 1 / \prod_{k \neq j} (x_j - x_k)$ of `points`, as given by
+/// [`LagrangeInterpContext::weights`]. Since the second barycentric form is used, scaling all the
+/// weights by the same constant doesn't change the result.
+pub fn barycentric_evaluate<F: Field>(
+    weights: &[F],
+    points: &[F],
+    evals: &[F],
+    z: F,
+) -> Result<F, Error> {
+    if points.is_empty() {
+        return Err(Error::NoPointsGiven);
+    }
+    for (n, expected) in [(weights.len(), points.len()), (evals.len(), points.len())] {
+        if n != expected {
+            return Err(Error::EvalsIncorrectSize {
+                poly: 0,
+                n,
+                expected,
+            });
+        }
+    }
+    if let Some(j) = points.iter().position(|x| *x == z) {
+        return Ok(evals[j]);
+    }
+    let mut diffs = points.iter().map(|x| z - x).collect::<Vec<_>>();
+    batch_inversion(&mut diffs);
+    let mut num = F::zero();
+    let mut denom = F::zero();
+    for ((w, y), d) in weights.iter().zip(evals).zip(diffs) {
+        let t = *w * d;
+        num += t * y;
+        denom += t;
+    }
+    Ok(num * denom.inverse().ok_or(Error::DivisorIsZero)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{barycentric_evaluate, LagrangeInterpContext};
+    use crate::test_rng;
+    use ark_bls12_381::Fr;
+    use ark_poly::{EvaluationDomain, GeneralEvaluationDomain, Radix2EvaluationDomain};
+    use ark_std::UniformRand;
+
+    #[test]
+    fn test_batch_inversion_matches_single() {
+        use super::Interp;
+        use ark_ff::Field;
+        use ark_poly::{univariate::DensePolynomial, DenseUVPolynomial, Polynomial};
+
+        let points = (0..12)
+            .map(|_| Fr::rand(&mut test_rng()))
+            .collect::<Vec<_>>();
+        let expected = points
+            .iter()
             .enumerate()
-            .map(|(j, target)| self.lag_polys[j].mul(target))
-            .reduce(|x, y| x + y)
-            .ok_or(Error::NoPointsGiven)
+            .map(|(j, x_j)| {
+                let l_poly = points.iter().enumerate().filter(|(k, _)| *k != j).fold(
+                    DensePolynomial::from_coefficients_vec(vec![Fr::from(1u64)]),
+                    |acc, (_, x_k)| {
+                        &acc * &DensePolynomial::from_coefficients_vec(vec![-*x_k, Fr::from(1u64)])
+                    },
+                );
+                &l_poly * l_poly.evaluate(x_j).inverse().unwrap()
+            })
+            .collect::<Vec<_>>();
+        match LagrangeInterpContext::new_from_points_general(&points)
+            .unwrap()
+            .inner
+        {
+            Interp::Points(lag_polys) => assert_eq!(expected, lag_polys),
+            _ => panic!("Few random points should use the lagrange basis"),
+        }
+
+        let repeated = vec![points[0], points[1], points[0]];
+        assert!(LagrangeInterpContext::new_from_points(&repeated).is_err());
+    }
+
+    #[test]
+    fn test_barycentric_evaluate() {
+        use ark_poly::{univariate::DensePolynomial, DenseUVPolynomial, Polynomial};
+
+        let poly = DensePolynomial::<Fr>::rand(7, &mut test_rng());
+        let random = (0..8)
+            .map(|_| Fr::rand(&mut test_rng()))
+            .collect::<Vec<_>>();
+        let domain = GeneralEvaluationDomain::<Fr>::new(8).unwrap();
+        let coset = domain
+            .get_coset(Fr::rand(&mut test_rng()))
+            .unwrap()
+            .elements()
+            .collect::<Vec<_>>();
+        for points in [random, coset] {
+            let ctx = LagrangeInterpContext::new_from_points(&points).unwrap();
+            let general = LagrangeInterpContext::new_from_points_general(&points).unwrap();
+            assert_eq!(general.weights(), ctx.weights());
+
+            let evals = points.iter().map(|x| poly.evaluate(x)).collect::<Vec<_>>();
+            let z = Fr::rand(&mut test_rng());
+            assert_eq!(
+                Ok(poly.evaluate(&z)),
+                barycentric_evaluate(ctx.weights(), &points, &evals, z)
+            );
+            assert_eq!(
+                Ok(evals[3]),
+                barycentric_evaluate(ctx.weights(), &points, &evals, points[3])
+            );
+        }
+    }
+
+    #[test]
+    fn test_coset_matches_general() {
+        let domain = GeneralEvaluationDomain::<Fr>::new(8).unwrap();
+        let coset = domain.get_coset(Fr::rand(&mut test_rng())).unwrap();
+        let points = coset.elements().collect::<Vec<_>>();
+        let general = LagrangeInterpContext::new_from_points_general(&points).unwrap();
+        let detected = LagrangeInterpContext::new_from_points(&points).unwrap();
+
+        let evals = (0..3)
+            .map(|_| {
+                (0..8)
+                    .map(|_| Fr::rand(&mut test_rng()))
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        let scalars = (0..3)
+            .map(|_| Fr::rand(&mut test_rng()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            general
+                .lagrange_interp_linear_combo(&evals, &scalars)
+                .unwrap(),
+            detected
+                .lagrange_interp_linear_combo(&evals, &scalars)
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn test_domain_matches_points() {
+        let domain = GeneralEvaluationDomain::<Fr>::new(16).unwrap();
+        let points = domain.elements().collect::<Vec<_>>();
+        let from_points = LagrangeInterpContext::new_from_points_general(&points).unwrap();
+        let from_domain = LagrangeInterpContext::new_from_domain(&domain);
+
+        let evals = (0..5)
+            .map(|_| {
+                (0..16)
+                    .map(|_| Fr::rand(&mut test_rng()))
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        let scalars = (0..5)
+            .map(|_| Fr::rand(&mut test_rng()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            from_points
+                .lagrange_interp_linear_combo(&evals, &scalars)
+                .unwrap(),
+            from_domain
+                .lagrange_interp_linear_combo(&evals, &scalars)
+                .unwrap()
+        );
+
+        let radix2 = Radix2EvaluationDomain::<Fr>::new(16).unwrap();
+        let from_radix2 = LagrangeInterpContext::new_from_radix2_domain(&radix2);
+        assert_eq!(from_points.weights(), from_radix2.weights());
+        assert_eq!(
+            from_points
+                .lagrange_interp_linear_combo(&evals, &scalars)
+                .unwrap(),
+            from_radix2
+                .lagrange_interp_linear_combo(&evals, &scalars)
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn test_strategies_match() {
+        let domain = GeneralEvaluationDomain::<Fr>::new(256).unwrap();
+        let random = (0..200)
+            .map(|_| Fr::rand(&mut test_rng()))
+            .collect::<Vec<_>>();
+        let roots = domain.elements().collect::<Vec<_>>();
+        for points in [random, roots] {
+            let naive = LagrangeInterpContext::new_from_points_general(&points).unwrap();
+            let tree = LagrangeInterpContext::new_from_points_tree(&points).unwrap();
+            let picked = LagrangeInterpContext::new_from_points(&points).unwrap();
+            assert_eq!(naive.weights(), tree.weights());
+            assert_eq!(naive.weights(), picked.weights());
+
+            let evals = (0..3)
+                .map(|_| {
+                    (0..points.len())
+                        .map(|_| Fr::rand(&mut test_rng()))
+                        .collect::<Vec<_>>()
+                })
+                .collect::<Vec<_>>();
+            let scalars = (0..3)
+                .map(|_| Fr::rand(&mut test_rng()))
+                .collect::<Vec<_>>();
+            let expected = naive
+                .lagrange_interp_linear_combo(&evals, &scalars)
+                .unwrap();
+            assert_eq!(
+                expected,
+                tree.lagrange_interp_linear_combo(&evals, &scalars).unwrap()
+            );
+            assert_eq!(
+                expected,
+                picked
+                    .lagrange_interp_linear_combo(&evals, &scalars)
+                    .unwrap()
+            );
+        }
+
+        let repeated = vec![Fr::from(7u64); 200];
+        assert!(LagrangeInterpContext::new_from_points_tree(&repeated).is_err());
+    }
+
+    #[cfg(feature = "debug")]
+    #[test]
+    fn test_strategy_selection() {
+        use super::InterpStrategy;
+        let random = |n| {
+            (0..n)
+                .map(|_| Fr::rand(&mut test_rng()))
+                .collect::<Vec<_>>()
+        };
+        let ctx = |points: &[Fr]| LagrangeInterpContext::new_from_points(points).unwrap();
+        assert_eq!(InterpStrategy::Naive, ctx(&random(16)).strategy());
+        assert_eq!(InterpStrategy::SubproductTree, ctx(&random(300)).strategy());
+        let domain = GeneralEvaluationDomain::<Fr>::new(512).unwrap();
+        let roots = domain.elements().collect::<Vec<_>>();
+        assert_eq!(InterpStrategy::Fft, ctx(&roots).strategy());
     }
 }
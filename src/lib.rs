@@ -1,22 +1,46 @@
-use ark_ec::{pairing::Pairing, scalar_mul::fixed_base::FixedBase, CurveGroup, ScalarMul};
-use ark_ff::{Field, PrimeField};
+use ark_ec::{
+    pairing::{Pairing, PairingOutput},
+    scalar_mul::fixed_base::FixedBase,
+    AffineRepr, CurveGroup, ScalarMul,
+};
+use ark_ff::{FftField, Field, PrimeField, Zero};
 use ark_poly::{
     univariate::{DenseOrSparsePolynomial, DensePolynomial},
-    DenseUVPolynomial,
+    DenseUVPolynomial, EvaluationDomain, GeneralEvaluationDomain,
+};
+use ark_serialize::{
+    CanonicalDeserialize, CanonicalSerialize, Compress, Read, SerializationError, Valid, Validate,
+    Write,
 };
-use ark_serialize::{CanonicalSerialize, Compress, SerializationError};
-use ark_std::rand::RngCore;
+use ark_std::{rand::RngCore, UniformRand};
 use merlin::Transcript;
 #[cfg(test)]
 use rand::thread_rng as test_rng;
+use smallvec::SmallVec;
+use std::collections::HashSet;
 
 pub mod method1;
 pub mod method2;
 
+pub mod bivariate;
+#[cfg(feature = "blob")]
+pub mod blob;
+pub mod bundle;
+#[cfg(feature = "insecure-deterministic-transcript")]
+pub mod deterministic;
+pub mod equality;
+pub mod grid;
 pub mod lagrange;
+mod multi_eval;
+pub use multi_eval::multi_evaluate;
 #[cfg(feature = "blst")]
 pub mod m1_blst;
+pub mod msm;
+pub mod packing;
+#[cfg(test)]
+mod test_helpers;
 pub mod traits;
+pub mod transcript;
 
 #[derive(thiserror::Error, Debug, Eq, PartialEq)]
 pub enum Error {
@@ -29,6 +53,8 @@ pub enum Error {
     DivisorIsZero,
     #[error("Expected polynomials, none were given")]
     NoPolynomialsGiven,
+    #[error("Every polynomial given has no coefficients")]
+    EmptyPolynomials,
     #[error("Given evaluations were the incorrect size")]
     EvalsIncorrectSize {
         poly: usize,
@@ -37,10 +63,101 @@ pub enum Error {
     },
     #[error("Serialization error")]
     SerializationError,
-    #[error("Not enough g2 powers")]
-    NotEnoughG2Powers,
+    #[error("Needed {needed} g2 powers, the setup has {have}")]
+    NotEnoughG2Powers { needed: usize, have: usize },
     #[error("Not given any points")]
     NoPointsGiven,
+    #[error("Proof failed to verify")]
+    VerificationFailed,
+    #[error("Point sets share a point")]
+    OverlappingPointSets,
+    #[error("Number of weights does not match the number of commitments")]
+    WeightsIncorrectSize { n: usize, expected: usize },
+    #[error("An opening point or challenge equals the setup's secret")]
+    PointIsSecret,
+    #[error("Commitment does not match the polynomial")]
+    CommitmentMismatch,
+    #[error("Evaluation {index} does not match the polynomial")]
+    EvalMismatch { index: usize },
+    #[error("Point set index {index} is out of range, there are {n} point sets")]
+    PointSetIndexOutOfRange { index: usize, n: usize },
+    #[error("Blob is {n} bytes, expected {expected}")]
+    BlobIncorrectSize { n: usize, expected: usize },
+    #[error("Element {index} is not less than the modulus")]
+    NonCanonicalScalar { index: usize },
+    #[error("A commitment or proof is not a valid point in the prime order subgroup")]
+    InvalidPoint,
+    #[error("Position {position} is out of range, there are {n} points")]
+    PositionOutOfRange { position: usize, n: usize },
+    #[error("Position {position} appears more than once")]
+    DuplicatePosition { position: usize },
+    #[error("Precompute level {level} is above the maximum of {max}")]
+    PrecomputeLevelTooLarge { level: usize, max: usize },
+    #[error("Given {n} evaluation sets or proofs for {expected} point sets")]
+    PointSetCountMismatch { n: usize, expected: usize },
+    #[error("Given {n} commitments, expected {expected}")]
+    CommitsIncorrectSize { n: usize, expected: usize },
+    #[error("Polynomial index {index} is out of range, there are {n} polynomials")]
+    PolyIndexOutOfRange { index: usize, n: usize },
+    #[error("Polynomial index {index} appears more than once")]
+    DuplicatePolyIndex { index: usize },
+    #[error("Expansion factor {expansion} isn't a power of two the field supports")]
+    InvalidExpansionFactor { expansion: usize },
+    #[error("The msm backend produced a point that isn't on the curve")]
+    MsmBackendInconsistency,
+    #[error("Commitment {index} isn't a valid compressed point")]
+    MalformedCommitment { index: usize },
+    #[error("The proof isn't a valid compressed proof")]
+    MalformedProof,
+    #[error("The setup needs at least one g1 and one g2 power")]
+    EmptyPowers,
+    #[error("The setup powers aren't powers of the same secret")]
+    InconsistentSrs,
+    #[error("The setup has no blinding generator")]
+    NoBlindingGenerator,
+    #[error("Given {n} blinding polynomials for {expected} polynomials")]
+    BlindingsIncorrectSize { n: usize, expected: usize },
+    #[error("Blinding {index} has {n_coeffs} coefficients, over the max of {expected_max}")]
+    BlindingTooLarge {
+        index: usize,
+        n_coeffs: usize,
+        expected_max: usize,
+    },
+    #[error(
+        "Grid width {width} isn't a power of two split into whole cells of width {cell_width}"
+    )]
+    InvalidGridWidth { width: usize, cell_width: usize },
+    #[error("Columns {start}..{end} aren't exactly one cell of the grid")]
+    CellNotAligned { start: usize, end: usize },
+    #[error("Vanishing polynomial has degree {degree}, expected one per point, {expected}")]
+    VanishingPolyIncorrectDegree { degree: usize, expected: usize },
+}
+
+/// Why a proof didn't verify, returned by
+/// [`M1NoPrecomp::verify_detailed`](method1::M1NoPrecomp::verify_detailed)
+#[derive(thiserror::Error, Debug, Eq, PartialEq)]
+pub enum VerifyFailure<E: Pairing> {
+    /// The inputs were well formed, but the pairing check failed: the evals, commitments or
+    /// proof don't match. Carries both sides of the check so they can be logged.
+    #[error("The pairing check failed")]
+    PairingMismatch {
+        lhs: PairingOutput<E>,
+        rhs: PairingOutput<E>,
+    },
+    /// The inputs couldn't be checked, e.g. they have the wrong shape or invalid points
+    #[error(transparent)]
+    Input(#[from] Error),
+}
+
+impl<E: Pairing> VerifyFailure<E> {
+    /// The `Result<bool, Error>` that plain `verify` returns: a pairing mismatch is `Ok(false)`
+    pub fn into_verify_result(result: Result<(), Self>) -> Result<bool, Error> {
+        match result {
+            Ok(()) => Ok(true),
+            Err(VerifyFailure::PairingMismatch { .. }) => Ok(false),
+            Err(VerifyFailure::Input(e)) => Err(e),
+        }
+    }
 }
 
 impl From<SerializationError> for Error {
@@ -49,15 +166,324 @@ impl From<SerializationError> for Error {
     }
 }
 
+/// A deterministic rng, so downstream tests and benchmarks can reproduce exact inputs
+#[cfg(feature = "test-utils")]
+pub fn seeded_rng(seed: u64) -> ark_std::rand::rngs::StdRng {
+    use ark_std::rand::SeedableRng;
+    ark_std::rand::rngs::StdRng::seed_from_u64(seed)
+}
+
 #[derive(Debug)]
 pub struct Commitment<E: Pairing>(pub E::G1Affine);
 
+impl<E: Pairing> Commitment<E> {
+    /// The commitment to the zero polynomial, i.e. the group identity
+    pub fn zero() -> Self {
+        Commitment(E::G1Affine::zero())
+    }
+
+    /// Whether this is the commitment to the zero polynomial
+    pub fn is_zero(&self) -> bool {
+        self.0.is_zero()
+    }
+
+    /// The compressed serialization, 48 bytes for BLS12-381
+    pub fn to_compressed_bytes(&self) -> Vec<u8> {
+        to_compressed_bytes(self)
+    }
+
+    /// Reads a commitment from exactly its compressed serialization. Errors with
+    /// [`Error::InvalidPoint`] if it isn't on the curve or in the prime order subgroup, and
+    /// [`Error::MalformedCommitment`] with index 0 for anything else.
+    pub fn from_compressed_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        from_exact_compressed(bytes, Error::MalformedCommitment { index: 0 })
+    }
+
+    /// A commitment to $s f$, where this commits to $f$
+    pub fn scale(&self, s: E::ScalarField) -> Self {
+        Commitment((self.0 * s).into_affine())
+    }
+}
+
+/// A commitment to $f + g$, where the commitments are to $f$ and $g$
+impl<E: Pairing> std::ops::Add for &Commitment<E> {
+    type Output = Commitment<E>;
+
+    fn add(self, other: Self) -> Commitment<E> {
+        Commitment((self.0 + other.0).into_affine())
+    }
+}
+
+impl<E: Pairing> std::ops::Add for Commitment<E> {
+    type Output = Commitment<E>;
+
+    fn add(self, other: Self) -> Commitment<E> {
+        &self + &other
+    }
+}
+
+impl<E: Pairing> std::fmt::Display for Commitment<E> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        crate::fmt_compressed_hex(f, "Commitment", self)
+    }
+}
+
+impl<E: Pairing> CanonicalSerialize for Commitment<E> {
+    fn serialize_with_mode<W: Write>(
+        &self,
+        writer: W,
+        compress: Compress,
+    ) -> Result<(), SerializationError> {
+        self.0.serialize_with_mode(writer, compress)
+    }
+
+    fn serialized_size(&self, compress: Compress) -> usize {
+        self.0.serialized_size(compress)
+    }
+}
+
+impl<E: Pairing> Valid for Commitment<E> {
+    fn check(&self) -> Result<(), SerializationError> {
+        self.0.check()
+    }
+}
+
+impl<E: Pairing> CanonicalDeserialize for Commitment<E> {
+    fn deserialize_with_mode<R: Read>(
+        reader: R,
+        compress: Compress,
+        validate: Validate,
+    ) -> Result<Self, SerializationError> {
+        Ok(Commitment(E::G1Affine::deserialize_with_mode(
+            reader, compress, validate,
+        )?))
+    }
+}
+
+/// Checks that a setup loaded from `n_g1` g1 powers and `n_g2` g2 powers has the generator in
+/// both groups and the g2 power of the secret, which every verifier needs
+pub(crate) fn check_powers_len(n_g1: usize, n_g2: usize) -> Result<(), Error> {
+    if n_g1 == 0 || n_g2 == 0 {
+        return Err(Error::EmptyPowers);
+    }
+    if n_g2 < 2 {
+        return Err(Error::NotEnoughG2Powers {
+            needed: 2,
+            have: n_g2,
+        });
+    }
+    Ok(())
+}
+
+/// Checks that `g1s` and `g2s` are the successive powers of one secret $x$ on each generator, i.e.
+/// $e(g_1^{x^i}, g_2) = e(g_1^{x^{i-1}}, g_2^x)$ for every g1 power and
+/// $e(g_1, g_2^{x^j}) = e(g_1^x, g_2^{x^{j-1}})$ for every g2 power. Every relation is checked at
+/// once by taking random linear combinations of both sides, so it's one msm per side and a
+/// single multi pairing rather than a pairing per power. A trivial secret of 0 or 1 is rejected.
+pub(crate) fn validate_powers<E: Pairing>(
+    g1s: &[E::G1Affine],
+    g2s: &[E::G2Affine],
+    rng: &mut impl RngCore,
+) -> Result<(), Error> {
+    check_powers_len(g1s.len(), g2s.len())?;
+    if g1s[0].is_zero() || g2s[0].is_zero() {
+        return Err(Error::InconsistentSrs);
+    }
+    // A secret of 0 or 1 gives powers that are consistent but useless, so reject those too
+    if let Some(g1x) = g1s.get(1) {
+        if g1x.is_zero() || *g1x == g1s[0] {
+            return Err(Error::InconsistentSrs);
+        }
+    }
+    let r1 = (1..g1s.len())
+        .map(|_| E::ScalarField::rand(rng))
+        .collect::<Vec<_>>();
+    let g1_shifted = curve_msm::<E::G1>(&g1s[1..], &r1)?;
+    let g1_unshifted = curve_msm::<E::G1>(&g1s[..g1s.len() - 1], &r1)?;
+    let r2 = (1..g2s.len())
+        .map(|_| E::ScalarField::rand(rng))
+        .collect::<Vec<_>>();
+    let g2_shifted = curve_msm::<E::G2>(&g2s[1..], &r2)?;
+    let g2_unshifted = curve_msm::<E::G2>(&g2s[..g2s.len() - 1], &r2)?;
+    // With a single g1 power there is no g1 power of x to tie the g2 powers to
+    let g1x = g1s.get(1).copied().unwrap_or_else(E::G1Affine::zero);
+    let g2_side = if g1s.len() > 1 {
+        [g2_shifted, -g2_unshifted]
+    } else {
+        [E::G2::zero(), E::G2::zero()]
+    };
+    let res = E::multi_pairing(
+        [
+            g1_shifted,
+            -g1_unshifted,
+            g1s[0].into_group(),
+            g1x.into_group(),
+        ],
+        [
+            g2s[0].into_group(),
+            g2s[1].into_group(),
+            g2_side[0],
+            g2_side[1],
+        ],
+    );
+    if !res.is_zero() {
+        return Err(Error::InconsistentSrs);
+    }
+    Ok(())
+}
+
+/// The compressed serialization of `value`
+pub(crate) fn to_compressed_bytes(value: &impl CanonicalSerialize) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(value.compressed_size());
+    value
+        .serialize_compressed(&mut bytes)
+        .expect("Serializing into a vec can't fail");
+    bytes
+}
+
+/// Deserializes a `T` from exactly `bytes`, checking its points. Truncated input, trailing bytes
+/// and bytes that aren't a point at all are `malformed`, while points off the curve or outside the
+/// prime order subgroup are [`Error::InvalidPoint`].
+pub(crate) fn from_exact_compressed<T: CanonicalDeserialize>(
+    bytes: &[u8],
+    malformed: Error,
+) -> Result<T, Error> {
+    let mut reader = bytes;
+    let value = match T::deserialize_compressed_unchecked(&mut reader) {
+        Ok(value) if reader.is_empty() => value,
+        _ => return Err(malformed),
+    };
+    // Only check the points once they've parsed, so a point that isn't on the curve or isn't in
+    // the prime order subgroup is told apart from bytes that aren't a point at all
+    value.check().map_err(|_| Error::InvalidPoint)?;
+    Ok(value)
+}
+
+/// Writes `value` as `name(0x...)`, with the hex of its compressed serialization, so commitments
+/// and proofs are readable in logs. `Debug` still prints the full structure.
+pub(crate) fn fmt_compressed_hex(
+    f: &mut std::fmt::Formatter<'_>,
+    name: &str,
+    value: &impl CanonicalSerialize,
+) -> std::fmt::Result {
+    let mut bytes = Vec::new();
+    value
+        .serialize_compressed(&mut bytes)
+        .map_err(|_| std::fmt::Error)?;
+    write!(f, "{name}(0x")?;
+    for b in bytes {
+        write!(f, "{b:02x}")?;
+    }
+    write!(f, ")")
+}
+
+/// Computes $\sum_i w_i C_i$ for commitments $C_i$ and weights $w_i$. By the homomorphism this
+/// is a commitment to $\sum_i w_i f_i$.
+pub fn combine_commitments<E: Pairing>(
+    commits: &[Commitment<E>],
+    weights: &[E::ScalarField],
+) -> Result<Commitment<E>, Error> {
+    if commits.len() != weights.len() {
+        return Err(Error::WeightsIncorrectSize {
+            n: weights.len(),
+            expected: commits.len(),
+        });
+    }
+    let cms = commits.iter().map(|c| c.0).collect::<Vec<_>>();
+    Ok(Commitment(curve_msm::<E::G1>(&cms, weights)?.into_affine()))
+}
+
+/// Given a commitment `old` to $f$ against `powers_of_g1`, returns a commitment to
+/// $f + \delta x^{index}$, for each setup's `update_commitment`
+pub(crate) fn update_commitment<E: Pairing>(
+    powers_of_g1: &[impl Into<E::G1> + Copy],
+    old: &Commitment<E>,
+    index: usize,
+    delta: E::ScalarField,
+) -> Result<Commitment<E>, Error> {
+    let g: E::G1 = powers_of_g1
+        .get(index)
+        .copied()
+        .ok_or_else(|| Error::PolynomialTooLarge {
+            n_coeffs: index.saturating_add(1),
+            expected_max: powers_of_g1.len(),
+        })?
+        .into();
+    Ok(Commitment((old.0.into_group() + g * delta).into_affine()))
+}
+
+/// Serializes `proofs` into a single blob prefixed with the number of proofs, using compressed
+/// point encodings
+pub fn serialize_proofs<P: CanonicalSerialize>(proofs: &[P]) -> Result<Vec<u8>, Error> {
+    serialize_proofs_with_mode(proofs, Compress::Yes)
+}
+
+/// Serializes `proofs` into a single blob prefixed with the number of proofs. Compressed points
+/// are about half the size, uncompressed points skip the square root when deserializing.
+pub fn serialize_proofs_with_mode<P: CanonicalSerialize>(
+    proofs: &[P],
+    compress: Compress,
+) -> Result<Vec<u8>, Error> {
+    let mut out = Vec::new();
+    (proofs.len() as u64).serialize_with_mode(&mut out, compress)?;
+    for proof in proofs {
+        proof.serialize_with_mode(&mut out, compress)?;
+    }
+    Ok(out)
+}
+
+/// Reads a blob written by [`serialize_proofs`]
+pub fn deserialize_proofs<P: CanonicalDeserialize>(bytes: &[u8]) -> Result<Vec<P>, Error> {
+    deserialize_proofs_with_mode(bytes, Compress::Yes)
+}
+
+/// Reads a blob written by [`serialize_proofs_with_mode`] with the same `compress`. Errors if the
+/// blob holds fewer proofs than its count claims or has trailing bytes.
+pub fn deserialize_proofs_with_mode<P: CanonicalDeserialize>(
+    mut bytes: &[u8],
+    compress: Compress,
+) -> Result<Vec<P>, Error> {
+    let count = u64::deserialize_with_mode(&mut bytes, compress, Validate::Yes)?;
+    // Don't trust the count for preallocation, a truncated blob will fail below
+    let mut proofs = Vec::new();
+    for _ in 0..count {
+        proofs.push(P::deserialize_with_mode(
+            &mut bytes,
+            compress,
+            Validate::Yes,
+        )?);
+    }
+    if !bytes.is_empty() {
+        return Err(Error::SerializationError);
+    }
+    Ok(proofs)
+}
+
 pub(crate) fn gen_powers<F: Field>(element: F, len: usize) -> Vec<F> {
-    let mut powers = vec![F::one(); len];
+    let mut powers = Vec::with_capacity(len);
+    gen_powers_into(element, len, &mut powers);
+    powers
+}
+
+/// Same as [`gen_powers`], but doesn't allocate for up to 32 powers. Verification usually only
+/// needs a power per commitment, so this avoids a heap allocation in the common case.
+pub(crate) fn gen_powers_small<F: Field>(element: F, len: usize) -> SmallVec<[F; 32]> {
+    let mut powers = SmallVec::with_capacity(len);
+    let mut cur = F::one();
+    for _ in 0..len {
+        powers.push(cur);
+        cur *= element;
+    }
+    powers
+}
+
+/// Fills `powers` with the first `len` powers of `element`, reusing its allocation
+pub(crate) fn gen_powers_into<F: Field>(element: F, len: usize, powers: &mut Vec<F>) {
+    powers.clear();
+    powers.resize(len, F::one());
     for i in 1..len {
         powers[i] = element * powers[i - 1];
     }
-    powers
 }
 
 #[inline]
@@ -71,22 +497,269 @@ pub(crate) fn curve_msm<G: ScalarMul + CurveGroup>(
             expected_max: bases.len(),
         });
     }
+    let scalars = trim_zeros(scalars);
+    if scalars.is_empty() {
+        return Ok(G::zero());
+    }
     let scalars = scalars.iter().map(|x| x.into_bigint()).collect::<Vec<_>>();
     let sp = G::msm_bigint(&bases[..scalars.len()], &scalars);
     Ok(sp)
 }
 
-pub(crate) fn vanishing_polynomial<F: Field>(points: impl AsRef<[F]>) -> DensePolynomial<F> {
+/// Drops the trailing zeros of `scalars`, which add nothing to an msm. Polynomials from an ifft
+/// of zero padded evaluations often have many.
+pub(crate) fn trim_zeros<F: Zero>(scalars: &[F]) -> &[F] {
+    let len = scalars
+        .iter()
+        .rposition(|x| !x.is_zero())
+        .map_or(0, |i| i + 1);
+    &scalars[..len]
+}
+
+/// Below this many commitments, $\sum_i \gamma^i C_i$ is computed with horner's rule instead of an
+/// msm. Horner costs one full scalar multiplication per commitment, while an msm amortizes its
+/// doublings across all the points but pays for bucket setup (and, with blst, for converting the
+/// commitments into its tables), which dominates for a handful of points.
+pub(crate) const HORNER_COMMIT_THRESHOLD: usize = 8;
+
+/// Computes $\sum_i \gamma^i C_i$ over the first `gammas.len()` commitments, where `gammas` are
+/// the powers of $\gamma$ starting at 1
+pub(crate) fn gamma_commit_combo<E: Pairing>(
+    commits: &[Commitment<E>],
+    gammas: &[E::ScalarField],
+) -> Result<E::G1, Error> {
+    if gammas.len() < HORNER_COMMIT_THRESHOLD {
+        return horner_commit_combo(commits, gammas);
+    }
+    let cms = commits.iter().map(|c| c.0).collect::<Vec<_>>();
+    curve_msm::<E::G1>(&cms, gammas)
+}
+
+/// Compares the serializations of `a` and `b`, looking at every byte rather than stopping at the
+/// first difference
+pub(crate) fn ct_eq<T: CanonicalSerialize>(a: &T, b: &T) -> Result<bool, Error> {
+    let mut a_bytes = Vec::new();
+    let mut b_bytes = Vec::new();
+    a.serialize_uncompressed(&mut a_bytes)?;
+    b.serialize_uncompressed(&mut b_bytes)?;
+    let diff = a_bytes
+        .iter()
+        .zip(&b_bytes)
+        .fold(0u8, |acc, (x, y)| acc | (x ^ y));
+    Ok(diff == 0 && a_bytes.len() == b_bytes.len())
+}
+
+/// Same as [`gamma_commit_combo`] with the powers of `gamma`, but consumes the commitments in
+/// chunks as they're yielded. Errors if there aren't exactly `expected` of them, or if any isn't a
+/// valid point.
+pub(crate) fn gamma_commit_combo_streaming<E: Pairing>(
+    commits: impl Iterator<Item = Commitment<E>>,
+    gamma: E::ScalarField,
+    expected: usize,
+) -> Result<E::G1, Error> {
+    let mut n = 0;
+    let mut acc = E::G1::zero();
+    // gamma^n, the power the next chunk starts at
+    let mut offset = E::ScalarField::ONE;
+    let mut chunk = Vec::with_capacity(TRY_ITER_CHUNK_SIZE);
+    let mut commits = commits.fuse();
+    loop {
+        chunk.clear();
+        chunk.extend(commits.by_ref().take(TRY_ITER_CHUNK_SIZE).map(|c| c.0));
+        if chunk.is_empty() {
+            break;
+        }
+        E::G1Affine::batch_check(chunk.iter()).map_err(|_| Error::InvalidPoint)?;
+        let gammas = gen_powers(gamma, chunk.len() + 1);
+        let scaled = gammas[..chunk.len()]
+            .iter()
+            .map(|g| *g * offset)
+            .collect::<Vec<_>>();
+        acc += curve_msm::<E::G1>(&chunk, &scaled)?;
+        offset *= gammas[chunk.len()];
+        n += chunk.len();
+    }
+    if n != expected {
+        return Err(Error::CommitsIncorrectSize { n, expected });
+    }
+    Ok(acc)
+}
+
+/// Same as [`gamma_commit_combo`], always using horner's rule
+pub(crate) fn horner_commit_combo<E: Pairing>(
+    commits: &[Commitment<E>],
+    gammas: &[E::ScalarField],
+) -> Result<E::G1, Error> {
+    if gammas.len() > commits.len() {
+        return Err(Error::PolynomialTooLarge {
+            n_coeffs: gammas.len(),
+            expected_max: commits.len(),
+        });
+    }
+    // With a single commitment gamma is never used
+    let gamma = gammas.get(1).copied().unwrap_or_default();
+    Ok(commits[..gammas.len()]
+        .iter()
+        .rev()
+        .fold(E::G1::zero(), |acc, c| acc * gamma + c.0.into_group()))
+}
+
+/// Computes an msm of `bases` against the concatenation of `chunks`, only holding one chunk of
+/// scalars at a time
+pub(crate) fn curve_msm_streaming<G: ScalarMul + CurveGroup>(
+    bases: &[G::Affine],
+    chunks: impl Iterator<Item = Vec<G::ScalarField>>,
+) -> Result<G, Error> {
+    let mut offset = 0;
+    let mut acc = G::zero();
+    for chunk in chunks {
+        let end = offset + chunk.len();
+        if end > bases.len() {
+            return Err(Error::PolynomialTooLarge {
+                n_coeffs: end,
+                expected_max: bases.len(),
+            });
+        }
+        acc += curve_msm::<G>(&bases[offset..end], &chunk)?;
+        offset = end;
+    }
+    Ok(acc)
+}
+
+/// How many scalars [`curve_msm_try_iter`] buffers before running an msm over them
+const TRY_ITER_CHUNK_SIZE: usize = 1 << 12;
+
+/// Computes an msm of `bases` against the scalars yielded by `scalars`, buffering at most
+/// [`TRY_ITER_CHUNK_SIZE`] of them at a time. Stops at and returns the first error.
+pub(crate) fn curve_msm_try_iter<G: ScalarMul + CurveGroup>(
+    bases: &[G::Affine],
+    scalars: impl Iterator<Item = Result<G::ScalarField, Error>>,
+) -> Result<G, Error> {
+    msm_try_iter_with(bases.len(), scalars, |range, chunk| {
+        curve_msm::<G>(&bases[range], chunk)
+    })
+}
+
+/// Same as [`curve_msm_try_iter`], with `msm` computing each chunk's msm against the bases in
+/// the given range, for setups that don't keep affine bases
+pub(crate) fn msm_try_iter_with<G: CurveGroup>(
+    n_bases: usize,
+    scalars: impl Iterator<Item = Result<G::ScalarField, Error>>,
+    mut msm: impl FnMut(std::ops::Range<usize>, &[G::ScalarField]) -> Result<G, Error>,
+) -> Result<G, Error> {
+    let mut offset = 0;
+    let mut acc = G::zero();
+    let mut chunk = Vec::with_capacity(TRY_ITER_CHUNK_SIZE);
+    let mut scalars = scalars.fuse();
+    loop {
+        chunk.clear();
+        for scalar in scalars.by_ref().take(TRY_ITER_CHUNK_SIZE) {
+            chunk.push(scalar?);
+        }
+        if chunk.is_empty() {
+            return Ok(acc);
+        }
+        let end = offset + chunk.len();
+        if end > n_bases {
+            return Err(Error::PolynomialTooLarge {
+                n_coeffs: end,
+                expected_max: n_bases,
+            });
+        }
+        acc += msm(offset..end, &chunk)?;
+        offset = end;
+    }
+}
+
+/// Checks that every one of `point_sets` fits the `n_g2_powers` g2 powers of a setup. The
+/// vanishing polynomial of a set has one more coefficient than there are points.
+pub(crate) fn check_point_set_sizes<F>(
+    point_sets: &[Vec<F>],
+    n_g2_powers: usize,
+) -> Result<(), Error> {
+    match point_sets.iter().find(|ps| ps.len() >= n_g2_powers) {
+        Some(ps) => Err(Error::NotEnoughG2Powers {
+            needed: ps.len() + 1,
+            have: n_g2_powers,
+        }),
+        None => Ok(()),
+    }
+}
+
+/// Checks that no point appears in more than one of `point_sets`
+pub(crate) fn check_disjoint<F: Field>(point_sets: &[Vec<F>]) -> Result<(), Error> {
+    let mut seen = HashSet::new();
+    for ps in point_sets {
+        // Points repeated within a set are caught by the lagrange interpolation
+        let set: HashSet<_> = ps.iter().collect();
+        if set.iter().any(|p| seen.contains(p)) {
+            return Err(Error::OverlappingPointSets);
+        }
+        seen.extend(set);
+    }
+    Ok(())
+}
+
+/// Whether every eval is zero. In that case every $r_i$ is the zero polynomial, so verifiers can
+/// skip interpolating them.
+pub(crate) fn evals_all_zero<F: Field>(evals: &[impl AsRef<[F]>]) -> bool {
+    evals.iter().all(|e| e.as_ref().iter().all(|y| y.is_zero()))
+}
+
+/// If `points` are exactly a coset $sH$ of a multiplicative subgroup $H$ of order `points.len()`,
+/// in the order $s, s\omega, s\omega^2, \ldots$, returns it as an evaluation domain
+pub(crate) fn as_coset_domain<F: FftField>(points: &[F]) -> Option<GeneralEvaluationDomain<F>> {
+    if points.is_empty() || points[0].is_zero() {
+        return None;
+    }
+    let domain = GeneralEvaluationDomain::<F>::new(points.len())?;
+    if domain.size() != points.len() {
+        return None;
+    }
+    let coset = domain.get_coset(points[0])?;
+    if coset.elements().zip(points).all(|(a, b)| a == *b) {
+        Some(coset)
+    } else {
+        None
+    }
+}
+
+/// The polynomial $\prod_i (X - z_i)$ that is zero exactly on `points`
+pub fn vanishing_polynomial<F: FftField>(points: impl AsRef<[F]>) -> DensePolynomial<F> {
+    let points = points.as_ref();
+    match as_coset_domain(points) {
+        Some(domain) => vanishing_polynomial_for_domain(&domain),
+        None => vanishing_polynomial_naive(points),
+    }
+}
+
+/// The vanishing polynomial of all the points in `domain` in closed form, $X^n - s^n$ for a coset
+/// $sH$ of order $n$, which is $X^n - 1$ for the roots of unity themselves
+pub fn vanishing_polynomial_for_domain<F: FftField>(
+    domain: &impl EvaluationDomain<F>,
+) -> DensePolynomial<F> {
+    let n = domain.size();
+    let mut coeffs = vec![F::zero(); n + 1];
+    coeffs[0] = -domain.coset_offset_pow_size();
+    coeffs[n] = F::one();
+    DensePolynomial::from_coefficients_vec(coeffs)
+}
+
+fn vanishing_polynomial_naive<F: Field>(points: &[F]) -> DensePolynomial<F> {
     let one = DensePolynomial::from_coefficients_vec(vec![F::one()]);
     points
-        .as_ref()
         .iter()
         .map(|&point| DensePolynomial::from_coefficients_vec(vec![-point, F::one()]))
         .fold(one, |x, y| x.naive_mul(&y))
 }
 
-/// Does polynomial division, returning q, r
-pub(crate) fn poly_div_q_r<F: Field>(
+/// Divides `num` by `denom`, returning the coefficients of the quotient $q$ and remainder $r$
+/// such that `num` $= q \cdot$ `denom` $+ r$ with $\deg r < \deg$ `denom`.
+///
+/// Coefficients are in ascending degree order, and trailing zeros are trimmed, so the zero
+/// polynomial is an empty vector. If `denom` has a higher degree than `num`, the quotient is
+/// empty and the remainder is `num`. A zero `denom` is [`Error::DivisorIsZero`].
+pub fn poly_div_q_r<F: Field>(
     num: DenseOrSparsePolynomial<F>,
     denom: DenseOrSparsePolynomial<F>,
 ) -> Result<(Vec<F>, Vec<F>), Error> {
@@ -97,48 +770,153 @@ pub(crate) fn poly_div_q_r<F: Field>(
     Ok((q.coeffs, r.coeffs))
 }
 
+/// Divides the polynomial with coefficients `coeffs` by $X - z$ with synthetic division, returning
+/// the quotient. The remainder, the evaluation at $z$, is dropped.
+pub(crate) fn div_by_linear<F: Field>(coeffs: &[F], z: F) -> Vec<F> {
+    let mut q = vec![F::zero(); coeffs.len().saturating_sub(1)];
+    let mut acc = F::zero();
+    for (i, c) in coeffs.iter().enumerate().skip(1).rev() {
+        acc = acc * z + c;
+        q[i - 1] = acc;
+    }
+    q
+}
+
+/// Checks that there is one row of evals per polynomial or commitment, each with one eval per
+/// point. Catches e.g. a transposed eval matrix up front instead of as a proof that fails to
+/// verify. A missing row counts as having no evals, and any row past the last is an error.
+pub(crate) fn check_eval_shape<F>(
+    evals: &[impl AsRef<[F]>],
+    n_rows: usize,
+    n_points: usize,
+) -> Result<(), Error> {
+    for poly in 0..evals.len().max(n_rows) {
+        let n = evals.get(poly).map_or(0, |e| e.as_ref().len());
+        let expected = if poly < n_rows { n_points } else { 0 };
+        if n != expected || poly >= n_rows {
+            return Err(Error::EvalsIncorrectSize { poly, n, expected });
+        }
+    }
+    Ok(())
+}
+
+/// Errors with [`Error::PolynomialTooLarge`] if any of `polys` has more than `max_coeffs`
+/// coefficients, so an oversized open fails before doing any work
+pub(crate) fn check_polys_fit<F>(
+    polys: &[impl AsRef<[F]>],
+    max_coeffs: usize,
+) -> Result<(), Error> {
+    match polys.iter().map(|p| p.as_ref().len()).max() {
+        Some(n_coeffs) if n_coeffs > max_coeffs => Err(Error::PolynomialTooLarge {
+            n_coeffs,
+            expected_max: max_coeffs,
+        }),
+        _ => Ok(()),
+    }
+}
+
+/// Computes $\sum_i c_i p_i$, erroring with [`Error::NoPolynomialsGiven`] if there are no
+/// polynomials and [`Error::EmptyPolynomials`] if they all have no coefficients
 pub(crate) fn linear_combination<F: Field>(
     polynomials: &[impl AsRef<[F]>],
     challenges: &[F],
-) -> Option<Vec<F>> {
-    polynomials
-        .as_ref()
+) -> Result<Vec<F>, Error> {
+    if polynomials.is_empty() {
+        return Err(Error::NoPolynomialsGiven);
+    }
+    if polynomials.iter().all(|p| p.as_ref().is_empty()) {
+        return Err(Error::EmptyPolynomials);
+    }
+    Ok(polynomials
         .iter()
         .zip(challenges.iter())
         .map(|(p, &c)| &DensePolynomial::from_coefficients_slice(p.as_ref()) * c)
-        .reduce(|x, y| x + y)?
-        .coeffs
-        .into()
+        .reduce(|x, y| x + y)
+        .ok_or(Error::NoPolynomialsGiven)?
+        .coeffs)
 }
 
+/// Computes $g^p$ for each of `powers`, with $g$ drawn from `rng`. The rng is only used to pick
+/// the generator, nothing is masked, so the same rng state always gives the same points. The
+/// projective coordinates are normalized to $Z = 1$, so even their in-memory representation
+/// only depends on the rng state and `powers`.
 pub(crate) fn gen_curve_powers_proj<G: ScalarMul + CurveGroup>(
     powers: &[G::ScalarField],
     rng: &mut impl RngCore,
 ) -> Vec<G> {
-    let g = G::rand(rng);
-    let window_size = FixedBase::get_mul_window_size(powers.len());
-    let scalar_size = G::ScalarField::MODULUS_BIT_SIZE as usize;
-    let g_table = FixedBase::get_window_table::<G>(scalar_size, window_size, g);
-    FixedBase::msm::<G>(scalar_size, window_size, &g_table, powers)
+    gen_curve_powers::<G>(powers, rng)
+        .into_iter()
+        .map(|p| p.into_group())
+        .collect()
 }
 
+/// Same as [`gen_curve_powers_proj`], but returns the affine points
 pub(crate) fn gen_curve_powers<G: ScalarMul + CurveGroup>(
     powers: &[G::ScalarField],
     rng: &mut impl RngCore,
 ) -> Vec<G::Affine> {
-    G::normalize_batch(&gen_curve_powers_proj(powers, rng))
+    gen_curve_powers_with_generator(powers, G::rand(rng))
+}
+
+/// Computes $g^p$ for each of `powers`, using no randomness at all
+pub(crate) fn gen_curve_powers_with_generator<G: ScalarMul + CurveGroup>(
+    powers: &[G::ScalarField],
+    g: G,
+) -> Vec<G::Affine> {
+    let window_size = FixedBase::get_mul_window_size(powers.len());
+    let scalar_size = G::ScalarField::MODULUS_BIT_SIZE as usize;
+    let g_table = FixedBase::get_window_table::<G>(scalar_size, window_size, g);
+    G::normalize_batch(&FixedBase::msm::<G>(
+        scalar_size,
+        window_size,
+        &g_table,
+        powers,
+    ))
 }
 
-pub(crate) fn get_field_size<F: Field + CanonicalSerialize>() -> usize {
+/// The number of bytes each scalar contributes to the transcript, its compressed serialized size
+pub fn get_field_size<F: Field + CanonicalSerialize>() -> usize {
     F::zero().serialized_size(Compress::Yes)
 }
 
+/// The version of the transcript layout, the first message every opening absorbs. A prover and
+/// verifier on incompatible versions derive different challenges, so verification fails rather
+/// than quietly using a different layout. This must be bumped whenever the order,
+/// labels, or encoding of anything absorbed into the transcript changes.
+pub const PROOF_FORMAT_VERSION: u8 = 3;
+
+pub(crate) fn transcribe_format_version(transcript: &mut Transcript, version: u8) {
+    transcript.append_message(b"pmp format version", &[version]);
+}
+
+/// Absorbed first by every method 1 opening, so its challenges differ from method 2's
+pub(crate) const METHOD1_TRANSCRIPT_TAG: &[u8] = b"PMP-M1";
+/// Absorbed first by every method 2 opening
+pub(crate) const METHOD2_TRANSCRIPT_TAG: &[u8] = b"PMP-M2";
+
+/// Absorbs the format version and then which method is opening, so a proof from one method can
+/// never be confused with, or replayed as, a proof from the other over the same inputs. Every
+/// opening starts with this.
+pub(crate) fn transcribe_header(transcript: &mut Transcript, tag: &[u8]) {
+    transcribe_format_version(transcript, PROOF_FORMAT_VERSION);
+    transcript.append_message(b"pmp method", tag);
+}
+
+/// Absorbs the evals and then the points into the transcript. Each scalar
+/// takes up `field_size_bytes` bytes, its compressed little endian serialization.
+///
+/// The evals are appended as a single message labeled `open evals`, laid out poly by poly, so
+/// eval `j` of poly `i` is at `(i * points.len() + j) * field_size_bytes`. The points follow as a
+/// single message labeled `open points`, in the order given.
 pub(crate) fn transcribe_points_and_evals<F: CanonicalSerialize>(
     transcript: &mut Transcript,
     points: &[F],
     evals: &[impl AsRef<[F]>],
     field_size_bytes: usize,
 ) -> Result<(), Error> {
+    if points.is_empty() {
+        return Err(Error::NoPointsGiven);
+    }
     let n_points = points.len();
     let mut eval_bytes = vec![0u8; field_size_bytes * n_points * evals.len()];
     for (i, e) in evals.iter().enumerate() {
@@ -175,11 +953,68 @@ pub(crate) fn transcribe_generic<F: CanonicalSerialize>(
     Ok(())
 }
 
+/// Absorbs the commitments into the transcript, so that challenges derived afterwards are bound
+/// to the commitments
+pub(crate) fn transcribe_commits<E: Pairing>(
+    transcript: &mut Transcript,
+    commits: &[Commitment<E>],
+) -> Result<(), Error> {
+    let mut commit_bytes = Vec::new();
+    for c in commits {
+        c.0.serialize_compressed(&mut commit_bytes)?;
+    }
+    transcript.append_message(b"open commits", &commit_bytes);
+    Ok(())
+}
+
+/// Absorbs the format version and then the commitments, for openings that start by binding
+/// their challenges to the commitments, so the version is still the first message
+pub(crate) fn transcribe_bound_commits<E: Pairing>(
+    transcript: &mut Transcript,
+    commits: &[Commitment<E>],
+) -> Result<(), Error> {
+    transcribe_format_version(transcript, PROOF_FORMAT_VERSION);
+    transcribe_commits(transcript, commits)
+}
+
+/// Absorbs the format version and then caller supplied context bytes, such as a session id or
+/// block height
+pub(crate) fn transcribe_context(transcript: &mut Transcript, context: &[u8]) {
+    transcribe_format_version(transcript, PROOF_FORMAT_VERSION);
+    transcript.append_message(b"open context", context);
+}
+
+/// Returns a digest of the transcript state after absorbing `points` and `evals`, without
+/// modifying `transcript`. Equal digests on the prover and verifier side mean they will derive
+/// the same challenges, so comparing them pins down input ordering mismatches.
+pub fn transcript_digest<F: Field + CanonicalSerialize>(
+    transcript: &Transcript,
+    points: &[F],
+    evals: &[impl AsRef<[F]>],
+) -> Result<[u8; 32], Error> {
+    let mut transcript = transcript.clone();
+    transcribe_points_and_evals(&mut transcript, points, evals, get_field_size::<F>())?;
+    Ok(transcript_state_digest(&transcript))
+}
+
+/// Returns a digest of the transcript's current state without modifying it
+pub fn transcript_state_digest(transcript: &Transcript) -> [u8; 32] {
+    let mut digest = [0u8; 32];
+    transcript.clone().challenge_bytes(b"digest", &mut digest);
+    digest
+}
+
+/// Squeezes `field_size_bytes` bytes from the transcript and reduces them, read as a big endian
+/// integer, mod the field order
 pub(crate) fn get_challenge<F: PrimeField>(
     transcript: &mut Transcript,
     label: &'static [u8],
     field_size_bytes: usize,
 ) -> F {
+    #[cfg(feature = "insecure-deterministic-transcript")]
+    if let Some(c) = deterministic::fixed_challenge(label) {
+        return c;
+    }
     let mut challenge_bytes = vec![0u8; field_size_bytes];
     transcript.challenge_bytes(label, &mut challenge_bytes);
     F::from_be_bytes_mod_order(&challenge_bytes)
@@ -197,3 +1032,352 @@ macro_rules! cfg_iter {
         result
     }};
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        combine_commitments, gen_powers, gen_powers_into, gen_powers_small, transcript_digest,
+        Commitment, Error,
+    };
+    use crate::test_rng;
+    use ark_bls12_381::{Bls12_381, Fr, G1Projective};
+    use ark_ec::CurveGroup;
+    use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, Compress, Validate};
+    use ark_std::{UniformRand, Zero};
+    use merlin::Transcript;
+
+    #[test]
+    fn test_commitment_serialization_modes() {
+        let c = Commitment::<Bls12_381>(G1Projective::rand(&mut test_rng()).into_affine());
+        for compress in [Compress::Yes, Compress::No] {
+            let mut bytes = Vec::new();
+            c.serialize_with_mode(&mut bytes, compress).unwrap();
+            assert_eq!(c.serialized_size(compress), bytes.len());
+            let read =
+                Commitment::<Bls12_381>::deserialize_with_mode(&bytes[..], compress, Validate::Yes)
+                    .unwrap();
+            assert_eq!(c.0, read.0);
+        }
+    }
+
+    #[cfg(feature = "test-utils")]
+    #[test]
+    fn test_seeded_rng_is_deterministic() {
+        let a = Fr::rand(&mut super::seeded_rng(42));
+        assert_eq!(a, Fr::rand(&mut super::seeded_rng(42)));
+        assert_ne!(a, Fr::rand(&mut super::seeded_rng(43)));
+    }
+
+    #[test]
+    fn test_commitment_zero() {
+        use crate::{
+            method1::M1NoPrecomp,
+            traits::{Committer, PolyMultiProofNoPrecomp},
+        };
+        let s = M1NoPrecomp::<Bls12_381>::new(8, None, &mut test_rng()).unwrap();
+        let c = s.commit(vec![Fr::zero(); 8]).unwrap();
+        assert!(c.is_zero());
+        assert_eq!(Commitment::<Bls12_381>::zero().0, c.0);
+        assert!(!s.commit(vec![Fr::from(1u64)]).unwrap().is_zero());
+    }
+
+    #[test]
+    fn test_coset_vanishing_polynomial() {
+        use ark_poly::{EvaluationDomain, GeneralEvaluationDomain};
+        let domain = GeneralEvaluationDomain::<Fr>::new(16).unwrap();
+        let coset = domain.get_coset(Fr::rand(&mut test_rng())).unwrap();
+        let points = coset.elements().collect::<Vec<_>>();
+        assert!(super::as_coset_domain(&points).is_some());
+        assert_eq!(
+            super::vanishing_polynomial_naive(&points),
+            super::vanishing_polynomial(&points)
+        );
+
+        let mut shuffled = points.clone();
+        shuffled.swap(1, 2);
+        assert!(super::as_coset_domain(&shuffled).is_none());
+        assert_eq!(
+            super::vanishing_polynomial_naive(&shuffled),
+            super::vanishing_polynomial(&shuffled)
+        );
+    }
+
+    #[test]
+    fn test_domain_vanishing_polynomial() {
+        use ark_poly::{EvaluationDomain, GeneralEvaluationDomain};
+        let domain = GeneralEvaluationDomain::<Fr>::new(32).unwrap();
+        let points = domain.elements().collect::<Vec<_>>();
+        assert_eq!(
+            super::vanishing_polynomial_naive(&points),
+            super::vanishing_polynomial_for_domain(&domain)
+        );
+        let coset = domain.get_coset(Fr::rand(&mut test_rng())).unwrap();
+        let points = coset.elements().collect::<Vec<_>>();
+        assert_eq!(
+            super::vanishing_polynomial_naive(&points),
+            super::vanishing_polynomial_for_domain(&coset)
+        );
+    }
+
+    #[test]
+    fn test_horner_matches_msm() {
+        let commits = (0..20)
+            .map(|_| Commitment::<Bls12_381>(G1Projective::rand(&mut test_rng()).into_affine()))
+            .collect::<Vec<_>>();
+        let gamma = Fr::rand(&mut test_rng());
+        for n in [1, 3, 7, 8, 20] {
+            let gammas = gen_powers(gamma, n);
+            let cms = commits.iter().map(|c| c.0).collect::<Vec<_>>();
+            let expected = crate::curve_msm::<G1Projective>(&cms, &gammas).unwrap();
+            assert_eq!(
+                expected,
+                super::horner_commit_combo(&commits, &gammas).unwrap()
+            );
+            assert_eq!(
+                expected,
+                super::gamma_commit_combo(&commits, &gammas).unwrap()
+            );
+        }
+        assert!(super::horner_commit_combo(&commits[..2], &gen_powers(gamma, 3)).is_err());
+    }
+
+    #[test]
+    fn test_msm_trims_trailing_zeros() {
+        let bases = (0..16)
+            .map(|_| G1Projective::rand(&mut test_rng()).into_affine())
+            .collect::<Vec<_>>();
+        let poly = (0..10)
+            .map(|_| Fr::rand(&mut test_rng()))
+            .collect::<Vec<_>>();
+        let mut padded = poly.clone();
+        padded.resize(16, Fr::zero());
+        assert_eq!(10, super::trim_zeros(&padded).len());
+        assert!(super::trim_zeros(&[Fr::zero(); 4]).is_empty());
+        assert_eq!(
+            crate::curve_msm::<G1Projective>(&bases, &poly).unwrap(),
+            crate::curve_msm::<G1Projective>(&bases, &padded).unwrap()
+        );
+        assert!(crate::curve_msm::<G1Projective>(&bases, &[Fr::zero(); 16])
+            .unwrap()
+            .is_zero());
+        // Trailing zeros still count towards the size limit
+        padded.push(Fr::zero());
+        assert!(crate::curve_msm::<G1Projective>(&bases, &padded).is_err());
+    }
+
+    #[test]
+    fn test_display_hex() {
+        let c = Commitment::<Bls12_381>(G1Projective::rand(&mut test_rng()).into_affine());
+        let mut bytes = Vec::new();
+        c.serialize_compressed(&mut bytes).unwrap();
+        let hex = bytes.iter().map(|b| format!("{b:02x}")).collect::<String>();
+        assert_eq!(format!("Commitment(0x{hex})"), c.to_string());
+        assert_eq!(48 * 2 + 14, c.to_string().len());
+    }
+
+    #[test]
+    fn test_curve_powers_deterministic() {
+        use ark_ec::Group;
+        use rand::{rngs::StdRng, SeedableRng};
+        let powers = gen_powers(Fr::from(7u64), 20);
+        let gen = |seed| {
+            super::gen_curve_powers_proj::<G1Projective>(&powers, &mut StdRng::seed_from_u64(seed))
+        };
+        let (a, b, c) = (gen(1), gen(1), gen(2));
+        assert_ne!(a, c);
+        for (a, b) in a.iter().zip(&b) {
+            // Compare coordinates, since == on projective points ignores the representation
+            assert_eq!((a.x, a.y, a.z), (b.x, b.y, b.z));
+            assert!(a.z == ark_bls12_381::Fq::from(1u64));
+        }
+
+        let g = G1Projective::generator();
+        let expected = powers
+            .iter()
+            .map(|p| (g * p).into_affine())
+            .collect::<Vec<_>>();
+        assert_eq!(expected, super::gen_curve_powers_with_generator(&powers, g));
+    }
+
+    #[test]
+    fn test_poly_div_q_r() {
+        use ark_poly::{univariate::DensePolynomial, DenseUVPolynomial};
+        let num = DensePolynomial::<Fr>::rand(10, &mut test_rng());
+        let denom = DensePolynomial::<Fr>::rand(3, &mut test_rng());
+        let (q, r) = super::poly_div_q_r((&num).into(), (&denom).into()).unwrap();
+        assert!(r.len() <= 3);
+        let q = DensePolynomial::from_coefficients_vec(q);
+        let r = DensePolynomial::from_coefficients_vec(r);
+        assert_eq!(num, &(&q * &denom) + &r);
+
+        // Higher degree divisor gives a zero quotient
+        let (q, r) = super::poly_div_q_r((&denom).into(), (&num).into()).unwrap();
+        assert!(q.is_empty());
+        assert_eq!(denom.coeffs, r);
+
+        assert_eq!(
+            Err(Error::DivisorIsZero),
+            super::poly_div_q_r((&num).into(), (&DensePolynomial::zero()).into())
+        );
+    }
+
+    #[test]
+    fn test_div_by_linear() {
+        use ark_poly::{univariate::DensePolynomial, DenseUVPolynomial};
+        let num = DensePolynomial::<Fr>::rand(10, &mut test_rng());
+        let z = Fr::rand(&mut test_rng());
+        let denom = DensePolynomial::from_coefficients_vec(vec![-z, Fr::from(1u64)]);
+        let (q, _) = super::poly_div_q_r((&num).into(), (&denom).into()).unwrap();
+        assert_eq!(q, super::div_by_linear(&num.coeffs, z));
+        assert!(super::div_by_linear(&[Fr::from(3u64)], z).is_empty());
+        assert!(super::div_by_linear::<Fr>(&[], z).is_empty());
+    }
+
+    #[test]
+    fn test_method_tag_changes_challenges() {
+        let mut a = Transcript::new(b"testing");
+        let mut b = Transcript::new(b"testing");
+        super::transcribe_header(&mut a, super::METHOD1_TRANSCRIPT_TAG);
+        super::transcribe_header(&mut b, super::METHOD2_TRANSCRIPT_TAG);
+        let field_size = super::get_field_size::<Fr>();
+        assert_ne!(
+            super::get_challenge::<Fr>(&mut a, b"open gamma", field_size),
+            super::get_challenge::<Fr>(&mut b, b"open gamma", field_size)
+        );
+    }
+
+    #[test]
+    fn test_format_version_changes_challenges() {
+        let mut a = Transcript::new(b"testing");
+        let mut b = Transcript::new(b"testing");
+        super::transcribe_format_version(&mut a, super::PROOF_FORMAT_VERSION);
+        super::transcribe_format_version(&mut b, super::PROOF_FORMAT_VERSION + 1);
+        let field_size = super::get_field_size::<Fr>();
+        assert_ne!(
+            super::get_challenge::<Fr>(&mut a, b"open gamma", field_size),
+            super::get_challenge::<Fr>(&mut b, b"open gamma", field_size)
+        );
+    }
+
+    #[test]
+    fn test_transcript_digest_detects_reordering() {
+        let points = (0..4)
+            .map(|_| Fr::rand(&mut test_rng()))
+            .collect::<Vec<_>>();
+        let evals = vec![(0..4)
+            .map(|_| Fr::rand(&mut test_rng()))
+            .collect::<Vec<_>>()];
+        let transcript = Transcript::new(b"testing");
+        let digest = transcript_digest(&transcript, &points, &evals).unwrap();
+        assert_eq!(
+            digest,
+            transcript_digest(&Transcript::new(b"testing"), &points, &evals).unwrap()
+        );
+
+        let mut swapped = points.clone();
+        swapped.swap(0, 1);
+        assert_ne!(
+            digest,
+            transcript_digest(&transcript, &swapped, &evals).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_gen_powers_into_reuses_buffer() {
+        let x = Fr::rand(&mut test_rng());
+        let mut buf = vec![Fr::rand(&mut test_rng()); 40];
+        gen_powers_into(x, 10, &mut buf);
+        assert_eq!(gen_powers(x, 10), buf);
+        assert_eq!(x * x * x, buf[3]);
+        assert_eq!(
+            gen_powers(x, 40).as_slice(),
+            gen_powers_small(x, 40).as_slice()
+        );
+    }
+
+    #[test]
+    fn test_combine_commitments_matches_fold() {
+        let commits = (0..10)
+            .map(|_| Commitment::<Bls12_381>(G1Projective::rand(&mut test_rng()).into_affine()))
+            .collect::<Vec<_>>();
+        let weights = (0..10)
+            .map(|_| Fr::rand(&mut test_rng()))
+            .collect::<Vec<_>>();
+        let expected = commits
+            .iter()
+            .zip(weights.iter())
+            .fold(G1Projective::zero(), |acc, (c, w)| acc + c.0 * w);
+        let combined = combine_commitments(&commits, &weights).unwrap();
+        assert_eq!(expected.into_affine(), combined.0);
+        assert_eq!(
+            Err(Error::WeightsIncorrectSize { n: 9, expected: 10 }),
+            combine_commitments(&commits, &weights[..9]).map(|c| c.0)
+        );
+    }
+
+    #[test]
+    fn test_commitment_homomorphism() {
+        use crate::{
+            method1::M1NoPrecomp,
+            traits::{Committer, PolyMultiProofNoPrecomp},
+        };
+        let s = M1NoPrecomp::<Bls12_381>::new(32, None, &mut test_rng()).unwrap();
+        let polys = (0..5)
+            .map(|_| {
+                (0..32)
+                    .map(|_| Fr::rand(&mut test_rng()))
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        let weights = (0..5)
+            .map(|_| Fr::rand(&mut test_rng()))
+            .collect::<Vec<_>>();
+        let commits = s.commit_many(&polys).unwrap();
+
+        let combined_poly = super::linear_combination(&polys, &weights).unwrap();
+        assert_eq!(
+            s.commit(&combined_poly).unwrap().0,
+            combine_commitments(&commits, &weights).unwrap().0
+        );
+
+        let sum = polys[0]
+            .iter()
+            .zip(&polys[1])
+            .map(|(a, b)| *a + b)
+            .collect::<Vec<_>>();
+        assert_eq!(s.commit(&sum).unwrap().0, (&commits[0] + &commits[1]).0);
+        let scaled = polys[2].iter().map(|a| *a * weights[2]).collect::<Vec<_>>();
+        assert_eq!(s.commit(&scaled).unwrap().0, commits[2].scale(weights[2]).0);
+        assert!((commits[3].scale(-Fr::from(1u64)) + Commitment(commits[3].0)).is_zero());
+    }
+
+    #[test]
+    fn test_from_compressed_bytes_rejects_wrong_subgroup() {
+        let bad = crate::test_helpers::non_subgroup_g1();
+        assert!(bad.is_on_curve());
+        let mut bytes = Vec::new();
+        bad.serialize_compressed(&mut bytes).unwrap();
+        assert_eq!(
+            Err(Error::InvalidPoint),
+            Commitment::<Bls12_381>::from_compressed_bytes(&bytes).map(|c| c.0)
+        );
+        assert_eq!(
+            Err(Error::InvalidPoint),
+            crate::method1::Proof::<Bls12_381>::from_compressed_bytes(&bytes)
+                .map(|p| p.quotient_commitment())
+        );
+
+        let good = G1Projective::rand(&mut test_rng()).into_affine();
+        let mut bytes = Vec::new();
+        good.serialize_compressed(&mut bytes).unwrap();
+        assert_eq!(
+            Ok(good),
+            Commitment::<Bls12_381>::from_compressed_bytes(&bytes).map(|c| c.0)
+        );
+        assert_eq!(
+            Ok(good),
+            crate::method1::Proof::<Bls12_381>::from_compressed_bytes(&bytes)
+                .map(|p| p.quotient_commitment())
+        );
+    }
+}
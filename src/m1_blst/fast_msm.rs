@@ -1,21 +1,48 @@
-use ark_ff::BigInt;
+use ark_ff::{BigInt, PrimeField, Zero};
 use ark_serialize::CanonicalSerialize;
 use blst::{
-    blst_fp, blst_fp2, blst_p1, blst_p1_affine, blst_p1_from_affine, blst_p1_mult,
-    blst_p2, blst_p2_affine, blst_p2_from_affine, blst_p2_mult, p1_affines, p2_affines,
+    blst_fp, blst_fp2, blst_p1, blst_p1_add_or_double, blst_p1_affine, blst_p1_from_affine,
+    blst_p1_mult, blst_p1s_mult_pippenger, blst_p1s_mult_pippenger_scratch_sizeof,
+    blst_p1s_mult_wbits, blst_p1s_mult_wbits_precompute, blst_p1s_mult_wbits_precompute_sizeof,
+    blst_p1s_mult_wbits_scratch_sizeof, blst_p2, blst_p2_add_or_double, blst_p2_affine,
+    blst_p2_from_affine, blst_p2_mult, blst_p2s_mult_pippenger,
+    blst_p2s_mult_pippenger_scratch_sizeof, limb_t, p1_affines, p2_affines,
 };
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
 use std::marker::PhantomData;
 
-use crate::Error;
+use crate::{msm::MsmBackend, Error};
+#[cfg(debug_assertions)]
+use ark_ec::CurveGroup;
 
+/// Above this many scalars, g1 msms are split into chunks that run on separate threads
+#[cfg(feature = "parallel")]
+const PARALLEL_MSM_THRESHOLD: usize = 1 << 12;
+
+/// The bit length of the scalars blst multiplies by, that of [`ark_bls12_381::Fr`]. This isn't a
+/// window size: blst picks its pippenger window from the number of points, and passing fewer bits
+/// would only use the low bits of each scalar. The window of a fixed base [`G1Table`] can be set.
+const SCALAR_BITS: usize = 255;
+
+/// arkworks and blst both use jacobian coordinates over the same montgomery representation, so
+/// points convert limb by limb. The identity is mapped to blst's all zero point explicitly rather
+/// than relying on both treating any `z = 0` as infinity.
 fn convert_g1(p: &ark_bls12_381::G1Projective) -> blst_p1 {
+    if p.is_zero() {
+        return blst_p1::default();
+    }
     let x = blst_fp { l: p.x.0 .0 };
     let y = blst_fp { l: p.y.0 .0 };
     let z = blst_fp { l: p.z.0 .0 };
     blst_p1 { x, y, z }
 }
 
+/// Same as [`convert_g1`] in G2
 fn convert_g2(p: &ark_bls12_381::G2Projective) -> blst_p2 {
+    if p.is_zero() {
+        return blst_p2::default();
+    }
     let x = blst_fp2 {
         fp: [blst_fp { l: p.x.c0.0 .0 }, blst_fp { l: p.x.c1.0 .0 }],
     };
@@ -44,14 +71,61 @@ pub(crate) fn prep_g2s(points: &[ark_bls12_381::G2Projective]) -> p2_affines {
     p2_affines::from(&convert_g2_slice(points))
 }
 
-fn prep_scalars(scalars: &[ark_bls12_381::Fr]) -> Vec<u8> {
-    let mut scalars_le = vec![0u8; 32 * scalars.len()];
+/// blst and arkworks share the montgomery representation, so affine points convert directly.
+/// blst represents the point at infinity as all zeros.
+fn convert_g1_affine(p: &ark_bls12_381::G1Affine) -> blst_p1_affine {
+    if p.infinity {
+        return blst_p1_affine::default();
+    }
+    blst_p1_affine {
+        x: blst_fp { l: p.x.0 .0 },
+        y: blst_fp { l: p.y.0 .0 },
+    }
+}
+
+fn convert_g2_affine(p: &ark_bls12_381::G2Affine) -> blst_p2_affine {
+    if p.infinity {
+        return blst_p2_affine::default();
+    }
+    blst_p2_affine {
+        x: blst_fp2 {
+            fp: [blst_fp { l: p.x.c0.0 .0 }, blst_fp { l: p.x.c1.0 .0 }],
+        },
+        y: blst_fp2 {
+            fp: [blst_fp { l: p.y.c0.0 .0 }, blst_fp { l: p.y.c1.0 .0 }],
+        },
+    }
+}
+
+/// Same as [`prep_g1s`], but skips the batch normalization since the points are already affine
+pub(crate) fn prep_g1s_affine(points: &[ark_bls12_381::G1Affine]) -> p1_affines {
+    p1_affines {
+        points: points.iter().map(convert_g1_affine).collect(),
+    }
+}
+
+/// Same as [`prep_g2s`], but skips the batch normalization since the points are already affine
+pub(crate) fn prep_g2s_affine(points: &[ark_bls12_381::G2Affine]) -> p2_affines {
+    p2_affines {
+        points: points.iter().map(convert_g2_affine).collect(),
+    }
+}
+
+pub(crate) fn prep_scalars(scalars: &[ark_bls12_381::Fr]) -> Vec<u8> {
+    let mut scalars_le = Vec::new();
+    prep_scalars_into(scalars, &mut scalars_le);
+    scalars_le
+}
+
+/// Same as [`prep_scalars`], but writes into `scalars_le`, reusing its allocation
+fn prep_scalars_into(scalars: &[ark_bls12_381::Fr], scalars_le: &mut Vec<u8>) {
+    scalars_le.clear();
+    scalars_le.resize(32 * scalars.len(), 0);
     for (i, s) in scalars.iter().enumerate() {
         // This _must_ be little endian bytes for this to work
         s.serialize_compressed(&mut scalars_le[i * 32..(i + 1) * 32])
             .unwrap();
     }
-    scalars_le
 }
 
 pub(crate) fn g1_msm(
@@ -59,14 +133,58 @@ pub(crate) fn g1_msm(
     scalars: &[ark_bls12_381::Fr],
     g1s_len: usize,
 ) -> Result<ark_bls12_381::G1Projective, Error> {
-    if g1s_len < scalars.len() / 32 {
+    g1_msm_with_buffer(g1s, scalars, g1s_len, &mut Vec::new(), None)
+}
+
+/// Same as [`g1_msm`], but serializes the scalars into `scalars_le`, so msms over many rows can
+/// share one buffer instead of allocating one per row. With `nthreads`, the msm runs on at most
+/// that many threads instead of blst's pool of one per core.
+pub(crate) fn g1_msm_with_buffer(
+    g1s: &p1_affines,
+    scalars: &[ark_bls12_381::Fr],
+    g1s_len: usize,
+    scalars_le: &mut Vec<u8>,
+    nthreads: Option<usize>,
+) -> Result<ark_bls12_381::G1Projective, Error> {
+    if g1s_len < scalars.len() {
         return Err(Error::PolynomialTooLarge {
-            n_coeffs: scalars.len() / 32,
+            n_coeffs: scalars.len(),
             expected_max: g1s_len,
         });
     }
-    let scalars_le = prep_scalars(&scalars);
-    let res_p1 = if scalars.len() == 1 {
+    let scalars = crate::trim_zeros(scalars);
+    if scalars.is_empty() {
+        return Ok(ark_bls12_381::G1Projective::zero());
+    }
+    prep_scalars_into(scalars, scalars_le);
+    g1_msm_prepped(g1s, scalars_le, nthreads)
+}
+
+/// The msm of the first `scalars_le.len() / 32` points with scalars already serialized by
+/// [`prep_scalars`], which must be nonempty and fit in the points
+fn g1_msm_prepped(
+    g1s: &p1_affines,
+    scalars_le: &[u8],
+    nthreads: Option<usize>,
+) -> Result<ark_bls12_381::G1Projective, Error> {
+    let n_scalars = scalars_le.len() / 32;
+    if let Some(nthreads) = nthreads.filter(|_| n_scalars > 1) {
+        return check_g1(convert_p1_back(&msm_on_threads(
+            &g1s.points[..n_scalars],
+            scalars_le,
+            nthreads,
+            g1_pippenger,
+            add_p1,
+        )));
+    }
+    #[cfg(feature = "parallel")]
+    if n_scalars >= PARALLEL_MSM_THRESHOLD {
+        return check_g1(convert_p1_back(&g1_msm_chunked(
+            &g1s.points[..n_scalars],
+            scalars_le,
+        )));
+    }
+    let res_p1 = if n_scalars == 1 {
         let pt_affine = g1s.points[0];
         let mut out = blst_p1::default();
         let mut pt = blst_p1::default();
@@ -76,18 +194,350 @@ pub(crate) fn g1_msm(
                 &mut out as *mut blst_p1,
                 &pt as *const blst_p1,
                 scalars_le.as_ptr(),
-                255,
+                SCALAR_BITS,
             )
         }
         out
     } else {
-        g1s.mult(&scalars_le, 255)
+        g1s.mult(scalars_le, SCALAR_BITS)
     };
-    Ok(ark_bls12_381::G1Projective {
-        x: ark_ff::Fp(BigInt(res_p1.x.l), PhantomData),
-        y: ark_ff::Fp(BigInt(res_p1.y.l), PhantomData),
-        z: ark_ff::Fp(BigInt(res_p1.z.l), PhantomData),
-    })
+    check_g1(convert_p1_back(&res_p1))
+}
+
+/// The largest window size [`G1Table`] accepts
+pub(crate) const MAX_WBITS: usize = 12;
+
+/// A fixed base table of the multiples of each g1 power, $[1, 2^{w-1}] \cdot P$ for window size
+/// $w$, so msms against the powers don't need to build buckets. It holds $2^{w-1}$ affine points,
+/// 96 bytes each, per power.
+#[derive(Clone)]
+pub(crate) struct G1Table {
+    wbits: usize,
+    npoints: usize,
+    table: Vec<blst_p1_affine>,
+}
+
+impl G1Table {
+    pub(crate) fn new(points: &p1_affines, wbits: usize) -> Self {
+        let npoints = points.points.len();
+        let p: [*const blst_p1_affine; 2] = [points.points.as_ptr(), std::ptr::null()];
+        unsafe {
+            let table_len = blst_p1s_mult_wbits_precompute_sizeof(wbits, npoints)
+                / std::mem::size_of::<blst_p1_affine>();
+            let mut table = vec![blst_p1_affine::default(); table_len];
+            blst_p1s_mult_wbits_precompute(table.as_mut_ptr(), wbits, p.as_ptr(), npoints);
+            Self {
+                wbits,
+                npoints,
+                table,
+            }
+        }
+    }
+
+    /// Same as [`g1_msm_with_buffer`] against the points the table was built from. The table is
+    /// laid out point by point, so a prefix of the points uses a prefix of the table.
+    pub(crate) fn msm(
+        &self,
+        scalars: &[ark_bls12_381::Fr],
+        scalars_le: &mut Vec<u8>,
+    ) -> Result<ark_bls12_381::G1Projective, Error> {
+        if self.npoints < scalars.len() {
+            return Err(Error::PolynomialTooLarge {
+                n_coeffs: scalars.len(),
+                expected_max: self.npoints,
+            });
+        }
+        let scalars = crate::trim_zeros(scalars);
+        if scalars.is_empty() {
+            return Ok(ark_bls12_381::G1Projective::zero());
+        }
+        prep_scalars_into(scalars, scalars_le);
+        self.msm_prepped(scalars_le)
+    }
+
+    /// Same as [`G1Table::msm`], with the scalars already serialized by [`prep_scalars`]. They
+    /// must be nonempty and fit in the table.
+    fn msm_prepped(&self, scalars_le: &[u8]) -> Result<ark_bls12_381::G1Projective, Error> {
+        let npoints = scalars_le.len() / 32;
+        let s: [*const u8; 2] = [scalars_le.as_ptr(), std::ptr::null()];
+        let mut out = blst_p1::default();
+        unsafe {
+            let mut scratch = scratch_limbs(blst_p1s_mult_wbits_scratch_sizeof(npoints));
+            blst_p1s_mult_wbits(
+                &mut out,
+                self.table.as_ptr(),
+                self.wbits,
+                npoints,
+                s.as_ptr(),
+                SCALAR_BITS,
+                scratch.as_mut_ptr(),
+            );
+        }
+        check_g1(convert_p1_back(&out))
+    }
+}
+
+/// The powers prepped for blst, and a fixed base table of the g1 powers if one was built
+pub(crate) struct BlstMsm {
+    g1s: p1_affines,
+    g2s: p2_affines,
+    g1_table: Option<G1Table>,
+    /// The most threads one msm may use, or `None` for blst's pool of one thread per core
+    nthreads: Option<usize>,
+}
+
+impl BlstMsm {
+    pub(crate) fn new(g1s: p1_affines, g2s: p2_affines) -> Self {
+        Self {
+            g1s,
+            g2s,
+            g1_table: None,
+            nthreads: None,
+        }
+    }
+
+    /// Builds a [`G1Table`] with window size `wbits` for the g1 msms
+    pub(crate) fn with_table(mut self, wbits: usize) -> Self {
+        self.g1_table = Some(G1Table::new(&self.g1s, wbits));
+        self
+    }
+
+    /// Caps every msm at `nthreads` threads, with 0 treated as 1. Msms over many rows then run
+    /// the rows one after another, so the cap also holds for `commit_many`.
+    pub(crate) fn with_threads(mut self, nthreads: usize) -> Self {
+        self.nthreads = Some(nthreads.max(1));
+        self
+    }
+
+    fn msm_g1_with_buffer(
+        &self,
+        scalars: &[ark_bls12_381::Fr],
+        scalars_le: &mut Vec<u8>,
+    ) -> Result<ark_bls12_381::G1Projective, Error> {
+        match &self.g1_table {
+            Some(table) => table.msm(scalars, scalars_le),
+            None => g1_msm_with_buffer(
+                &self.g1s,
+                scalars,
+                self.g1s.points.len(),
+                scalars_le,
+                self.nthreads,
+            ),
+        }
+    }
+}
+
+impl MsmBackend<ark_bls12_381::Bls12_381> for BlstMsm {
+    fn msm_g1(&self, scalars: &[ark_bls12_381::Fr]) -> Result<ark_bls12_381::G1Projective, Error> {
+        self.msm_g1_with_buffer(scalars, &mut Vec::new())
+    }
+
+    fn msm_g2(&self, scalars: &[ark_bls12_381::Fr]) -> Result<ark_bls12_381::G2Projective, Error> {
+        g2_msm_with_threads(&self.g2s, scalars, self.g2s.points.len(), self.nthreads)
+    }
+
+    /// Hands the bytes straight to blst, which takes the same little endian encoding. blst would
+    /// drop the top bit of a scalar that isn't below the modulus, so those error with
+    /// [`Error::SerializationError`] here as they do when deserializing.
+    fn msm_g1_serialized(&self, scalars: &[u8]) -> Result<ark_bls12_381::G1Projective, Error> {
+        if scalars.len() % 32 != 0 || !scalars.chunks(32).all(is_canonical) {
+            return Err(Error::SerializationError);
+        }
+        let n_scalars = scalars.len() / 32;
+        if n_scalars > self.g1s.points.len() {
+            return Err(Error::PolynomialTooLarge {
+                n_coeffs: n_scalars,
+                expected_max: self.g1s.points.len(),
+            });
+        }
+        // Trailing zero scalars contribute nothing, and blst expects nonzero top scalars
+        let trimmed = scalars
+            .chunks(32)
+            .rposition(|c| c.iter().any(|b| *b != 0))
+            .map_or(0, |i| i + 1);
+        if trimmed == 0 {
+            return Ok(ark_bls12_381::G1Projective::zero());
+        }
+        let scalars = &scalars[..32 * trimmed];
+        match &self.g1_table {
+            Some(table) => table.msm_prepped(scalars),
+            None => g1_msm_prepped(&self.g1s, scalars, self.nthreads),
+        }
+    }
+
+    fn msm_g1_with_bases(
+        &self,
+        bases: &[ark_bls12_381::G1Affine],
+        scalars: &[ark_bls12_381::Fr],
+    ) -> Result<ark_bls12_381::G1Projective, Error> {
+        g1_msm_with_buffer(
+            &prep_g1s_affine(bases),
+            scalars,
+            bases.len(),
+            &mut Vec::new(),
+            self.nthreads,
+        )
+    }
+
+    /// Serializes every row's scalars into one reused buffer, one per thread with the `parallel`
+    /// feature, instead of allocating a buffer per row
+    fn msm_g1_many(
+        &self,
+        rows: &[&[ark_bls12_381::Fr]],
+    ) -> Result<Vec<ark_bls12_381::G1Projective>, Error> {
+        #[cfg(feature = "parallel")]
+        if self.nthreads.is_none() {
+            return rows
+                .par_iter()
+                .map_init(Vec::new, |buf, row| self.msm_g1_with_buffer(row, buf))
+                .collect();
+        }
+        let mut buf = Vec::new();
+        rows.iter()
+            .map(|row| self.msm_g1_with_buffer(row, &mut buf))
+            .collect()
+    }
+}
+
+/// blst's infinity is any point with `z = 0`, which becomes arkworks' canonical identity
+/// Whether the 32 little endian bytes of a scalar are below the modulus of
+/// [`ark_bls12_381::Fr`], i.e. are its canonical encoding
+fn is_canonical(scalar_le: &[u8]) -> bool {
+    let mut limbs = [0u64; 4];
+    for (limb, bytes) in limbs.iter_mut().zip(scalar_le.chunks(8)) {
+        *limb = u64::from_le_bytes(bytes.try_into().expect("chunk is 8 bytes"));
+    }
+    BigInt(limbs) < ark_bls12_381::Fr::MODULUS
+}
+
+fn convert_p1_back(p: &blst_p1) -> ark_bls12_381::G1Projective {
+    if p.z.l.iter().all(|l| *l == 0) {
+        return ark_bls12_381::G1Projective::zero();
+    }
+    ark_bls12_381::G1Projective {
+        x: ark_ff::Fp(BigInt(p.x.l), PhantomData),
+        y: ark_ff::Fp(BigInt(p.y.l), PhantomData),
+        z: ark_ff::Fp(BigInt(p.z.l), PhantomData),
+    }
+}
+
+/// The results are built straight from blst's limbs, assuming they're laid out like arkworks'
+/// montgomery form. Debug builds check that they're on the curve, so if that ever stops holding
+/// it shows up as an error instead of silently wrong points.
+fn check_g1(p: ark_bls12_381::G1Projective) -> Result<ark_bls12_381::G1Projective, Error> {
+    #[cfg(debug_assertions)]
+    if !p.into_affine().is_on_curve() {
+        return Err(Error::MsmBackendInconsistency);
+    }
+    Ok(p)
+}
+
+/// Same as [`check_g1`] in G2
+fn check_g2(p: ark_bls12_381::G2Projective) -> Result<ark_bls12_381::G2Projective, Error> {
+    #[cfg(debug_assertions)]
+    if !p.into_affine().is_on_curve() {
+        return Err(Error::MsmBackendInconsistency);
+    }
+    Ok(p)
+}
+
+/// Splits the msm into one chunk per thread, runs each chunk with pippenger, and sums the
+/// partial results
+#[cfg(feature = "parallel")]
+fn g1_msm_chunked(points: &[blst_p1_affine], scalars_le: &[u8]) -> blst_p1 {
+    let n_threads = rayon::current_num_threads();
+    let chunk_len = (points.len() + n_threads - 1) / n_threads;
+    points
+        .par_chunks(chunk_len)
+        .zip(scalars_le.par_chunks(chunk_len * 32))
+        .map(|(pts, scs)| g1_pippenger(pts, scs))
+        .reduce(blst_p1::default, |a, b| add_p1(&a, &b))
+}
+
+fn add_p1(a: &blst_p1, b: &blst_p1) -> blst_p1 {
+    let mut out = blst_p1::default();
+    unsafe { blst_p1_add_or_double(&mut out, a, b) };
+    out
+}
+
+fn add_p2(a: &blst_p2, b: &blst_p2) -> blst_p2 {
+    let mut out = blst_p2::default();
+    unsafe { blst_p2_add_or_double(&mut out, a, b) };
+    out
+}
+
+/// Splits the msm into at most `nthreads` chunks, runs `msm` on each chunk, and sums the partial
+/// results with `add`. The chunks run on rayon's pool with the `parallel` feature, so at most
+/// `nthreads` of its threads work on one msm, and one after another without it.
+fn msm_on_threads<P: Send, A: Sync>(
+    points: &[A],
+    scalars_le: &[u8],
+    nthreads: usize,
+    msm: fn(&[A], &[u8]) -> P,
+    add: fn(&P, &P) -> P,
+) -> P {
+    let chunk_len = (points.len() + nthreads - 1) / nthreads;
+    #[cfg(feature = "parallel")]
+    let sum = points
+        .par_chunks(chunk_len)
+        .zip(scalars_le.par_chunks(chunk_len * 32))
+        .map(|(pts, scs)| msm(pts, scs))
+        .reduce_with(|a, b| add(&a, &b));
+    #[cfg(not(feature = "parallel"))]
+    let sum = points
+        .chunks(chunk_len)
+        .zip(scalars_le.chunks(chunk_len * 32))
+        .map(|(pts, scs)| msm(pts, scs))
+        .reduce(|a, b| add(&a, &b));
+    sum.expect("msm must have points")
+}
+
+/// A zeroed scratch buffer of at least `bytes` bytes, as returned by blst's `*_scratch_sizeof`.
+/// `limb_t` is 32 bits on some targets, so the length can't assume 8 byte limbs.
+fn scratch_limbs(bytes: usize) -> Vec<limb_t> {
+    let limb_size = std::mem::size_of::<limb_t>();
+    vec![0 as limb_t; (bytes + limb_size - 1) / limb_size]
+}
+
+/// A single threaded pippenger msm over contiguous points and little endian scalars
+fn g1_pippenger(points: &[blst_p1_affine], scalars_le: &[u8]) -> blst_p1 {
+    let npoints = points.len();
+    // Null terminated pointer lists tell blst the points and scalars are contiguous
+    let p: [*const blst_p1_affine; 2] = [points.as_ptr(), std::ptr::null()];
+    let s: [*const u8; 2] = [scalars_le.as_ptr(), std::ptr::null()];
+    let mut out = blst_p1::default();
+    unsafe {
+        let mut scratch = scratch_limbs(blst_p1s_mult_pippenger_scratch_sizeof(npoints));
+        blst_p1s_mult_pippenger(
+            &mut out,
+            p.as_ptr(),
+            npoints,
+            s.as_ptr(),
+            SCALAR_BITS,
+            scratch.as_mut_ptr(),
+        );
+    }
+    out
+}
+
+/// Same as [`g1_pippenger`] in G2
+fn g2_pippenger(points: &[blst_p2_affine], scalars_le: &[u8]) -> blst_p2 {
+    let npoints = points.len();
+    let p: [*const blst_p2_affine; 2] = [points.as_ptr(), std::ptr::null()];
+    let s: [*const u8; 2] = [scalars_le.as_ptr(), std::ptr::null()];
+    let mut out = blst_p2::default();
+    unsafe {
+        let mut scratch = scratch_limbs(blst_p2s_mult_pippenger_scratch_sizeof(npoints));
+        blst_p2s_mult_pippenger(
+            &mut out,
+            p.as_ptr(),
+            npoints,
+            s.as_ptr(),
+            SCALAR_BITS,
+            scratch.as_mut_ptr(),
+        );
+    }
+    out
 }
 
 pub(crate) fn g2_msm(
@@ -95,13 +545,27 @@ pub(crate) fn g2_msm(
     scalars: &[ark_bls12_381::Fr],
     g2s_len: usize,
 ) -> Result<ark_bls12_381::G2Projective, Error> {
-    if g2s_len < scalars.len() / 32 {
+    g2_msm_with_threads(g2s, scalars, g2s_len, None)
+}
+
+/// Same as [`g2_msm`], on at most `nthreads` threads if given
+pub(crate) fn g2_msm_with_threads(
+    g2s: &p2_affines,
+    scalars: &[ark_bls12_381::Fr],
+    g2s_len: usize,
+    nthreads: Option<usize>,
+) -> Result<ark_bls12_381::G2Projective, Error> {
+    if g2s_len < scalars.len() {
         return Err(Error::PolynomialTooLarge {
-            n_coeffs: scalars.len() / 32,
+            n_coeffs: scalars.len(),
             expected_max: g2s_len,
         });
     }
-    let scalars_le = prep_scalars(&scalars);
+    let scalars = crate::trim_zeros(scalars);
+    if scalars.is_empty() {
+        return Ok(ark_bls12_381::G2Projective::zero());
+    }
+    let scalars_le = prep_scalars(scalars);
     let res_p2 = if scalars.len() == 1 {
         let pt_affine = g2s.points[0];
         let mut out = blst_p2::default();
@@ -112,27 +576,43 @@ pub(crate) fn g2_msm(
                 &mut out as *mut blst_p2,
                 &pt as *const blst_p2,
                 scalars_le.as_ptr(),
-                255,
+                SCALAR_BITS,
             )
         }
         out
+    } else if let Some(nthreads) = nthreads {
+        msm_on_threads(
+            &g2s.points[..scalars.len()],
+            &scalars_le,
+            nthreads,
+            g2_pippenger,
+            add_p2,
+        )
     } else {
-        g2s.mult(&scalars_le, 255)
+        g2s.mult(&scalars_le, SCALAR_BITS)
     };
-    Ok(ark_bls12_381::G2Projective {
+    check_g2(convert_p2_back(&res_p2))
+}
+
+/// Same as [`convert_p1_back`] in G2
+fn convert_p2_back(p: &blst_p2) -> ark_bls12_381::G2Projective {
+    if p.z.fp.iter().all(|c| c.l.iter().all(|l| *l == 0)) {
+        return ark_bls12_381::G2Projective::zero();
+    }
+    ark_bls12_381::G2Projective {
         x: ark_ff::QuadExtField {
-            c0: ark_ff::Fp(BigInt(res_p2.x.fp[0].l), PhantomData),
-            c1: ark_ff::Fp(BigInt(res_p2.x.fp[1].l), PhantomData),
+            c0: ark_ff::Fp(BigInt(p.x.fp[0].l), PhantomData),
+            c1: ark_ff::Fp(BigInt(p.x.fp[1].l), PhantomData),
         },
         y: ark_ff::QuadExtField {
-            c0: ark_ff::Fp(BigInt(res_p2.y.fp[0].l), PhantomData),
-            c1: ark_ff::Fp(BigInt(res_p2.y.fp[1].l), PhantomData),
+            c0: ark_ff::Fp(BigInt(p.y.fp[0].l), PhantomData),
+            c1: ark_ff::Fp(BigInt(p.y.fp[1].l), PhantomData),
         },
         z: ark_ff::QuadExtField {
-            c0: ark_ff::Fp(BigInt(res_p2.z.fp[0].l), PhantomData),
-            c1: ark_ff::Fp(BigInt(res_p2.z.fp[1].l), PhantomData),
+            c0: ark_ff::Fp(BigInt(p.z.fp[0].l), PhantomData),
+            c1: ark_ff::Fp(BigInt(p.z.fp[1].l), PhantomData),
         },
-    })
+    }
 }
 
 #[cfg(test)]
@@ -173,6 +653,98 @@ mod tests {
         assert_eq!(res2, alt_res2);
     }
 
+    #[test]
+    fn test_table_msm_works() {
+        let g1s = (0..64)
+            .map(|_| ark_bls12_381::G1Projective::rand(&mut thread_rng()))
+            .collect::<Vec<_>>();
+        let scalars = (0..64)
+            .map(|_| ark_bls12_381::Fr::rand(&mut thread_rng()))
+            .collect::<Vec<_>>();
+        let pg1 = prep_g1s(&g1s);
+        for wbits in [2, 5] {
+            let table = G1Table::new(&pg1, wbits);
+            for n in [1, 17, 64] {
+                assert_eq!(
+                    g1_msm(&pg1, &scalars[..n], g1s.len()).unwrap(),
+                    table.msm(&scalars[..n], &mut Vec::new()).unwrap()
+                );
+            }
+            assert!(table
+                .msm(&[ark_bls12_381::Fr::from(1u64); 65], &mut Vec::new())
+                .is_err());
+        }
+    }
+
+    #[test]
+    fn test_too_many_scalars_errors() {
+        let g1s = (0..8)
+            .map(|_| ark_bls12_381::G1Projective::rand(&mut thread_rng()))
+            .collect::<Vec<_>>();
+        let g2s = (0..8)
+            .map(|_| ark_bls12_381::G2Projective::rand(&mut thread_rng()))
+            .collect::<Vec<_>>();
+        let scalars = (0..9)
+            .map(|_| ark_bls12_381::Fr::rand(&mut thread_rng()))
+            .collect::<Vec<_>>();
+        let pg1 = prep_g1s(&g1s);
+        let pg2 = prep_g2s(&g2s);
+        let too_large = || Error::PolynomialTooLarge {
+            n_coeffs: 9,
+            expected_max: 8,
+        };
+        // The bound is on the number of scalars, not the number of serialized bytes / 32
+        assert_eq!(Err(too_large()), g1_msm(&pg1, &scalars, g1s.len()));
+        assert_eq!(Err(too_large()), g2_msm(&pg2, &scalars, g2s.len()));
+        assert!(g1_msm(&pg1, &scalars[..8], g1s.len()).is_ok());
+        assert!(g2_msm(&pg2, &scalars[..8], g2s.len()).is_ok());
+    }
+
+    #[test]
+    fn test_trailing_zeros_trimmed() {
+        let g1s = (0..64)
+            .map(|_| ark_bls12_381::G1Projective::rand(&mut thread_rng()))
+            .collect::<Vec<_>>();
+        let mut scalars = (0..40)
+            .map(|_| ark_bls12_381::Fr::rand(&mut thread_rng()))
+            .collect::<Vec<_>>();
+        let pg1 = prep_g1s(&g1s);
+        let expected = g1_msm(&pg1, &scalars, g1s.len()).unwrap();
+        scalars.resize(64, ark_bls12_381::Fr::zero());
+        assert_eq!(expected, g1_msm(&pg1, &scalars, g1s.len()).unwrap());
+        assert!(g1_msm(&pg1, &scalars[40..], g1s.len()).unwrap().is_zero());
+    }
+
+    #[test]
+    fn test_serialized_rejects_non_canonical() {
+        use ark_ff::BigInteger;
+        let g1s = (0..4)
+            .map(|_| ark_bls12_381::G1Affine::rand(&mut thread_rng()))
+            .collect::<Vec<_>>();
+        let g2s = vec![ark_bls12_381::G2Affine::rand(&mut thread_rng())];
+        let blst = BlstMsm::new(prep_g1s_affine(&g1s), prep_g2s_affine(&g2s));
+        let ark = crate::msm::ArkworksMsm::<ark_bls12_381::Bls12_381>::new(g1s, g2s);
+        let scalars = (0..4)
+            .map(|_| ark_bls12_381::Fr::rand(&mut thread_rng()))
+            .collect::<Vec<_>>();
+        let mut bytes = prep_scalars(&scalars);
+        assert_eq!(
+            ark.msm_g1_serialized(&bytes),
+            blst.msm_g1_serialized(&bytes)
+        );
+
+        // The modulus itself is the smallest non-canonical scalar
+        bytes[32..64].copy_from_slice(&ark_bls12_381::Fr::MODULUS.to_bytes_le());
+        assert_eq!(
+            Err(Error::SerializationError),
+            ark.msm_g1_serialized(&bytes)
+        );
+        assert_eq!(
+            Err(Error::SerializationError),
+            blst.msm_g1_serialized(&bytes)
+        );
+    }
+
     #[test]
     fn test_single_works() {
         let g1s = vec![ark_bls12_381::G1Projective::rand(&mut thread_rng())];
@@ -194,4 +766,128 @@ mod tests {
         assert_eq!(res1, alt_res1);
         assert_eq!(res2, alt_res2);
     }
+
+    #[test]
+    fn test_affine_prep_matches() {
+        let mut g1s = (0..64)
+            .map(|_| ark_bls12_381::G1Projective::rand(&mut thread_rng()))
+            .collect::<Vec<_>>();
+        g1s.push(ark_bls12_381::G1Projective::default());
+        let g2s = (0..64)
+            .map(|_| ark_bls12_381::G2Projective::rand(&mut thread_rng()))
+            .collect::<Vec<_>>();
+        let scalars = (0..65)
+            .map(|_| ark_bls12_381::Fr::rand(&mut thread_rng()))
+            .collect::<Vec<_>>();
+        let g1s_affine = ark_bls12_381::G1Projective::normalize_batch(&g1s);
+        let g2s_affine = ark_bls12_381::G2Projective::normalize_batch(&g2s);
+
+        assert_eq!(
+            g1_msm(&prep_g1s(&g1s), &scalars, g1s.len()).unwrap(),
+            g1_msm(&prep_g1s_affine(&g1s_affine), &scalars, g1s.len()).unwrap()
+        );
+        assert_eq!(
+            g2_msm(&prep_g2s(&g2s), &scalars[..64], g2s.len()).unwrap(),
+            g2_msm(&prep_g2s_affine(&g2s_affine), &scalars[..64], g2s.len()).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_threaded_msm_works() {
+        let g1s = (0..37)
+            .map(|_| ark_bls12_381::G1Projective::rand(&mut thread_rng()))
+            .collect::<Vec<_>>();
+        let g2s = (0..37)
+            .map(|_| ark_bls12_381::G2Projective::rand(&mut thread_rng()))
+            .collect::<Vec<_>>();
+        let scalars = (0..37)
+            .map(|_| ark_bls12_381::Fr::rand(&mut thread_rng()))
+            .collect::<Vec<_>>();
+        let pg1 = prep_g1s(&g1s);
+        let pg2 = prep_g2s(&g2s);
+        let res1 = g1_msm(&pg1, &scalars, g1s.len()).unwrap();
+        let res2 = g2_msm(&pg2, &scalars, g2s.len()).unwrap();
+        // More threads than points leaves one point per thread
+        for nthreads in [1, 4, 64] {
+            let buf = &mut Vec::new();
+            assert_eq!(
+                res1,
+                g1_msm_with_buffer(&pg1, &scalars, g1s.len(), buf, Some(nthreads)).unwrap()
+            );
+            assert_eq!(
+                res2,
+                g2_msm_with_threads(&pg2, &scalars, g2s.len(), Some(nthreads)).unwrap()
+            );
+        }
+    }
+
+    #[test]
+    fn test_identity_converts() {
+        use ark_ec::Group;
+        let p1 = ark_bls12_381::G1Projective::rand(&mut thread_rng());
+        let p2 = ark_bls12_381::G2Projective::rand(&mut thread_rng());
+        let zero1 = ark_bls12_381::G1Projective::zero();
+        let zero2 = ark_bls12_381::G2Projective::zero();
+        assert_eq!(zero1, convert_p1_back(&convert_g1(&zero1)));
+        assert_eq!(zero2, convert_p2_back(&convert_g2(&zero2)));
+        // p + (-p) computed by arkworks, whose z is zero but x and y aren't canonical
+        assert_eq!(zero1, convert_p1_back(&convert_g1(&(p1 + -p1))));
+        assert_eq!(p1, convert_p1_back(&convert_g1(&p1)));
+        assert_eq!(p2, convert_p2_back(&convert_g2(&p2)));
+
+        // An identity among the powers, and scalars that cancel out
+        let g1s = vec![p1, zero1, p1, ark_bls12_381::G1Projective::generator()];
+        let g2s = vec![p2, zero2, p2, ark_bls12_381::G2Projective::generator()];
+        let a = ark_bls12_381::Fr::rand(&mut thread_rng());
+        let b = ark_bls12_381::Fr::rand(&mut thread_rng());
+        let cancelling = [a, b, -a];
+        assert!(g1_msm(&prep_g1s(&g1s), &cancelling, 4).unwrap().is_zero());
+        assert!(g2_msm(&prep_g2s(&g2s), &cancelling, 4).unwrap().is_zero());
+        for nthreads in [1, 2] {
+            let buf = &mut Vec::new();
+            assert!(
+                g1_msm_with_buffer(&prep_g1s(&g1s), &cancelling, 4, buf, Some(nthreads))
+                    .unwrap()
+                    .is_zero()
+            );
+        }
+        let two = ark_bls12_381::Fr::from(2u64);
+        assert_eq!(
+            p1 * two,
+            g1_msm(&prep_g1s(&g1s), &[a, b, two - a], 4).unwrap()
+        );
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn test_chunked_msm_works() {
+        let n = PARALLEL_MSM_THRESHOLD + 3;
+        let g1s = (0..n)
+            .map(|_| ark_bls12_381::G1Projective::rand(&mut thread_rng()))
+            .collect::<Vec<_>>();
+        let scalars = (0..n)
+            .map(|_| ark_bls12_381::Fr::rand(&mut thread_rng()))
+            .collect::<Vec<_>>();
+
+        let pg1 = prep_g1s(&g1s);
+        let res = g1_msm(&pg1, &scalars, g1s.len()).unwrap();
+
+        let g1s_affine = g1s.iter().map(|p| p.into_affine()).collect::<Vec<_>>();
+        let alt_res = curve_msm::<ark_bls12_381::G1Projective>(&g1s_affine, &scalars).unwrap();
+        assert_eq!(res, alt_res);
+    }
+
+    #[cfg(debug_assertions)]
+    #[test]
+    fn test_off_curve_result_errors() {
+        use ark_ff::One;
+        let p = ark_bls12_381::G1Projective::rand(&mut thread_rng());
+        assert_eq!(Ok(p), check_g1(p));
+        let one = ark_bls12_381::Fq::one();
+        let bad = ark_bls12_381::G1Projective::new_unchecked(one, one, one);
+        assert_eq!(Err(Error::MsmBackendInconsistency), check_g1(bad));
+        let one = ark_bls12_381::Fq2::one();
+        let bad = ark_bls12_381::G2Projective::new_unchecked(one, one, one);
+        assert_eq!(Err(Error::MsmBackendInconsistency), check_g2(bad));
+    }
 }
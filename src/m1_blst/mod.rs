@@ -1,20 +1,36 @@
 use crate::{
-    gen_curve_powers_proj,
+    cfg_iter, gen_curve_powers_proj,
     lagrange::LagrangeInterpContext,
+    msm::MsmBackend,
     traits::{Committer, PolyMultiProofNoPrecomp},
 };
 use ark_poly::univariate::DensePolynomial;
-use ark_std::UniformRand;
-use blst::{p1_affines, p2_affines};
+use ark_serialize::{
+    CanonicalDeserialize, CanonicalSerialize, Compress, Read, SerializationError, Valid, Validate,
+    Write,
+};
+use ark_std::{UniformRand, Zero};
 use merlin::Transcript;
-use std::usize;
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::{Arc, RwLock},
+    usize,
+};
 
 use ark_ec::{pairing::Pairing, AffineRepr, CurveGroup};
 use ark_std::rand::RngCore;
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
 
-use crate::{get_challenge, get_field_size, transcribe_points_and_evals, Commitment};
+use crate::{
+    check_eval_shape, evals_all_zero, get_challenge, get_field_size, horner_commit_combo,
+    transcribe_header, transcribe_points_and_evals, Commitment, HORNER_COMMIT_THRESHOLD,
+    METHOD1_TRANSCRIPT_TAG,
+};
 
-use super::{gen_powers, linear_combination, poly_div_q_r, vanishing_polynomial, Error};
+use super::{
+    gen_powers, gen_powers_small, linear_combination, poly_div_q_r, vanishing_polynomial, Error,
+};
 
 pub use ark_bls12_381::{
     Bls12_381, Fr, G1Affine, G1Projective as G1, G2Affine, G2Projective as G2,
@@ -26,8 +42,75 @@ pub mod precompute;
 pub struct M1NoPrecomp {
     pub powers_of_g1: Vec<G1>,
     pub powers_of_g2: Vec<G2>,
-    prepped_g1s: p1_affines,
-    prepped_g2s: p2_affines,
+    /// Does the msms against the powers, blst unless given with [`M1NoPrecomp::new_with_backend`]
+    backend: Arc<dyn MsmBackend<Bls12_381>>,
+    /// The commitment in G2 to the vanishing polynomial of recently verified point sets
+    g2_zeros_cache: RwLock<G2ZerosCache>,
+}
+
+/// The bytes a setup written by [`M1NoPrecomp::write_to`] starts with
+pub const SETUP_MAGIC: &[u8; 8] = b"PMP-SRS\0";
+/// The layout version written after [`SETUP_MAGIC`]
+pub const SETUP_VERSION: u8 = 1;
+/// How many powers [`M1NoPrecomp::read_from`] reserves room for before any are read
+const SETUP_PREALLOC: usize = 1024;
+
+/// How many point sets' g2 zeros are cached by default
+pub const DEFAULT_G2_ZEROS_CACHE_CAPACITY: usize = 64;
+
+/// The g2 zeros of at most `capacity` point sets. The point sets come from whoever asks for a
+/// verification, so once full the oldest entry is evicted rather than growing without bound.
+#[derive(Clone)]
+struct G2ZerosCache {
+    map: HashMap<Vec<Fr>, G2>,
+    order: VecDeque<Vec<Fr>>,
+    capacity: usize,
+}
+
+impl Default for G2ZerosCache {
+    fn default() -> Self {
+        Self::with_capacity(DEFAULT_G2_ZEROS_CACHE_CAPACITY)
+    }
+}
+
+impl G2ZerosCache {
+    fn with_capacity(capacity: usize) -> Self {
+        Self {
+            map: HashMap::new(),
+            order: VecDeque::new(),
+            capacity,
+        }
+    }
+
+    fn get(&self, points: &[Fr]) -> Option<&G2> {
+        self.map.get(points)
+    }
+
+    fn insert(&mut self, points: &[Fr], g2_zeros: G2) {
+        if self.capacity == 0 || self.map.contains_key(points) {
+            return;
+        }
+        if self.map.len() == self.capacity {
+            if let Some(oldest) = self.order.pop_front() {
+                self.map.remove(&oldest);
+            }
+        }
+        self.map.insert(points.to_vec(), g2_zeros);
+        self.order.push_back(points.to_vec());
+    }
+
+    fn clear(&mut self) {
+        self.map.clear();
+        self.order.clear();
+    }
+
+    fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
 }
 
 impl Clone for M1NoPrecomp {
@@ -35,8 +118,8 @@ impl Clone for M1NoPrecomp {
         Self {
             powers_of_g1: self.powers_of_g1.clone(),
             powers_of_g2: self.powers_of_g2.clone(),
-            prepped_g1s: fast_msm::prep_g1s(&self.powers_of_g1),
-            prepped_g2s: fast_msm::prep_g2s(&self.powers_of_g2),
+            backend: self.backend.clone(),
+            g2_zeros_cache: RwLock::new(self.read_g2_zeros_cache().clone()),
         }
     }
 }
@@ -44,24 +127,343 @@ impl Clone for M1NoPrecomp {
 #[derive(Debug, Clone)]
 pub struct Proof(G1Affine);
 
+impl Proof {
+    /// The number of bytes a proof serializes to, without needing a proof at hand
+    pub fn size_in_bytes(compress: Compress) -> usize {
+        G1Affine::generator().serialized_size(compress)
+    }
+
+    /// Whether the proofs are the same group element, regardless of how the points are represented
+    pub fn eq_point(&self, other: &Self) -> bool {
+        self.0.into_group() == other.0.into_group()
+    }
+
+    /// The commitment to the quotient polynomial, which is all the proof consists of
+    pub fn quotient_commitment(&self) -> G1Affine {
+        self.0
+    }
+
+    /// The compressed serialization, 48 bytes
+    pub fn to_compressed_bytes(&self) -> Vec<u8> {
+        crate::to_compressed_bytes(self)
+    }
+
+    /// Reads a proof from exactly its compressed serialization. Errors with
+    /// [`Error::InvalidPoint`] if its points aren't on the curve or in the prime order subgroup,
+    /// and [`Error::MalformedProof`] for anything else.
+    pub fn from_compressed_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        crate::from_exact_compressed(bytes, Error::MalformedProof)
+    }
+}
+
+impl std::fmt::Display for Proof {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        crate::fmt_compressed_hex(f, "Proof", self)
+    }
+}
+
+impl CanonicalSerialize for Proof {
+    fn serialize_with_mode<W: Write>(
+        &self,
+        writer: W,
+        compress: Compress,
+    ) -> Result<(), SerializationError> {
+        self.0.serialize_with_mode(writer, compress)
+    }
+
+    fn serialized_size(&self, compress: Compress) -> usize {
+        self.0.serialized_size(compress)
+    }
+}
+
+impl Valid for Proof {
+    fn check(&self) -> Result<(), SerializationError> {
+        self.0.check()
+    }
+}
+
+impl CanonicalDeserialize for Proof {
+    fn deserialize_with_mode<R: Read>(
+        reader: R,
+        compress: Compress,
+        validate: Validate,
+    ) -> Result<Self, SerializationError> {
+        Ok(Proof(G1Affine::deserialize_with_mode(
+            reader, compress, validate,
+        )?))
+    }
+}
+
 impl M1NoPrecomp {
+    /// Commits to the polynomial whose coefficients are yielded by `coeffs`, such as while
+    /// deserializing them from disk, without collecting them first. Returns the first error
+    /// `coeffs` yields, if any. The backend only does msms from the first power, so every chunk
+    /// of coefficients after the first is an arkworks msm against its slice of the powers.
+    pub fn commit_try_iter(
+        &self,
+        coeffs: impl Iterator<Item = Result<Fr, Error>>,
+    ) -> Result<Commitment<Bls12_381>, Error> {
+        let res =
+            crate::msm_try_iter_with::<G1>(self.powers_of_g1.len(), coeffs, |range, chunk| {
+                match range.start {
+                    0 => self.backend.msm_g1(chunk),
+                    _ => crate::curve_msm::<G1>(
+                        &G1::normalize_batch(&self.powers_of_g1[range]),
+                        chunk,
+                    ),
+                }
+            })?;
+        Ok(Commitment(res.into_affine()))
+    }
+
+    /// The g1 generator raised to the powers of the secret
+    pub fn powers_of_g1(&self) -> &[G1] {
+        &self.powers_of_g1
+    }
+
+    /// The g2 generator raised to the powers of the secret
+    pub fn powers_of_g2(&self) -> &[G2] {
+        &self.powers_of_g2
+    }
+
+    /// The most points that can be opened at and verified, one less than the number of g2
+    /// powers since the vanishing polynomial has a coefficient more than there are points
+    pub fn max_points(&self) -> usize {
+        self.powers_of_g2.len().saturating_sub(1)
+    }
+
+    /// **Insecure, for tests only.** Same as
+    /// [`method1::M1NoPrecomp::new_from_secret`](crate::method1::M1NoPrecomp::new_from_secret),
+    /// with the blst tables prepped from the powers. Anyone who knows `tau` can forge proofs.
+    pub fn new_from_secret(
+        max_coeffs: usize,
+        max_pts: Option<usize>,
+        tau: Fr,
+        g1_gen: G1Affine,
+        g2_gen: G2Affine,
+    ) -> Self {
+        let inner = crate::method1::M1NoPrecomp::<Bls12_381>::new_from_secret(
+            max_coeffs, max_pts, tau, g1_gen, g2_gen,
+        );
+        Self::new_from_affine(&inner.powers_of_g1, &inner.powers_of_g2)
+    }
+
     pub fn new_from_powers(g1s: &Vec<G1>, g2s: &Vec<G2>) -> Self {
+        let backend = fast_msm::BlstMsm::new(fast_msm::prep_g1s(g1s), fast_msm::prep_g2s(g2s));
+        Self::with_backend(g1s.clone(), g2s.clone(), Arc::new(backend))
+    }
+
+    /// Same as [`M1NoPrecomp::new_from_powers`], but preps the blst tables straight from the
+    /// affine points, without normalizing them again
+    pub fn new_from_affine(g1s: &[G1Affine], g2s: &[G2Affine]) -> Self {
+        let backend = Self::blst_from_affine(g1s, g2s);
+        Self::with_backend(
+            g1s.iter().map(|i| i.into_group()).collect(),
+            g2s.iter().map(|i| i.into_group()).collect(),
+            Arc::new(backend),
+        )
+    }
+
+    /// Builds the setup from existing powers, such as from a trusted setup ceremony, prepping the
+    /// blst tables from them. Errors with [`Error::EmptyPowers`] if either is empty and
+    /// [`Error::NotEnoughG2Powers`] with fewer than two g2 powers.
+    pub fn from_powers(
+        powers_of_g1: &[G1Affine],
+        powers_of_g2: &[G2Affine],
+    ) -> Result<Self, Error> {
+        crate::check_powers_len(powers_of_g1.len(), powers_of_g2.len())?;
+        Ok(Self::new_from_affine(powers_of_g1, powers_of_g2))
+    }
+
+    /// Same as [`crate::method1::M1NoPrecomp::extend_g2_powers`]. The blst tables are rebuilt from
+    /// all the powers and the cached g2 zeros are cleared, so a custom backend, window size or
+    /// thread limit has to be set again afterwards.
+    pub fn extend_g2_powers(&mut self, additional_g2: Vec<G2Affine>) -> Result<(), Error> {
+        let (g1, g1x) = match self.powers_of_g1.as_slice() {
+            [g1, g1x, ..] => (*g1, *g1x),
+            _ => return Err(Error::InconsistentSrs),
+        };
+        let mut prev = self
+            .powers_of_g2
+            .last()
+            .ok_or(Error::EmptyPowers)?
+            .into_affine();
+        for next in &additional_g2 {
+            let check = Bls12_381::multi_pairing([g1x, -g1], [prev, *next]);
+            if !check.is_zero() {
+                return Err(Error::InconsistentSrs);
+            }
+            prev = *next;
+        }
+        self.powers_of_g2
+            .extend(additional_g2.iter().map(|p| p.into_group()));
+        let g1s = G1::normalize_batch(&self.powers_of_g1);
+        let g2s = G2::normalize_batch(&self.powers_of_g2);
+        self.backend = Arc::new(Self::blst_from_affine(&g1s, &g2s));
+        self.clear_g2_zeros_cache();
+        Ok(())
+    }
+
+    /// Writes the powers to `w` so the setup can be reloaded with [`M1NoPrecomp::read_from`]
+    /// instead of regenerated: [`SETUP_MAGIC`] and a [`SETUP_VERSION`] byte, the number of g1 and
+    /// g2 powers as little endian `u64`s, then the compressed g1 powers followed by the compressed
+    /// g2 powers. The blst tables aren't written, they are rebuilt on reading.
+    pub fn write_to<W: Write>(&self, w: &mut W) -> Result<(), Error> {
+        w.write_all(SETUP_MAGIC)
+            .and_then(|_| w.write_all(&[SETUP_VERSION]))
+            .map_err(|_| Error::SerializationError)?;
+        (self.powers_of_g1.len() as u64).serialize_compressed(&mut *w)?;
+        (self.powers_of_g2.len() as u64).serialize_compressed(&mut *w)?;
+        for p in G1::normalize_batch(&self.powers_of_g1) {
+            p.serialize_compressed(&mut *w)?;
+        }
+        for p in G2::normalize_batch(&self.powers_of_g2) {
+            p.serialize_compressed(&mut *w)?;
+        }
+        Ok(())
+    }
+
+    /// Reads a setup written by [`M1NoPrecomp::write_to`] and preps the blst tables for it, as
+    /// [`M1NoPrecomp::from_powers`] does. Errors with [`Error::SerializationError`] if the magic or
+    /// version don't match, if `r` ends before all the powers in the header, or if any of them
+    /// isn't a valid point.
+    pub fn read_from<R: Read>(r: &mut R) -> Result<Self, Error> {
+        let mut magic = [0u8; SETUP_MAGIC.len() + 1];
+        r.read_exact(&mut magic)
+            .map_err(|_| Error::SerializationError)?;
+        if magic[..SETUP_MAGIC.len()] != *SETUP_MAGIC || magic[SETUP_MAGIC.len()] != SETUP_VERSION {
+            return Err(Error::SerializationError);
+        }
+        let n_g1 = u64::deserialize_compressed(&mut *r)? as usize;
+        let n_g2 = u64::deserialize_compressed(&mut *r)? as usize;
+        // The header can't be trusted to size the vectors, a corrupt one would reserve gigabytes
+        // before the truncation is noticed. Start small and grow as the points actually parse.
+        let mut powers_of_g1 = Vec::with_capacity(n_g1.min(SETUP_PREALLOC));
+        for _ in 0..n_g1 {
+            powers_of_g1.push(G1Affine::deserialize_compressed(&mut *r)?);
+        }
+        let mut powers_of_g2 = Vec::with_capacity(n_g2.min(SETUP_PREALLOC));
+        for _ in 0..n_g2 {
+            powers_of_g2.push(G2Affine::deserialize_compressed(&mut *r)?);
+        }
+        Self::from_powers(&powers_of_g1, &powers_of_g2)
+    }
+
+    /// Same as [`M1NoPrecomp::new_from_affine`], but does the msms against the powers with
+    /// `backend`, e.g. one from a separate GPU crate. The backend must have been built from the
+    /// same powers, otherwise commitments and proofs won't verify.
+    pub fn new_with_backend(
+        g1s: &Vec<G1Affine>,
+        g2s: &Vec<G2Affine>,
+        backend: Arc<dyn MsmBackend<Bls12_381>>,
+    ) -> Self {
+        Self::with_backend(
+            g1s.iter().map(|i| i.into_group()).collect(),
+            g2s.iter().map(|i| i.into_group()).collect(),
+            backend,
+        )
+    }
+
+    fn with_backend(g1s: Vec<G1>, g2s: Vec<G2>, backend: Arc<dyn MsmBackend<Bls12_381>>) -> Self {
         Self {
-            powers_of_g1: g1s.clone(),
-            powers_of_g2: g2s.clone(),
-            prepped_g1s: fast_msm::prep_g1s(g1s),
-            prepped_g2s: fast_msm::prep_g2s(g2s),
+            powers_of_g1: g1s,
+            powers_of_g2: g2s,
+            backend,
+            g2_zeros_cache: Default::default(),
         }
     }
 
-    pub fn new_from_affine(g1s: &Vec<G1Affine>, g2s: &Vec<G2Affine>) -> Self {
-        Self::new_from_powers(
-            &g1s.iter().map(|i| i.into_group()).collect::<Vec<_>>(),
-            &g2s.iter().map(|i| i.into_group()).collect::<Vec<_>>(),
+    fn blst_from_affine(g1s: &[G1Affine], g2s: &[G2Affine]) -> fast_msm::BlstMsm {
+        fast_msm::BlstMsm::new(
+            fast_msm::prep_g1s_affine(g1s),
+            fast_msm::prep_g2s_affine(g2s),
+        )
+    }
+
+    /// Same as [`M1NoPrecomp::new_from_affine`], but also precomputes multiples of every g1
+    /// power, which makes the msms in `commit` and `open` faster at the cost of memory. Level 0
+    /// precomputes nothing. Level $l$ stores $2^l$ points, $96 \cdot 2^l$ bytes, per g1 power, so
+    /// e.g. level 4 takes 1.5KiB per power, 6MiB for 4096 powers. Errors for levels above 11.
+    pub fn new_with_precompute_level(
+        g1s: &Vec<G1Affine>,
+        g2s: &Vec<G2Affine>,
+        level: usize,
+    ) -> Result<Self, Error> {
+        if level >= fast_msm::MAX_WBITS {
+            return Err(Error::PrecomputeLevelTooLarge {
+                level,
+                max: fast_msm::MAX_WBITS - 1,
+            });
+        }
+        let mut backend = Self::blst_from_affine(g1s, g2s);
+        if level > 0 {
+            backend = backend.with_table(level + 1);
+        }
+        Ok(Self::new_with_backend(g1s, g2s, Arc::new(backend)))
+    }
+
+    /// Rebuilds the blst backend with a fixed base table of window `wbits` for the g1 msms, the
+    /// same as [`M1NoPrecomp::new_with_precompute_level`] with level `wbits - 1`. Small windows
+    /// suit small msms such as per cell openings, big ones full row commits, at $96 \cdot
+    /// 2^{w-1}$ bytes per g1 power. A `wbits` of 0 or 1 builds no table. This replaces any other
+    /// backend or thread limit. Errors for windows above 12.
+    pub fn with_window_bits(self, wbits: usize) -> Result<Self, Error> {
+        Self::new_with_precompute_level(
+            &G1::normalize_batch(&self.powers_of_g1),
+            &G2::normalize_batch(&self.powers_of_g2),
+            wbits.saturating_sub(1),
+        )
+    }
+
+    /// Same as [`M1NoPrecomp::new_from_affine`], but splits each msm into at most `nthreads`
+    /// chunks, e.g. to run several proofs at once on a big machine without oversubscribing it.
+    /// The chunks run on rayon's pool with the `parallel` feature and one after another without
+    /// it, so no threads are spawned per msm. `commit_many` then commits its rows one after
+    /// another. 0 is treated as 1.
+    pub fn new_with_threads(g1s: &[G1Affine], g2s: &[G2Affine], nthreads: usize) -> Self {
+        let backend = Self::blst_from_affine(g1s, g2s).with_threads(nthreads);
+        Self::with_backend(
+            g1s.iter().map(|i| i.into_group()).collect(),
+            g2s.iter().map(|i| i.into_group()).collect(),
+            Arc::new(backend),
+        )
+    }
+
+    /// Checks that the powers are consistent powers of one secret on each generator, e.g. after
+    /// loading them with [`M1NoPrecomp::from_powers`]. Errors with [`Error::InconsistentSrs`] if
+    /// not.
+    pub fn validate_srs(&self, rng: &mut impl RngCore) -> Result<(), Error> {
+        crate::validate_powers::<Bls12_381>(
+            &G1::normalize_batch(&self.powers_of_g1),
+            &G2::normalize_batch(&self.powers_of_g2),
+            rng,
         )
     }
 
-    fn open_with_vanishing_poly(
+    /// Commits to `poly` in G2 with the g2 powers, for protocols that pair the commitment against
+    /// a G1 element. There are usually far fewer g2 powers than g1 powers, so this errors with
+    /// [`Error::PolynomialTooLarge`] for polynomials a G1 commitment could take.
+    pub fn commit_g2(&self, poly: impl AsRef<[Fr]>) -> Result<G2Affine, Error> {
+        Ok(self.backend.msm_g2(poly.as_ref())?.into_affine())
+    }
+
+    /// Given a commitment `old` to $f$, returns a commitment to $f + \delta x^{index}$ without
+    /// recommitting to the whole polynomial
+    pub fn update_commitment(
+        &self,
+        old: &Commitment<Bls12_381>,
+        index: usize,
+        delta: Fr,
+    ) -> Result<Commitment<Bls12_381>, Error> {
+        crate::update_commitment(&self.powers_of_g1, old, index, delta)
+    }
+
+    /// Same as [`PolyMultiProofNoPrecomp::open`], but takes the vanishing polynomial of `points`,
+    /// so it can be computed once with [`vanishing_polynomial`](crate::vanishing_polynomial) and
+    /// reused across many openings at the same points. `vp` must be the vanishing polynomial of
+    /// exactly these `points`. It isn't checked, and a proof made with any other polynomial won't
+    /// verify.
+    pub fn open_with_vanishing_poly(
         &self,
         transcript: &mut Transcript,
         evals: &[impl AsRef<[Fr]>],
@@ -69,8 +471,133 @@ impl M1NoPrecomp {
         points: &[Fr],
         vp: &DensePolynomial<Fr>,
     ) -> Result<Proof, Error> {
+        self.open_with_quotient(transcript, evals, polys, points, vp)
+            .map(|(proof, _)| proof)
+    }
+
+    /// The vanishing polynomial of `points`, to compute once and pass to
+    /// [`M1NoPrecomp::open_precomputed`] for every opening at those points
+    pub fn precompute_vanishing(points: &[Fr]) -> DensePolynomial<Fr> {
+        vanishing_polynomial(points)
+    }
+
+    /// Same as [`M1NoPrecomp::open_with_vanishing_poly`]. `vp` must be
+    /// [`M1NoPrecomp::precompute_vanishing`] of exactly these `points`, it isn't checked, and a
+    /// proof made with any other polynomial won't verify.
+    pub fn open_precomputed(
+        &self,
+        transcript: &mut Transcript,
+        evals: &[impl AsRef<[Fr]>],
+        polys: &[impl AsRef<[Fr]>],
+        points: &[Fr],
+        vp: &DensePolynomial<Fr>,
+    ) -> Result<Proof, Error> {
+        self.open_with_vanishing_poly(transcript, evals, polys, points, vp)
+    }
+
+    /// Same as [`PolyMultiProofNoPrecomp::verify`], but takes the vanishing polynomial of
+    /// `points`, which must be the one the prover opened with
+    pub fn verify_with_vanishing_poly(
+        &self,
+        transcript: &mut Transcript,
+        commits: impl AsRef<[Commitment<Bls12_381>]>,
+        points: &[Fr],
+        evals: &[impl AsRef<[Fr]>],
+        proof: &Proof,
+        vp: &DensePolynomial<Fr>,
+    ) -> Result<bool, Error> {
+        crate::traits::check_points(commits.as_ref(), proof)?;
+        self.verify_at_points_unchecked(
+            transcript,
+            commits.as_ref(),
+            points,
+            evals,
+            proof,
+            Some(vp),
+        )
+    }
+
+    /// Verifies an opening at `points`, using `vp` as their vanishing polynomial if the g2 zeros
+    /// for them aren't cached yet
+    fn verify_at_points_unchecked(
+        &self,
+        transcript: &mut Transcript,
+        commits: &[Commitment<Bls12_381>],
+        points: &[Fr],
+        evals: &[impl AsRef<[Fr]>],
+        proof: &Proof,
+        vp: Option<&DensePolynomial<Fr>>,
+    ) -> Result<bool, Error> {
+        if points.len() > self.max_points() {
+            return Err(Error::NotEnoughG2Powers {
+                needed: points.len() + 1,
+                have: self.powers_of_g2.len(),
+            });
+        }
+        let g2_zeros = self.g2_zeros(points, vp)?;
+        let lag_ctx = LagrangeInterpContext::new_from_points(points)?;
+        self.verify_with_lag_ctx_g2_zeros_unchecked(
+            transcript, commits, points, evals, proof, &lag_ctx, &g2_zeros,
+        )
+    }
+
+    /// The commitment in G2 to the vanishing polynomial of `points`. This is a full G2 msm, so
+    /// it's cached per point set, and verifying repeatedly at the same points only computes it
+    /// once. The cache is shared between threads and holds at most
+    /// [`DEFAULT_G2_ZEROS_CACHE_CAPACITY`] point sets unless set with
+    /// [`M1NoPrecomp::with_g2_zeros_cache_capacity`].
+    fn g2_zeros(&self, points: &[Fr], vp: Option<&DensePolynomial<Fr>>) -> Result<G2, Error> {
+        if let Some(g2_zeros) = self.read_g2_zeros_cache().get(points) {
+            return Ok(*g2_zeros);
+        }
+        let g2_zeros = match vp {
+            Some(vp) => self.backend.msm_g2(vp)?,
+            None => self.backend.msm_g2(&vanishing_polynomial(points))?,
+        };
+        self.g2_zeros_cache
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(points, g2_zeros);
+        Ok(g2_zeros)
+    }
+
+    /// Caches the g2 zeros of at most `capacity` point sets, evicting the oldest once full. 0
+    /// turns the cache off, e.g. for a verifier that rarely sees the same points twice.
+    pub fn with_g2_zeros_cache_capacity(self, capacity: usize) -> Self {
+        Self {
+            g2_zeros_cache: RwLock::new(G2ZerosCache::with_capacity(capacity)),
+            ..self
+        }
+    }
+
+    /// Empties the cache of g2 zeros, e.g. after verifying at many one-off point sets
+    pub fn clear_g2_zeros_cache(&self) {
+        self.g2_zeros_cache
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .clear();
+    }
+
+    // A panic can't leave the map half updated, so a poisoned lock is still fine to use
+    fn read_g2_zeros_cache(&self) -> std::sync::RwLockReadGuard<G2ZerosCache> {
+        self.g2_zeros_cache
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Opens, additionally returning the quotient polynomial `q` that the proof commits to
+    fn open_with_quotient(
+        &self,
+        transcript: &mut Transcript,
+        evals: &[impl AsRef<[Fr]>],
+        polys: &[impl AsRef<[Fr]>],
+        points: &[Fr],
+        vp: &DensePolynomial<Fr>,
+    ) -> Result<(Proof, DensePolynomial<Fr>), Error> {
+        check_eval_shape(evals, polys.len(), points.len())?;
         // Commit the evals and the points to the transcript
         let field_size_bytes = get_field_size::<Fr>();
+        transcribe_header(transcript, METHOD1_TRANSCRIPT_TAG);
         transcribe_points_and_evals(transcript, points, evals, field_size_bytes)?;
 
         // Read the challenge
@@ -78,18 +605,60 @@ impl M1NoPrecomp {
         // Make the gamma powers
         let gammas = gen_powers::<Fr>(gamma, self.powers_of_g1.len());
         // Take a linear combo of gammas with the polynomials
-        let fsum = linear_combination::<Fr>(polys, &gammas).ok_or(Error::NoPolynomialsGiven)?;
+        let fsum = linear_combination::<Fr>(polys, &gammas)?;
 
         // Polynomial divide, the remained would contain the gamma * ri_s,
         // The result is the correct quotient
         let (q, _) = poly_div_q_r(DensePolynomial { coeffs: fsum }.into(), vp.into())?;
         // Open to the resulting polynomial
-        Ok(Proof(
-            fast_msm::g1_msm(&self.prepped_g1s, &q, self.powers_of_g1.len())?.into_affine(),
-        ))
+        let proof = Proof(self.backend.msm_g1(&q)?.into_affine());
+        Ok((proof, DensePolynomial { coeffs: q }))
+    }
+
+    /// Same as [`PolyMultiProofNoPrecomp::open`], but also returns the quotient polynomial `q`
+    /// so tests can inspect the prover's intermediate values.
+    #[cfg(feature = "debug")]
+    pub fn open_debug(
+        &self,
+        transcript: &mut Transcript,
+        evals: &[impl AsRef<[Fr]>],
+        polys: &[impl AsRef<[Fr]>],
+        points: &[Fr],
+    ) -> Result<(Proof, DensePolynomial<Fr>), Error> {
+        let vp = vanishing_polynomial(points);
+        self.open_with_quotient(transcript, evals, polys, points, &vp)
     }
 
-    fn verify_with_lag_ctx_g2_zeros(
+    /// The cheapest way to verify, for callers that cache the per point set values themselves.
+    ///
+    /// `lag_ctx` must be [`LagrangeInterpContext::new_from_points`] of exactly `points`, in the
+    /// same order, and `g2_zeros` must be the G2 msm of the setup's powers with
+    /// [`vanishing_polynomial`](crate::vanishing_polynomial) of `points`. Neither is checked, and
+    /// passing values for other points makes honest proofs fail to verify. The commitments and
+    /// proof are checked to be in the prime order subgroup.
+    pub fn verify_with_lag_ctx_g2_zeros(
+        &self,
+        transcript: &mut Transcript,
+        commits: impl AsRef<[Commitment<Bls12_381>]>,
+        points: &[Fr],
+        evals: &[impl AsRef<[Fr]>],
+        proof: &Proof,
+        lag_ctx: &LagrangeInterpContext<Fr>,
+        g2_zeros: &G2,
+    ) -> Result<bool, Error> {
+        crate::traits::check_points(commits.as_ref(), proof)?;
+        self.verify_with_lag_ctx_g2_zeros_unchecked(
+            transcript,
+            commits.as_ref(),
+            points,
+            evals,
+            proof,
+            lag_ctx,
+            g2_zeros,
+        )
+    }
+
+    fn verify_with_lag_ctx_g2_zeros_unchecked(
         &self,
         transcript: &mut Transcript,
         commits: &[Commitment<Bls12_381>],
@@ -99,22 +668,36 @@ impl M1NoPrecomp {
         lag_ctx: &LagrangeInterpContext<Fr>,
         g2_zeros: &G2,
     ) -> Result<bool, Error> {
+        check_eval_shape(evals, commits.len(), points.len())?;
+        // A point equal to the secret x makes the vanishing polynomial zero at x, which would
+        // reduce the pairing check to a comparison against the identity
+        if g2_zeros.is_zero() {
+            return Err(Error::PointIsSecret);
+        }
         let field_size_bytes = get_field_size::<Fr>();
+        transcribe_header(transcript, METHOD1_TRANSCRIPT_TAG);
         transcribe_points_and_evals(transcript, points, evals, field_size_bytes)?;
         let gamma = get_challenge(transcript, b"open gamma", field_size_bytes);
         // Aggregate the r_is and then do a single msm of just the ri's and gammas
-        let gammas = gen_powers(gamma, evals.len());
+        let gammas = gen_powers_small(gamma, evals.len());
 
         // Get the gamma^i r_i polynomials with lagrange interp. This does both the lagrange interp
         // and the gamma mul in one step so we can just lagrange interp once.
-        let gamma_ris = lag_ctx.lagrange_interp_linear_combo(evals, &gammas)?.coeffs;
-        let gamma_ris_pt =
-            fast_msm::g1_msm(&self.prepped_g1s, &gamma_ris, self.powers_of_g1.len())?;
+        // If all the evals are zero, so are the r_i, and we can skip the interp and msm.
+        let gamma_ris_pt = if evals_all_zero(evals) {
+            G1::zero()
+        } else {
+            let gamma_ris = lag_ctx.lagrange_interp_linear_combo(evals, &gammas)?.coeffs;
+            self.backend.msm_g1(&gamma_ris)?
+        };
 
-        // Then do a single msm of the gammas and commitments
-        let cms = commits.iter().map(|i| i.0.into_group()).collect::<Vec<_>>();
-        let cms_prep = fast_msm::prep_g1s(&cms.as_slice());
-        let gamma_cm_pt = fast_msm::g1_msm(&cms_prep, &gammas, cms.len())?;
+        // Then combine the commitments with the gammas
+        let gamma_cm_pt = if gammas.len() < HORNER_COMMIT_THRESHOLD {
+            horner_commit_combo(commits, &gammas)?
+        } else {
+            let cms = commits.iter().map(|i| i.0).collect::<Vec<_>>();
+            self.backend.msm_g1_with_bases(&cms, &gammas)?
+        };
 
         let g2 = self.powers_of_g2[0];
 
@@ -124,9 +707,24 @@ impl M1NoPrecomp {
 }
 
 impl Committer<Bls12_381> for M1NoPrecomp {
-    fn commit(&self, poly: impl AsRef<[Fr]>) -> Result<Commitment<Bls12_381>, Error> {
-        let res = fast_msm::g1_msm(&self.prepped_g1s, poly.as_ref(), self.powers_of_g1.len())?;
-        Ok(Commitment(res.into_affine()))
+    fn commit_projective(&self, poly: impl AsRef<[Fr]>) -> Result<G1, Error> {
+        self.backend.msm_g1(poly.as_ref())
+    }
+
+    /// Hands all the rows to the backend at once, so it can share work between them
+    fn commit_many(
+        &self,
+        polys: &[impl AsRef<[Fr]> + Sync],
+    ) -> Result<Vec<Commitment<Bls12_381>>, Error> {
+        let rows = polys.iter().map(|p| p.as_ref()).collect::<Vec<_>>();
+        let projective = self.backend.msm_g1_many(&rows)?;
+        Ok(G1::normalize_batch(&projective)
+            .into_iter()
+            .map(Commitment)
+            .collect())
+    }
+    fn max_coeffs(&self) -> usize {
+        self.powers_of_g1.len()
     }
 }
 
@@ -144,15 +742,7 @@ impl PolyMultiProofNoPrecomp<Bls12_381> for M1NoPrecomp {
         let powers_of_g1 = gen_curve_powers_proj::<G1>(x_powers.as_ref(), rng);
         let powers_of_g2 = gen_curve_powers_proj::<G2>(x_powers[..max_pts].as_ref(), rng);
 
-        let prepped_g1s = fast_msm::prep_g1s(&powers_of_g1);
-        let prepped_g2s = fast_msm::prep_g2s(&powers_of_g2);
-
-        Ok(M1NoPrecomp {
-            powers_of_g1,
-            powers_of_g2,
-            prepped_g1s,
-            prepped_g2s,
-        })
+        Ok(Self::new_from_powers(&powers_of_g1, &powers_of_g2))
     }
 
     fn open(
@@ -166,31 +756,97 @@ impl PolyMultiProofNoPrecomp<Bls12_381> for M1NoPrecomp {
         self.open_with_vanishing_poly(transcript, evals, polys, points, &vp)
     }
 
-    fn verify(
+    fn verify_unchecked(
         &self,
         transcript: &mut Transcript,
-        commits: &[Commitment<Bls12_381>],
+        commits: impl AsRef<[Commitment<Bls12_381>]>,
         points: &[Fr],
         evals: &[impl AsRef<[Fr]>],
         proof: &Proof,
     ) -> Result<bool, Error> {
-        let vp = vanishing_polynomial(points);
-        let g2_zeros = fast_msm::g2_msm(&self.prepped_g2s, &vp, self.powers_of_g2.len())?;
-        let lag_ctx = LagrangeInterpContext::new_from_points(points)?;
-        self.verify_with_lag_ctx_g2_zeros(
-            transcript, commits, points, evals, proof, &lag_ctx, &g2_zeros,
-        )
+        self.verify_at_points_unchecked(transcript, commits.as_ref(), points, evals, proof, None)
+    }
+}
+
+/// Polynomials whose scalars have been serialized for blst once, so committing to them again
+/// doesn't repeat the conversion. Made with [`M1NoPrecomp::prepare`].
+///
+/// The serialized scalars take 32 bytes per coefficient, as much again as the polynomials
+/// themselves, for as long as the batch is held. Only the commitments use them: opening takes a
+/// random linear combination of the polynomials as field elements, and the quotient it commits
+/// to has scalars of its own.
+pub struct PreparedBatch<'a> {
+    setup: &'a M1NoPrecomp,
+    polys: Vec<Vec<Fr>>,
+    scalars: Vec<Vec<u8>>,
+}
+
+impl M1NoPrecomp {
+    /// Serializes the scalars of every one of `polys` for blst, in parallel with the `parallel`
+    /// feature
+    pub fn prepare(&self, polys: Vec<Vec<Fr>>) -> PreparedBatch<'_> {
+        let scalars = cfg_iter!(polys)
+            .map(|(_, p)| fast_msm::prep_scalars(crate::trim_zeros(p)))
+            .collect();
+        PreparedBatch {
+            setup: self,
+            polys,
+            scalars,
+        }
+    }
+}
+
+impl PreparedBatch<'_> {
+    pub fn polys(&self) -> &[Vec<Fr>] {
+        &self.polys
+    }
+
+    /// Same as [`Committer::commit_many`] on the polynomials, from the serialized scalars
+    pub fn commit_all(&self) -> Result<Vec<Commitment<Bls12_381>>, Error> {
+        let projective = cfg_iter!(self.scalars)
+            .map(|(_, s)| self.setup.backend.msm_g1_serialized(s))
+            .collect::<Result<Vec<_>, Error>>()?;
+        Ok(G1::normalize_batch(&projective)
+            .into_iter()
+            .map(Commitment)
+            .collect())
+    }
+
+    /// Same as [`PolyMultiProofNoPrecomp::open`] on the polynomials
+    pub fn open(
+        &self,
+        transcript: &mut Transcript,
+        evals: &[impl AsRef<[Fr]>],
+        points: &[Fr],
+    ) -> Result<Proof, Error> {
+        self.setup.open(transcript, evals, &self.polys, points)
+    }
+
+    /// Commits to the polynomials from the serialized scalars and opens them at `points`, for
+    /// single shot callers that need both. The proof is an ordinary
+    /// [`PolyMultiProofNoPrecomp::open`], checked with [`PolyMultiProofNoPrecomp::verify`]
+    /// against the returned commitments.
+    pub fn commit_and_open(
+        &self,
+        transcript: &mut Transcript,
+        evals: &[impl AsRef<[Fr]>],
+        points: &[Fr],
+    ) -> Result<(Vec<Commitment<Bls12_381>>, Proof), Error> {
+        let commits = self.commit_all()?;
+        let proof = self.open(transcript, evals, points)?;
+        Ok((commits, proof))
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::M1NoPrecomp;
+    use super::{M1NoPrecomp, Proof, G1, G2};
     use crate::{
         test_rng,
         traits::{Committer, PolyMultiProofNoPrecomp},
+        Error,
     };
-    use ark_bls12_381::Fr;
+    use ark_bls12_381::{Bls12_381, Fr};
     use ark_poly::{univariate::DensePolynomial, DenseUVPolynomial, Polynomial};
     use ark_std::UniformRand;
     use merlin::Transcript;
@@ -224,6 +880,618 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_capacity() {
+        let s = M1NoPrecomp::new(16, 4.into(), &mut test_rng()).unwrap();
+        assert_eq!(15, s.max_poly_degree());
+        assert_eq!(4, s.max_points());
+        let poly = DensePolynomial::<Fr>::rand(s.max_poly_degree() + 1, &mut test_rng());
+        assert_eq!(
+            Err(Error::PolynomialTooLarge {
+                n_coeffs: 17,
+                expected_max: 16,
+            }),
+            s.commit(&poly.coeffs).map(|_| ())
+        );
+    }
+
+    #[test]
+    fn test_transposed_evals_errors() {
+        crate::test_helpers::check_transposed_evals_errors(
+            &M1NoPrecomp::new(64, 8.into(), &mut test_rng()).unwrap(),
+        );
+    }
+
+    #[test]
+    fn test_compressed_bytes_roundtrip() {
+        crate::test_helpers::check_compressed_bytes_roundtrip(
+            &M1NoPrecomp::new(32, 4.into(), &mut test_rng()).unwrap(),
+            48,
+            Proof::to_compressed_bytes,
+            Proof::from_compressed_bytes,
+        );
+    }
+
+    #[test]
+    fn test_open_precomputed() {
+        let s = M1NoPrecomp::new(32, 4.into(), &mut test_rng()).unwrap();
+        let points = (0..4)
+            .map(|_| Fr::rand(&mut test_rng()))
+            .collect::<Vec<_>>();
+        let vp = M1NoPrecomp::precompute_vanishing(&points);
+        assert_eq!(crate::vanishing_polynomial(&points), vp);
+        for _ in 0..3 {
+            let polys = (0..2)
+                .map(|_| DensePolynomial::<Fr>::rand(20, &mut test_rng()).coeffs)
+                .collect::<Vec<_>>();
+            let evals = polys
+                .iter()
+                .map(|p| crate::multi_evaluate(p, &points))
+                .collect::<Vec<_>>();
+            let precomputed = s
+                .open_precomputed(
+                    &mut Transcript::new(b"testing"),
+                    &evals,
+                    &polys,
+                    &points,
+                    &vp,
+                )
+                .unwrap();
+            let expected = s
+                .open(&mut Transcript::new(b"testing"), &evals, &polys, &points)
+                .unwrap();
+            assert_eq!(expected.0, precomputed.0);
+        }
+    }
+
+    #[test]
+    fn test_extend_g2_powers() {
+        use ark_bls12_381::{G1Affine, G2Affine};
+        use ark_ec::{AffineRepr, CurveGroup};
+        let x = Fr::from(5u64);
+        let x_powers = crate::gen_powers(x, 32);
+        let g1s = x_powers
+            .iter()
+            .map(|p| (G1Affine::generator() * p).into_affine())
+            .collect::<Vec<_>>();
+        let g2_powers = |powers: &[Fr]| {
+            powers
+                .iter()
+                .map(|p| (G2Affine::generator() * p).into_affine())
+                .collect::<Vec<_>>()
+        };
+        let mut s = M1NoPrecomp::from_powers(&g1s, &g2_powers(&x_powers[..5])).unwrap();
+
+        let points = (0..8)
+            .map(|_| Fr::rand(&mut test_rng()))
+            .collect::<Vec<_>>();
+        let poly = DensePolynomial::<Fr>::rand(20, &mut test_rng());
+        let evals = vec![points.iter().map(|x| poly.evaluate(x)).collect::<Vec<_>>()];
+        let commits = vec![s.commit(&poly.coeffs).unwrap()];
+        let open = s
+            .open(
+                &mut Transcript::new(b"testing"),
+                &evals,
+                &[&poly.coeffs],
+                &points,
+            )
+            .unwrap();
+        let verify = |s: &M1NoPrecomp| {
+            s.verify(
+                &mut Transcript::new(b"testing"),
+                &commits,
+                &points,
+                &evals,
+                &open,
+            )
+        };
+        assert_eq!(
+            Err(Error::NotEnoughG2Powers { needed: 9, have: 5 }),
+            verify(&s)
+        );
+
+        // Powers of another secret don't chain on
+        let y_powers = crate::gen_powers(Fr::from(7u64), 9);
+        assert_eq!(
+            Err(Error::InconsistentSrs),
+            s.extend_g2_powers(g2_powers(&y_powers[5..9]))
+        );
+        assert_eq!(5, s.powers_of_g2.len());
+
+        s.extend_g2_powers(g2_powers(&x_powers[5..9])).unwrap();
+        assert_eq!(9, s.powers_of_g2.len());
+        assert_eq!(Ok(true), verify(&s));
+    }
+
+    #[test]
+    fn test_from_powers() {
+        use ark_bls12_381::{G1Affine, G2Affine};
+        use ark_ec::{AffineRepr, CurveGroup};
+        // A fixed toy setup with secret 5
+        let x = Fr::from(5u64);
+        let x_powers = crate::gen_powers(x, 32);
+        let g1s = x_powers
+            .iter()
+            .map(|p| (G1Affine::generator() * p).into_affine())
+            .collect::<Vec<_>>();
+        let g2s = x_powers[..5]
+            .iter()
+            .map(|p| (G2Affine::generator() * p).into_affine())
+            .collect::<Vec<_>>();
+        let s = M1NoPrecomp::from_powers(&g1s, &g2s).unwrap();
+
+        let points = (0..4)
+            .map(|_| Fr::rand(&mut test_rng()))
+            .collect::<Vec<_>>();
+        let poly = DensePolynomial::<Fr>::rand(20, &mut test_rng());
+        let evals = vec![points.iter().map(|x| poly.evaluate(x)).collect::<Vec<_>>()];
+        let commits = vec![s.commit(&poly.coeffs).unwrap()];
+        assert_eq!(
+            (G1Affine::generator() * poly.evaluate(&x)).into_affine(),
+            commits[0].0
+        );
+        let open = s
+            .open(
+                &mut Transcript::new(b"testing"),
+                &evals,
+                &[&poly.coeffs],
+                &points,
+            )
+            .unwrap();
+        assert_eq!(
+            Ok(true),
+            s.verify(
+                &mut Transcript::new(b"testing"),
+                &commits,
+                &points,
+                &evals,
+                &open
+            )
+        );
+
+        assert_eq!(Ok(()), s.validate_srs(&mut test_rng()));
+        let mut corrupted = g1s.clone();
+        corrupted[9] = corrupted[10];
+        let bad = M1NoPrecomp::from_powers(&corrupted, &g2s).unwrap();
+        assert_eq!(
+            Err(Error::InconsistentSrs),
+            bad.validate_srs(&mut test_rng())
+        );
+
+        assert_eq!(
+            Err(Error::EmptyPowers),
+            M1NoPrecomp::from_powers(&[], &g2s).map(|_| ())
+        );
+        assert_eq!(
+            Err(Error::EmptyPowers),
+            M1NoPrecomp::from_powers(&g1s, &[]).map(|_| ())
+        );
+        assert_eq!(
+            Err(Error::NotEnoughG2Powers { needed: 2, have: 1 }),
+            M1NoPrecomp::from_powers(&g1s, &g2s[..1]).map(|_| ())
+        );
+    }
+
+    #[test]
+    fn test_commit_many() {
+        let s = M1NoPrecomp::new(64, 8.into(), &mut test_rng()).unwrap();
+        // Rows of different lengths, so the shared buffer has to shrink and grow
+        let polys = [40, 40, 10, 64, 1]
+            .iter()
+            .map(|&n| {
+                (0..n)
+                    .map(|_| Fr::rand(&mut test_rng()))
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        let commits = s.commit_many(&polys).unwrap();
+        for (poly, commit) in polys.iter().zip(&commits) {
+            assert_eq!(s.commit(poly).unwrap().0, commit.0);
+        }
+        assert!(s.commit_many(&Vec::<Vec<Fr>>::new()).unwrap().is_empty());
+        let too_large = vec![vec![Fr::from(1u64); 66]];
+        assert!(s.commit_many(&too_large).is_err());
+    }
+
+    #[test]
+    fn test_commit_try_iter() {
+        let s = M1NoPrecomp::new(64, None, &mut test_rng()).unwrap();
+        let poly = (0..60)
+            .map(|_| Fr::rand(&mut test_rng()))
+            .collect::<Vec<_>>();
+        let committed = s.commit_try_iter(poly.iter().map(|x| Ok(*x))).unwrap();
+        assert_eq!(s.commit(&poly).unwrap().0, committed.0);
+
+        // Nothing after the first error is read
+        let mut read = 0;
+        let res = s.commit_try_iter(poly.iter().enumerate().map(|(i, x)| {
+            read += 1;
+            if i == 10 {
+                Err(Error::SerializationError)
+            } else {
+                Ok(*x)
+            }
+        }));
+        assert_eq!(Some(Error::SerializationError), res.err());
+        assert_eq!(11, read);
+
+        let too_long = (0..s.powers_of_g1.len() + 1).map(|_| Ok(Fr::rand(&mut test_rng())));
+        assert!(s.commit_try_iter(too_long).is_err());
+
+        // Past the first chunk, the msm is against a later slice of the powers
+        let s = M1NoPrecomp::new(5000, 4.into(), &mut test_rng()).unwrap();
+        let poly = (0..5000)
+            .map(|_| Fr::rand(&mut test_rng()))
+            .collect::<Vec<_>>();
+        let committed = s.commit_try_iter(poly.iter().map(|x| Ok(*x))).unwrap();
+        assert_eq!(s.commit(&poly).unwrap().0, committed.0);
+    }
+
+    #[test]
+    fn test_commit_and_open() {
+        let s = M1NoPrecomp::new(64, 8.into(), &mut test_rng()).unwrap();
+        let points = (0..8)
+            .map(|_| Fr::rand(&mut test_rng()))
+            .collect::<Vec<_>>();
+        let polys = (0..5)
+            .map(|_| DensePolynomial::<Fr>::rand(40, &mut test_rng()))
+            .collect::<Vec<_>>();
+        let evals: Vec<Vec<_>> = polys
+            .iter()
+            .map(|p| points.iter().map(|x| p.evaluate(x)).collect())
+            .collect();
+        let coeffs = polys.iter().map(|p| p.coeffs.clone()).collect::<Vec<_>>();
+        let (commits, open) = s
+            .prepare(coeffs.clone())
+            .commit_and_open(&mut Transcript::new(b"testing"), &evals, &points)
+            .unwrap();
+        for (c, p) in commits.iter().zip(&coeffs) {
+            assert_eq!(s.commit(p).unwrap().0, c.0);
+        }
+        assert_eq!(
+            Ok(true),
+            s.verify(
+                &mut Transcript::new(b"testing"),
+                &commits,
+                &points,
+                &evals,
+                &open
+            )
+        );
+    }
+
+    #[test]
+    fn test_precompute_level() {
+        use ark_ec::CurveGroup;
+        let s = M1NoPrecomp::new(64, 8.into(), &mut test_rng()).unwrap();
+        let g1s = s.powers_of_g1.iter().map(|p| p.into_affine()).collect();
+        let g2s = s.powers_of_g2.iter().map(|p| p.into_affine()).collect();
+        let fast = M1NoPrecomp::new_with_precompute_level(&g1s, &g2s, 3).unwrap();
+        let points = (0..8)
+            .map(|_| Fr::rand(&mut test_rng()))
+            .collect::<Vec<_>>();
+        let poly = DensePolynomial::<Fr>::rand(40, &mut test_rng());
+        let evals = vec![points.iter().map(|x| poly.evaluate(x)).collect::<Vec<_>>()];
+        let commits = vec![fast.commit(&poly.coeffs).unwrap()];
+        assert_eq!(s.commit(&poly.coeffs).unwrap().0, commits[0].0);
+        let open = fast
+            .clone()
+            .open(
+                &mut Transcript::new(b"testing"),
+                &evals,
+                &[&poly.coeffs],
+                &points,
+            )
+            .unwrap();
+        assert_eq!(
+            Ok(true),
+            s.verify(
+                &mut Transcript::new(b"testing"),
+                &commits,
+                &points,
+                &evals,
+                &open
+            )
+        );
+        assert!(M1NoPrecomp::new_with_precompute_level(&g1s, &g2s, 12).is_err());
+    }
+
+    #[test]
+    fn test_zero_commitments() {
+        use ark_ec::CurveGroup;
+        let s = M1NoPrecomp::new(64, 4.into(), &mut test_rng()).unwrap();
+        let points = (0..4)
+            .map(|_| Fr::rand(&mut test_rng()))
+            .collect::<Vec<_>>();
+        let poly = DensePolynomial::<Fr>::rand(40, &mut test_rng());
+        let neg = poly.coeffs.iter().map(|c| -*c).collect::<Vec<_>>();
+        let zero = vec![Fr::from(0u64); 41];
+        let commits = s.commit_many(&[&zero, &poly.coeffs, &neg]).unwrap();
+        assert!(commits[0].is_zero());
+        assert!((commits[1].0 + commits[2].0).into_affine().is_zero());
+        let sum = poly
+            .coeffs
+            .iter()
+            .zip(&neg)
+            .map(|(a, b)| *a + b)
+            .collect::<Vec<_>>();
+        assert!(s.commit(&sum).unwrap().is_zero());
+
+        let evals = vec![vec![Fr::from(0u64); 4]];
+        let open = s
+            .open(&mut Transcript::new(b"testing"), &evals, &[&zero], &points)
+            .unwrap();
+        assert_eq!(
+            Ok(true),
+            s.verify(
+                &mut Transcript::new(b"testing"),
+                &commits[..1],
+                &points,
+                &evals,
+                &open
+            )
+        );
+    }
+
+    #[test]
+    fn test_threads_match_default() {
+        use ark_ec::CurveGroup;
+        let s = M1NoPrecomp::new(256, 16.into(), &mut test_rng()).unwrap();
+        let g1s = G1::normalize_batch(&s.powers_of_g1);
+        let g2s = G2::normalize_batch(&s.powers_of_g2);
+        let points = (0..16)
+            .map(|_| Fr::rand(&mut test_rng()))
+            .collect::<Vec<_>>();
+        let polys = (0..4)
+            .map(|_| DensePolynomial::<Fr>::rand(200, &mut test_rng()).coeffs)
+            .collect::<Vec<_>>();
+        let evals = polys
+            .iter()
+            .map(|p| crate::multi_evaluate(p, &points))
+            .collect::<Vec<_>>();
+        let commits = s.commit_many(&polys).unwrap();
+        let open = s
+            .open(&mut Transcript::new(b"testing"), &evals, &polys, &points)
+            .unwrap();
+
+        for nthreads in [1, 3] {
+            let t = M1NoPrecomp::new_with_threads(&g1s, &g2s, nthreads);
+            let t_commits = t.commit_many(&polys).unwrap();
+            assert_eq!(
+                commits.iter().map(|c| c.0).collect::<Vec<_>>(),
+                t_commits.iter().map(|c| c.0).collect::<Vec<_>>()
+            );
+            let t_open = t
+                .open(&mut Transcript::new(b"testing"), &evals, &polys, &points)
+                .unwrap();
+            assert!(open.eq_point(&t_open));
+            assert_eq!(
+                Ok(true),
+                t.verify(
+                    &mut Transcript::new(b"testing"),
+                    &t_commits,
+                    &points,
+                    &evals,
+                    &t_open
+                )
+            );
+        }
+    }
+
+    #[test]
+    fn test_verify_with_lag_ctx_g2_zeros() {
+        use crate::{lagrange::LagrangeInterpContext, vanishing_polynomial};
+        use ark_bls12_381::G2Projective;
+        use ark_ec::Group;
+        let s = M1NoPrecomp::new(64, 8.into(), &mut test_rng()).unwrap();
+        let points = (0..8)
+            .map(|_| Fr::rand(&mut test_rng()))
+            .collect::<Vec<_>>();
+        let poly = DensePolynomial::<Fr>::rand(40, &mut test_rng());
+        let evals = vec![points.iter().map(|x| poly.evaluate(x)).collect::<Vec<_>>()];
+        let commits = vec![s.commit(&poly.coeffs).unwrap()];
+        let open = s
+            .open(
+                &mut Transcript::new(b"testing"),
+                &evals,
+                &[&poly.coeffs],
+                &points,
+            )
+            .unwrap();
+        let lag_ctx = LagrangeInterpContext::new_from_points(&points).unwrap();
+        let g2_zeros = s.backend.msm_g2(&vanishing_polynomial(&points)).unwrap();
+        let verify = |g2_zeros: G2Projective| {
+            s.verify_with_lag_ctx_g2_zeros(
+                &mut Transcript::new(b"testing"),
+                &commits,
+                &points,
+                &evals,
+                &open,
+                &lag_ctx,
+                &g2_zeros,
+            )
+        };
+        assert_eq!(Ok(true), verify(g2_zeros));
+        assert_eq!(Ok(false), verify(g2_zeros.double()));
+    }
+
+    #[test]
+    fn test_arkworks_backend() {
+        use crate::msm::ArkworksMsm;
+        use ark_ec::CurveGroup;
+        use std::sync::Arc;
+        let s = M1NoPrecomp::new(64, 8.into(), &mut test_rng()).unwrap();
+        let g1s = s.powers_of_g1.iter().map(|p| p.into_affine()).collect();
+        let g2s = s.powers_of_g2.iter().map(|p| p.into_affine()).collect();
+        let backend = ArkworksMsm::from_projective(&s.powers_of_g1, &s.powers_of_g2);
+        let ark = M1NoPrecomp::new_with_backend(&g1s, &g2s, Arc::new(backend));
+        let points = (0..8)
+            .map(|_| Fr::rand(&mut test_rng()))
+            .collect::<Vec<_>>();
+        let poly = DensePolynomial::<Fr>::rand(40, &mut test_rng());
+        let evals = vec![points.iter().map(|x| poly.evaluate(x)).collect::<Vec<_>>()];
+        let commits = ark.commit_many(&[&poly.coeffs]).unwrap();
+        assert_eq!(s.commit(&poly.coeffs).unwrap().0, commits[0].0);
+        let open = ark
+            .open(
+                &mut Transcript::new(b"testing"),
+                &evals,
+                &[&poly.coeffs],
+                &points,
+            )
+            .unwrap();
+        let expected = s
+            .open(
+                &mut Transcript::new(b"testing"),
+                &evals,
+                &[&poly.coeffs],
+                &points,
+            )
+            .unwrap();
+        assert!(open.eq_point(&expected));
+        assert_eq!(
+            Ok(true),
+            ark.verify(
+                &mut Transcript::new(b"testing"),
+                &commits,
+                &points,
+                &evals,
+                &open
+            )
+        );
+    }
+
+    #[test]
+    fn test_prepared_batch() {
+        use crate::msm::ArkworksMsm;
+        use ark_ec::CurveGroup;
+        use std::sync::Arc;
+        let s = M1NoPrecomp::new(64, 8.into(), &mut test_rng()).unwrap();
+        let g1s = s.powers_of_g1.iter().map(|p| p.into_affine()).collect();
+        let g2s = s.powers_of_g2.iter().map(|p| p.into_affine()).collect();
+        let backend = ArkworksMsm::from_projective(&s.powers_of_g1, &s.powers_of_g2);
+        let ark = M1NoPrecomp::new_with_backend(&g1s, &g2s, Arc::new(backend));
+        let points = (0..8)
+            .map(|_| Fr::rand(&mut test_rng()))
+            .collect::<Vec<_>>();
+        let mut polys = (0..4)
+            .map(|_| DensePolynomial::<Fr>::rand(40, &mut test_rng()).coeffs)
+            .collect::<Vec<_>>();
+        polys[1].resize(60, Fr::from(0u64));
+        polys[2] = vec![Fr::from(0u64); 3];
+        let evals: Vec<Vec<_>> = polys
+            .iter()
+            .map(|p| {
+                let p = DensePolynomial::from_coefficients_slice(p);
+                points.iter().map(|x| p.evaluate(x)).collect()
+            })
+            .collect();
+        let expected = s.commit_many(&polys).unwrap();
+        let expected_open = s
+            .open(&mut Transcript::new(b"testing"), &evals, &polys, &points)
+            .unwrap();
+        for setup in [&s, &ark] {
+            let batch = setup.prepare(polys.clone());
+            let commits = batch.commit_all().unwrap();
+            for (c, e) in commits.iter().zip(&expected) {
+                assert_eq!(e.0, c.0);
+            }
+            let open = batch
+                .open(&mut Transcript::new(b"testing"), &evals, &points)
+                .unwrap();
+            assert!(open.eq_point(&expected_open));
+        }
+    }
+
+    #[test]
+    fn test_g2_zeros_cached() {
+        let s = M1NoPrecomp::new(64, 8.into(), &mut test_rng()).unwrap();
+        let points = (0..8)
+            .map(|_| Fr::rand(&mut test_rng()))
+            .collect::<Vec<_>>();
+        let poly = DensePolynomial::<Fr>::rand(40, &mut test_rng());
+        let evals = vec![points.iter().map(|x| poly.evaluate(x)).collect::<Vec<_>>()];
+        let commits = vec![s.commit(&poly.coeffs).unwrap()];
+        let open = s
+            .open(
+                &mut Transcript::new(b"testing"),
+                &evals,
+                &[&poly.coeffs],
+                &points,
+            )
+            .unwrap();
+        for _ in 0..2 {
+            assert_eq!(
+                Ok(true),
+                s.verify(
+                    &mut Transcript::new(b"testing"),
+                    &commits,
+                    &points,
+                    &evals,
+                    &open
+                )
+            );
+            assert_eq!(1, s.read_g2_zeros_cache().len());
+        }
+        let cloned = s.clone();
+        assert_eq!(1, cloned.read_g2_zeros_cache().len());
+
+        // Each point set gets its own entry
+        let other = &points[..4];
+        let other_evals = vec![evals[0][..4].to_vec()];
+        let other_open = s
+            .open(
+                &mut Transcript::new(b"testing"),
+                &other_evals,
+                &[&poly.coeffs],
+                other,
+            )
+            .unwrap();
+        assert_eq!(
+            Ok(true),
+            s.verify(
+                &mut Transcript::new(b"testing"),
+                &commits,
+                other,
+                &other_evals,
+                &other_open
+            )
+        );
+        assert_eq!(2, s.read_g2_zeros_cache().len());
+
+        // A full cache evicts the oldest point set
+        let capped = s.clone().with_g2_zeros_cache_capacity(1);
+        let verify = |s: &M1NoPrecomp, points: &[Fr], evals: &[Vec<Fr>], open: &Proof| {
+            s.verify(
+                &mut Transcript::new(b"testing"),
+                &commits,
+                points,
+                evals,
+                open,
+            )
+        };
+        assert_eq!(Ok(true), verify(&capped, &points, &evals, &open));
+        assert_eq!(Ok(true), verify(&capped, other, &other_evals, &other_open));
+        assert_eq!(1, capped.read_g2_zeros_cache().len());
+        assert!(capped.read_g2_zeros_cache().get(other).is_some());
+        let off = s.clone().with_g2_zeros_cache_capacity(0);
+        assert_eq!(Ok(true), verify(&off, &points, &evals, &open));
+        assert!(off.read_g2_zeros_cache().is_empty());
+
+        s.clear_g2_zeros_cache();
+        assert!(s.read_g2_zeros_cache().is_empty());
+        assert_eq!(
+            Ok(true),
+            s.verify(
+                &mut Transcript::new(b"testing"),
+                &commits,
+                &points,
+                &evals,
+                &open
+            )
+        );
+    }
+
     #[test]
     fn test_single_row_works() {
         let s = M1NoPrecomp::new(256, 32.into(), &mut test_rng()).unwrap();
@@ -250,4 +1518,157 @@ mod tests {
             s.verify(&mut transcript, &commits, &points, &evals, &open)
         );
     }
+
+    #[test]
+    fn test_write_read_setup() {
+        let s = M1NoPrecomp::new(32, 4.into(), &mut test_rng()).unwrap();
+        let mut bytes = Vec::new();
+        s.write_to(&mut bytes).unwrap();
+        assert_eq!(9 + 16 + 32 * 48 + 5 * 96, bytes.len());
+        let read = M1NoPrecomp::read_from(&mut &bytes[..]).unwrap();
+        assert_eq!(s.powers_of_g1, read.powers_of_g1);
+        assert_eq!(s.powers_of_g2, read.powers_of_g2);
+
+        let points = (0..4)
+            .map(|_| Fr::rand(&mut test_rng()))
+            .collect::<Vec<_>>();
+        let polys = (0..2)
+            .map(|_| DensePolynomial::<Fr>::rand(20, &mut test_rng()).coeffs)
+            .collect::<Vec<_>>();
+        let evals = polys
+            .iter()
+            .map(|p| crate::multi_evaluate(p, &points))
+            .collect::<Vec<_>>();
+        let commits = read.commit_many(&polys).unwrap();
+        for (c, p) in commits.iter().zip(&polys) {
+            assert_eq!(s.commit(p).unwrap().0, c.0);
+        }
+        let proof = read
+            .open(&mut Transcript::new(b"testing"), &evals, &polys, &points)
+            .unwrap();
+        assert_eq!(
+            Ok(true),
+            s.verify(
+                &mut Transcript::new(b"testing"),
+                &commits,
+                &points,
+                &evals,
+                &proof
+            )
+        );
+
+        assert_eq!(
+            Err(Error::SerializationError),
+            M1NoPrecomp::read_from(&mut &bytes[..bytes.len() - 1]).map(|_| ())
+        );
+        for i in [0, 8] {
+            let mut bad = bytes.clone();
+            bad[i] ^= 1;
+            assert_eq!(
+                Err(Error::SerializationError),
+                M1NoPrecomp::read_from(&mut &bad[..]).map(|_| ())
+            );
+        }
+        // A header claiming far more powers than follow fails without reserving room for them
+        let mut huge = bytes[..9].to_vec();
+        huge.extend((u64::MAX / 2).to_le_bytes());
+        huge.extend((u64::MAX / 2).to_le_bytes());
+        huge.extend(&bytes[25..]);
+        assert_eq!(
+            Err(Error::SerializationError),
+            M1NoPrecomp::read_from(&mut &huge[..]).map(|_| ())
+        );
+    }
+
+    #[test]
+    fn test_commit_g2() {
+        use ark_ec::pairing::Pairing;
+        let s = M1NoPrecomp::new(32, 8.into(), &mut test_rng()).unwrap();
+        let poly = DensePolynomial::<Fr>::rand(8, &mut test_rng());
+        let c1 = s.commit(&poly.coeffs).unwrap();
+        let c2 = s.commit_g2(&poly.coeffs).unwrap();
+        assert_eq!(
+            Bls12_381::pairing(c1.0, s.powers_of_g2[0]),
+            Bls12_381::pairing(s.powers_of_g1[0], c2)
+        );
+        // There are only 9 g2 powers
+        let poly = DensePolynomial::<Fr>::rand(9, &mut test_rng());
+        assert_eq!(
+            Err(Error::PolynomialTooLarge {
+                n_coeffs: 10,
+                expected_max: 9
+            }),
+            s.commit_g2(&poly.coeffs)
+        );
+    }
+
+    #[test]
+    fn test_new_from_secret() {
+        use ark_bls12_381::{G1Affine, G2Affine};
+        use ark_ec::AffineRepr;
+        let tau = Fr::from(1234u64);
+        let new = || {
+            M1NoPrecomp::new_from_secret(
+                16,
+                4.into(),
+                tau,
+                G1Affine::generator(),
+                G2Affine::generator(),
+            )
+        };
+        let (a, b) = (new(), new());
+        assert_eq!(a.powers_of_g1, b.powers_of_g1);
+        assert_eq!(a.powers_of_g2, b.powers_of_g2);
+
+        let generic = crate::method1::M1NoPrecomp::<Bls12_381>::new_from_secret(
+            16,
+            4.into(),
+            tau,
+            G1Affine::generator(),
+            G2Affine::generator(),
+        );
+        let poly = DensePolynomial::<Fr>::rand(15, &mut test_rng());
+        let commit = a.commit(&poly.coeffs).unwrap().0;
+        assert_eq!(commit, b.commit(&poly.coeffs).unwrap().0);
+        assert_eq!(commit, generic.commit(&poly.coeffs).unwrap().0);
+    }
+
+    #[test]
+    fn test_window_bits_match() {
+        let s = M1NoPrecomp::new(64, 4.into(), &mut test_rng()).unwrap();
+        let points = (0..4)
+            .map(|_| Fr::rand(&mut test_rng()))
+            .collect::<Vec<_>>();
+        let polys = [8, 64]
+            .map(|n| DensePolynomial::<Fr>::rand(n - 1, &mut test_rng()).coeffs)
+            .to_vec();
+        let evals = polys
+            .iter()
+            .map(|p| crate::multi_evaluate(p, &points))
+            .collect::<Vec<_>>();
+        let commits = s.commit_many(&polys).unwrap();
+        let proof = s
+            .open(&mut Transcript::new(b"testing"), &evals, &polys, &points)
+            .unwrap();
+        for wbits in [2, 8, 12] {
+            let windowed = s.clone().with_window_bits(wbits).unwrap();
+            for (p, c) in polys.iter().zip(&commits) {
+                assert_eq!(c.0, windowed.commit(p).unwrap().0);
+            }
+            let windowed_proof = windowed
+                .open(&mut Transcript::new(b"testing"), &evals, &polys, &points)
+                .unwrap();
+            assert!(proof.eq_point(&windowed_proof));
+        }
+        // 255 is the scalar size, not a window
+        for wbits in [16, 255] {
+            assert_eq!(
+                Err(Error::PrecomputeLevelTooLarge {
+                    level: wbits - 1,
+                    max: 11
+                }),
+                s.clone().with_window_bits(wbits).map(|_| ())
+            );
+        }
+    }
 }
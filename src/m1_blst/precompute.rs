@@ -1,4 +1,4 @@
-use ark_bls12_381::{Bls12_381, Fr, G2Projective as G2};
+use ark_bls12_381::{Bls12_381, Fr, G1Projective as G1, G2Projective as G2};
 use ark_poly::univariate::DensePolynomial;
 use merlin::Transcript;
 use std::usize;
@@ -6,11 +6,14 @@ use std::usize;
 #[cfg(feature = "parallel")]
 use rayon::prelude::*;
 
-use super::{fast_msm, vanishing_polynomial, Error, Proof};
+use super::{vanishing_polynomial, Error, Proof};
 use crate::lagrange::LagrangeInterpContext;
 use crate::traits::{Committer, PolyMultiProof, PolyMultiProofNoPrecomp};
-use crate::{cfg_iter, Commitment};
+use crate::{cfg_iter, check_disjoint, Commitment};
 
+/// A method 1 setup with everything that only depends on the point sets computed up front: the
+/// vanishing polynomials, their commitments in G2, and the lagrange bases. Verifying then only
+/// does the transcript, the interpolation against the cached basis, the msms, and two pairings.
 pub struct M1Precomp {
     pub inner: super::M1NoPrecomp,
     point_sets: Vec<Vec<Fr>>,
@@ -20,12 +23,47 @@ pub struct M1Precomp {
 }
 
 impl M1Precomp {
+    /// Same as [`PolyMultiProof::new`], but errors with [`Error::OverlappingPointSets`] if any
+    /// point is in more than one point set
+    pub fn new_strict(
+        max_coeffs: usize,
+        point_sets: Vec<Vec<Fr>>,
+        r: &mut impl ark_std::rand::RngCore,
+    ) -> Result<Self, Error> {
+        check_disjoint(&point_sets)?;
+        <Self as PolyMultiProof<Bls12_381>>::new(max_coeffs, point_sets, r)
+    }
+
+    /// Same as [`M1Precomp::from_inner`], but errors with [`Error::OverlappingPointSets`] if any
+    /// point is in more than one point set
+    pub fn from_inner_strict(
+        inner: super::M1NoPrecomp,
+        point_sets: Vec<Vec<Fr>>,
+    ) -> Result<Self, Error> {
+        check_disjoint(&point_sets)?;
+        Self::from_inner(inner, point_sets)
+    }
+
+    /// The number of point sets this setup can open at
+    pub fn num_point_sets(&self) -> usize {
+        self.point_sets.len()
+    }
+
+    /// The number of points in the point set at `index`, or `None` if there is no such set
+    pub fn point_set_len(&self, index: usize) -> Option<usize> {
+        self.point_sets.get(index).map(|ps| ps.len())
+    }
+
     pub fn from_inner(inner: super::M1NoPrecomp, point_sets: Vec<Vec<Fr>>) -> Result<Self, Error> {
+        if point_sets.iter().any(|ps| ps.is_empty()) {
+            return Err(Error::NoPointsGiven);
+        }
+        crate::check_point_set_sizes(&point_sets, inner.powers_of_g2.len())?;
         let vanishing_polys: Vec<_> = cfg_iter!(point_sets)
             .map(|(_, ps)| vanishing_polynomial(ps))
             .collect();
         let g2_zeros = cfg_iter!(vanishing_polys)
-            .map(|(_, p)| fast_msm::g2_msm(&inner.prepped_g2s, &p, inner.powers_of_g2.len()))
+            .map(|(_, p)| inner.backend.msm_g2(p))
             .collect::<Result<Vec<_>, Error>>()?;
         let lagrange_ctxs = cfg_iter!(point_sets)
             .map(|(_, ps)| LagrangeInterpContext::new_from_points(ps.as_ref()))
@@ -42,8 +80,17 @@ impl M1Precomp {
 }
 
 impl Committer<Bls12_381> for M1Precomp {
-    fn commit(&self, poly: impl AsRef<[Fr]>) -> Result<Commitment<Bls12_381>, Error> {
-        self.inner.commit(poly)
+    fn commit_projective(&self, poly: impl AsRef<[Fr]>) -> Result<G1, Error> {
+        self.inner.commit_projective(poly)
+    }
+    fn commit_many(
+        &self,
+        polys: &[impl AsRef<[Fr]> + Sync],
+    ) -> Result<Vec<Commitment<Bls12_381>>, Error> {
+        self.inner.commit_many(polys)
+    }
+    fn max_coeffs(&self) -> usize {
+        self.inner.max_coeffs()
     }
 }
 
@@ -75,27 +122,41 @@ impl PolyMultiProof<Bls12_381> for M1Precomp {
         polys: &[impl AsRef<[Fr]>],
         point_set_index: usize,
     ) -> Result<Self::Proof, Error> {
+        let points =
+            self.point_sets
+                .get(point_set_index)
+                .ok_or(Error::PointSetIndexOutOfRange {
+                    index: point_set_index,
+                    n: self.point_sets.len(),
+                })?;
         self.inner.open_with_vanishing_poly(
             transcript,
             evals,
             polys,
-            &self.point_sets[point_set_index],
+            points,
             &self.vanishing_polys[point_set_index],
         )
     }
 
-    fn verify(
+    fn verify_unchecked(
         &self,
         transcript: &mut Transcript,
-        commits: &[Commitment<Bls12_381>],
+        commits: impl AsRef<[Commitment<Bls12_381>]>,
         point_set_index: usize,
         evals: &[impl AsRef<[Fr]>],
         proof: &Self::Proof,
     ) -> Result<bool, Error> {
-        self.inner.verify_with_lag_ctx_g2_zeros(
+        let points =
+            self.point_sets
+                .get(point_set_index)
+                .ok_or(Error::PointSetIndexOutOfRange {
+                    index: point_set_index,
+                    n: self.point_sets.len(),
+                })?;
+        self.inner.verify_with_lag_ctx_g2_zeros_unchecked(
             transcript,
-            commits,
-            &self.point_sets[point_set_index],
+            commits.as_ref(),
+            points,
             evals,
             proof,
             &self.lagrange_ctxs[point_set_index],
@@ -110,6 +171,7 @@ mod tests {
     use crate::{
         test_rng,
         traits::{Committer, PolyMultiProof},
+        Error,
     };
     use ark_bls12_381::Fr;
     use ark_poly::{univariate::DensePolynomial, DenseUVPolynomial, Polynomial};
@@ -144,5 +206,15 @@ mod tests {
             Ok(true),
             s.verify(&mut transcript, &commits, 0, &evals, &open)
         );
+
+        assert_eq!(
+            Some(Error::PointSetIndexOutOfRange { index: 1, n: 1 }),
+            s.open(&mut Transcript::new(b"testing"), &evals, &coeffs, 1)
+                .err()
+        );
+        assert_eq!(
+            Err(Error::PointSetIndexOutOfRange { index: 1, n: 1 }),
+            s.verify(&mut Transcript::new(b"testing"), &commits, 1, &evals, &open)
+        );
     }
 }
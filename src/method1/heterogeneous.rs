@@ -0,0 +1,200 @@
+//! Opening each polynomial at its own set of points with a single proof.
+//!
+//! With $T$ the union of the point sets $S_i$, the prover commits to
+//! $q = \sum_i \gamma^i (f_i - r_i) / Z_{S_i}$, which is $\sum_i \gamma^i (f_i - r_i) Z_{T
+//! \setminus S_i} / Z_T$. The verifier checks
+//! $\prod_i e(\gamma^i (C_i - g_1^{r_i(x)}), g_2^{Z_{T \setminus S_i}(x)}) = e(\pi, g_2^{Z_T(x)})$
+//! with one pairing per polynomial, so the union of the point sets has to fit in the g2 powers.
+use ark_ec::{pairing::Pairing, AffineRepr, CurveGroup};
+use ark_poly::univariate::DensePolynomial;
+use ark_std::Zero;
+use merlin::Transcript;
+use std::collections::HashSet;
+
+use super::{M1NoPrecomp, Proof};
+use crate::{
+    gen_powers, get_challenge, get_field_size, lagrange::LagrangeInterpContext, poly_div_q_r,
+    transcribe_header, transcribe_points_and_evals, vanishing_polynomial, Commitment, Error,
+    METHOD1_TRANSCRIPT_TAG,
+};
+
+impl<E: Pairing> M1NoPrecomp<E> {
+    /// Opens each polynomial at its own points, where `openings[i]` is polynomial `i`, its
+    /// points, and its evals at those points. The point sets may overlap. A polynomial with no
+    /// points is skipped, its commitment isn't part of the check.
+    pub fn open_heterogeneous(
+        &self,
+        transcript: &mut Transcript,
+        openings: &[(&[E::ScalarField], &[E::ScalarField], &[E::ScalarField])],
+    ) -> Result<Proof<E>, Error> {
+        let sets = openings
+            .iter()
+            .map(|(_, points, evals)| (*points, *evals))
+            .collect::<Vec<_>>();
+        let gamma = transcribe_heterogeneous(transcript, &sets)?;
+        let gammas = gen_powers(gamma, openings.len());
+
+        let mut q = DensePolynomial::zero();
+        for ((poly, points, _), g) in openings.iter().zip(gammas) {
+            if points.is_empty() {
+                continue;
+            }
+            // (f_i - r_i) / Z_{S_i}, the remainder is r_i
+            let (q_i, _) = poly_div_q_r(
+                DensePolynomial {
+                    coeffs: poly.to_vec(),
+                }
+                .into(),
+                (&vanishing_polynomial(points)).into(),
+            )?;
+            q += &(&DensePolynomial { coeffs: q_i } * g);
+        }
+        Ok(Proof(
+            crate::curve_msm::<E::G1>(&self.powers_of_g1, &q.coeffs)?.into_affine(),
+        ))
+    }
+
+    /// Verifies a proof from [`M1NoPrecomp::open_heterogeneous`], where `openings[i]` is the
+    /// points and evals of the polynomial committed to in `commits[i]`
+    pub fn verify_heterogeneous(
+        &self,
+        transcript: &mut Transcript,
+        commits: &[Commitment<E>],
+        openings: &[(&[E::ScalarField], &[E::ScalarField])],
+        proof: &Proof<E>,
+    ) -> Result<bool, Error> {
+        crate::traits::check_points(commits, proof)?;
+        if commits.len() != openings.len() {
+            return Err(Error::CommitsIncorrectSize {
+                n: commits.len(),
+                expected: openings.len(),
+            });
+        }
+        let gamma = transcribe_heterogeneous(transcript, openings)?;
+        let gammas = gen_powers(gamma, openings.len());
+
+        let union = point_union(openings.iter().map(|(points, _)| *points));
+        let g2_zeros = self.g2_zeros(&union, &vanishing_polynomial(&union))?;
+        if g2_zeros.is_zero() {
+            return Err(Error::PointIsSecret);
+        }
+
+        let mut g1s = vec![-proof.0.into_group()];
+        let mut g2s = vec![g2_zeros];
+        for ((commit, (points, evals)), g) in commits.iter().zip(openings).zip(gammas) {
+            if points.is_empty() {
+                continue;
+            }
+            // gamma^i r_i, interpolated from the evals
+            let lag_ctx = LagrangeInterpContext::new_from_points(points)?;
+            let gamma_ri = lag_ctx.lagrange_interp_linear_combo(&[evals], &[g])?;
+            let ri_pt = crate::curve_msm::<E::G1>(&self.powers_of_g1, &gamma_ri.coeffs)?;
+            g1s.push(commit.0 * g - ri_pt);
+
+            let in_set = points.iter().collect::<HashSet<_>>();
+            let others = union
+                .iter()
+                .filter(|p| !in_set.contains(p))
+                .copied()
+                .collect::<Vec<_>>();
+            g2s.push(crate::curve_msm::<E::G2>(
+                &self.powers_of_g2,
+                &vanishing_polynomial(&others),
+            )?);
+        }
+        Ok(E::multi_pairing(g1s, g2s).is_zero())
+    }
+}
+
+/// Absorbs the number of openings and then each one's points and evals, skipping the ones with
+/// no points, and draws gamma. Errors with [`Error::NoPointsGiven`] if every one is skipped.
+fn transcribe_heterogeneous<F: ark_ff::PrimeField>(
+    transcript: &mut Transcript,
+    openings: &[(&[F], &[F])],
+) -> Result<F, Error> {
+    if openings.iter().all(|(points, _)| points.is_empty()) {
+        return Err(Error::NoPointsGiven);
+    }
+    let field_size_bytes = get_field_size::<F>();
+    transcribe_header(transcript, METHOD1_TRANSCRIPT_TAG);
+    transcript.append_u64(b"open heterogeneous", openings.len() as u64);
+    for (points, evals) in openings {
+        if points.is_empty() {
+            continue;
+        }
+        transcribe_points_and_evals(transcript, points, &[*evals], field_size_bytes)?;
+    }
+    Ok(get_challenge(transcript, b"open gamma", field_size_bytes))
+}
+
+/// Every point in any of the sets, once, in the order they first appear
+fn point_union<'a, F: ark_ff::Field>(sets: impl Iterator<Item = &'a [F]>) -> Vec<F> {
+    let mut seen = HashSet::new();
+    sets.flatten()
+        .filter(|p| seen.insert(*p))
+        .copied()
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{method1::M1NoPrecomp, test_rng, traits::Committer, Error};
+    use ark_bls12_381::{Bls12_381, Fr};
+    use ark_poly::{univariate::DensePolynomial, DenseUVPolynomial, Polynomial};
+    use ark_std::UniformRand;
+    use merlin::Transcript;
+
+    #[test]
+    fn test_heterogeneous_open() {
+        let s = M1NoPrecomp::<Bls12_381>::new(64, 16.into(), &mut test_rng()).unwrap();
+        let points = (0..10)
+            .map(|_| Fr::rand(&mut test_rng()))
+            .collect::<Vec<_>>();
+        let polys = (0..4)
+            .map(|_| DensePolynomial::<Fr>::rand(40, &mut test_rng()))
+            .collect::<Vec<_>>();
+        // Disjoint, overlapping with the first two, and empty
+        let sets = [&points[..3], &points[3..7], &points[2..10], &points[..0]];
+        let evals = polys
+            .iter()
+            .zip(sets)
+            .map(|(p, set)| set.iter().map(|x| p.evaluate(x)).collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        let commits = polys
+            .iter()
+            .map(|p| s.commit(&p.coeffs).unwrap())
+            .collect::<Vec<_>>();
+
+        let check = |n: usize, evals: &[Vec<Fr>]| {
+            let openings = (0..n)
+                .map(|i| (&polys[i].coeffs[..], sets[i], &evals[i][..]))
+                .collect::<Vec<_>>();
+            let proof = s
+                .open_heterogeneous(&mut Transcript::new(b"testing"), &openings)
+                .unwrap();
+            let openings = (0..n).map(|i| (sets[i], &evals[i][..])).collect::<Vec<_>>();
+            s.verify_heterogeneous(
+                &mut Transcript::new(b"testing"),
+                &commits[..n],
+                &openings,
+                &proof,
+            )
+        };
+        // Two polynomials at disjoint points
+        assert_eq!(Ok(true), check(2, &evals));
+        assert_eq!(Ok(true), check(4, &evals));
+
+        let mut wrong = evals.clone();
+        wrong[2][5] += Fr::from(1u64);
+        assert_eq!(Ok(false), check(4, &wrong));
+
+        assert_eq!(
+            Err(Error::NoPointsGiven),
+            s.open_heterogeneous(
+                &mut Transcript::new(b"testing"),
+                &[(&polys[0].coeffs[..], &points[..0], &[][..])]
+            )
+            .map(|p| p.0)
+        );
+    }
+}
@@ -0,0 +1,341 @@
+//! Hiding commitments and openings for method 1, following the `PolyCommit_Ped` construction of
+//! Kate, Zaverucha and Goldberg. A commitment is $g_1^{f(x)} h^{\hat r(x)}$ for a random blinding
+//! polynomial $\hat r$, so commitments to the same polynomial are unrelated.
+//!
+//! The blinding is a polynomial rather than a single scalar `r`: opening has to carry the
+//! blinding through the quotient, and with $C = g_1^{f(x)} h^r$ the verifier would need `r`
+//! itself to remove it, which unblinds the commitment. Instead the proof reveals the blinding
+//! polynomial at the opened points only, which says nothing about the committed polynomial as
+//! long as fewer points are opened than the blinding polynomial has coefficients.
+//!
+//! The blinding powers live in [`M1Hiding`] rather than the plain setup, so setups that never
+//! hide don't pay for them. Only method 1 has a hiding path. A setup loaded for
+//! [`crate::m1_blst::M1NoPrecomp`] can be used here by building the generic
+//! [`M1NoPrecomp`] from the same powers, the proofs are the same.
+use ark_ec::{pairing::Pairing, CurveGroup};
+use ark_poly::univariate::DensePolynomial;
+use ark_serialize::{
+    CanonicalDeserialize, CanonicalSerialize, Compress, Read, SerializationError, Valid, Validate,
+    Write,
+};
+use ark_std::{rand::RngCore, UniformRand, Zero};
+use merlin::Transcript;
+
+use super::{verifier_gamma, M1NoPrecomp};
+use crate::{
+    gen_curve_powers, gen_powers, lagrange::LagrangeInterpContext, linear_combination,
+    poly_div_q_r, vanishing_polynomial, Commitment, Error,
+};
+
+/// A method 1 setup along with powers of the same secret on an independent blinding generator
+/// $h$, for hiding commitments
+#[derive(Clone, Debug)]
+pub struct M1Hiding<E: Pairing> {
+    pub inner: M1NoPrecomp<E>,
+    /// Powers of the secret on $h$, one per coefficient of the blinding polynomials
+    pub powers_of_h: Vec<E::G1Affine>,
+}
+
+/// An opening of hiding commitments made with [`M1Hiding::commit_hiding`]
+#[derive(Debug, Clone)]
+pub struct HidingProof<E: Pairing> {
+    /// The commitment to the quotients of both the polynomials and their blindings
+    pub w: E::G1Affine,
+    /// The remainder of $\sum_i \gamma^i \hat r_i$ divided by the vanishing polynomial of the
+    /// points, i.e. the combined blinding interpolated at the points
+    pub blinding_remainder: Vec<E::ScalarField>,
+}
+
+impl<E: Pairing> CanonicalSerialize for HidingProof<E> {
+    fn serialize_with_mode<W: Write>(
+        &self,
+        mut writer: W,
+        compress: Compress,
+    ) -> Result<(), SerializationError> {
+        self.w.serialize_with_mode(&mut writer, compress)?;
+        self.blinding_remainder
+            .serialize_with_mode(&mut writer, compress)
+    }
+
+    fn serialized_size(&self, compress: Compress) -> usize {
+        self.w.serialized_size(compress) + self.blinding_remainder.serialized_size(compress)
+    }
+}
+
+impl<E: Pairing> Valid for HidingProof<E> {
+    fn check(&self) -> Result<(), SerializationError> {
+        self.w.check()?;
+        self.blinding_remainder.check()
+    }
+}
+
+impl<E: Pairing> CanonicalDeserialize for HidingProof<E> {
+    fn deserialize_with_mode<R: Read>(
+        mut reader: R,
+        compress: Compress,
+        validate: Validate,
+    ) -> Result<Self, SerializationError> {
+        Ok(HidingProof {
+            w: E::G1Affine::deserialize_with_mode(&mut reader, compress, validate)?,
+            blinding_remainder: Vec::deserialize_with_mode(&mut reader, compress, validate)?,
+        })
+    }
+}
+
+impl<E: Pairing> M1Hiding<E> {
+    /// Same as [`PolyMultiProofNoPrecomp::new`](crate::traits::PolyMultiProofNoPrecomp::new),
+    /// along with `max_pts + 1` blinding powers on a random $h$
+    pub fn new(
+        max_coeffs: usize,
+        max_pts: Option<usize>,
+        rng: &mut impl RngCore,
+    ) -> Result<Self, Error> {
+        let x = E::ScalarField::rand(rng);
+        let max_pts = max_pts.unwrap_or(max_coeffs) + 1;
+        let x_powers = gen_powers(x, std::cmp::max(max_coeffs, max_pts));
+        let inner = M1NoPrecomp {
+            powers_of_g1: gen_curve_powers::<E::G1>(x_powers.as_ref(), rng),
+            powers_of_g2: gen_curve_powers::<E::G2>(x_powers[..max_pts].as_ref(), rng),
+        };
+        let powers_of_h = gen_curve_powers::<E::G1>(x_powers[..max_pts].as_ref(), rng);
+        Self::from_parts(inner, powers_of_h)
+    }
+
+    /// Uses blinding powers loaded alongside the rest of a setup. They must be powers of the
+    /// same secret as `inner`'s on a generator whose discrete log is unknown. Errors with
+    /// [`Error::NoBlindingGenerator`] if there are none.
+    pub fn from_parts(inner: M1NoPrecomp<E>, powers_of_h: Vec<E::G1Affine>) -> Result<Self, Error> {
+        if powers_of_h.is_empty() {
+            return Err(Error::NoBlindingGenerator);
+        }
+        Ok(Self { inner, powers_of_h })
+    }
+
+    /// Commits to `poly` with a fresh random blinding polynomial, returning the commitment and
+    /// the blinding, which is needed to open it with [`M1Hiding::open_hiding`]. The blinding
+    /// has as many coefficients as there are blinding powers, so openings at fewer points than
+    /// that reveal nothing about `poly`.
+    pub fn commit_hiding(
+        &self,
+        poly: impl AsRef<[E::ScalarField]>,
+        rng: &mut impl RngCore,
+    ) -> Result<(Commitment<E>, Vec<E::ScalarField>), Error> {
+        let blinding = (0..self.powers_of_h.len())
+            .map(|_| E::ScalarField::rand(rng))
+            .collect::<Vec<_>>();
+        let commit = crate::curve_msm::<E::G1>(&self.inner.powers_of_g1, poly.as_ref())?
+            + crate::curve_msm::<E::G1>(&self.powers_of_h, &blinding)?;
+        Ok((Commitment(commit.into_affine()), blinding))
+    }
+
+    /// Opens commitments made with [`M1Hiding::commit_hiding`], where `blindings[i]` is the
+    /// blinding returned for `polys[i]`. The transcript is the same as for
+    /// [`PolyMultiProofNoPrecomp::open`](crate::traits::PolyMultiProofNoPrecomp::open). Errors
+    /// with [`Error::BlindingTooLarge`] if a blinding has more coefficients than there are
+    /// blinding powers.
+    pub fn open_hiding(
+        &self,
+        transcript: &mut Transcript,
+        evals: &[impl AsRef<[E::ScalarField]>],
+        polys: &[impl AsRef<[E::ScalarField]>],
+        blindings: &[impl AsRef<[E::ScalarField]>],
+        points: &[E::ScalarField],
+    ) -> Result<HidingProof<E>, Error> {
+        if blindings.len() != polys.len() {
+            return Err(Error::BlindingsIncorrectSize {
+                n: blindings.len(),
+                expected: polys.len(),
+            });
+        }
+        if let Some((index, b)) = blindings
+            .iter()
+            .enumerate()
+            .find(|(_, b)| b.as_ref().len() > self.powers_of_h.len())
+        {
+            return Err(Error::BlindingTooLarge {
+                index,
+                n_coeffs: b.as_ref().len(),
+                expected_max: self.powers_of_h.len(),
+            });
+        }
+        // Draw the same gamma the opening below will draw
+        let gamma = verifier_gamma(&mut transcript.clone(), points, evals)?;
+        let vp = vanishing_polynomial(points);
+        let proof = self
+            .inner
+            .open_with_vanishing_poly(transcript, evals, polys, points, &vp)?;
+
+        let gammas = gen_powers(gamma, blindings.len());
+        let blinding_sum = linear_combination(blindings, &gammas)?;
+        let (q, blinding_remainder) = poly_div_q_r(
+            DensePolynomial {
+                coeffs: blinding_sum,
+            }
+            .into(),
+            (&vp).into(),
+        )?;
+        let w = proof.0.into_group() + crate::curve_msm::<E::G1>(&self.powers_of_h, &q)?;
+        Ok(HidingProof {
+            w: w.into_affine(),
+            blinding_remainder,
+        })
+    }
+
+    /// Verifies a [`HidingProof`] from [`M1Hiding::open_hiding`]. This is the usual check
+    /// $e(\sum_i \gamma^i (C_i - g_1^{r_i(x)}), g_2) = e(w, g_2^{z(x)})$ with the revealed
+    /// blinding remainder also taken out of the left hand side.
+    pub fn verify_hiding(
+        &self,
+        transcript: &mut Transcript,
+        commits: impl AsRef<[Commitment<E>]>,
+        points: &[E::ScalarField],
+        evals: &[impl AsRef<[E::ScalarField]>],
+        proof: &HidingProof<E>,
+    ) -> Result<bool, Error> {
+        crate::traits::check_points(commits.as_ref(), proof)?;
+        if proof.blinding_remainder.len() > points.len() {
+            return Err(Error::PolynomialTooLarge {
+                n_coeffs: proof.blinding_remainder.len(),
+                expected_max: points.len(),
+            });
+        }
+        let g2_zeros = self.inner.g2_zeros(points, &vanishing_polynomial(points))?;
+        if g2_zeros.is_zero() {
+            return Err(Error::PointIsSecret);
+        }
+        let lag_ctx = LagrangeInterpContext::new_from_points(points)?;
+        let lhs = self
+            .inner
+            .verify_lhs(transcript, commits.as_ref(), points, evals, &lag_ctx)?
+            - crate::curve_msm::<E::G1>(&self.powers_of_h, &proof.blinding_remainder)?;
+        let g2 = self.inner.powers_of_g2[0];
+        Ok(E::pairing(lhs, g2) == E::pairing(proof.w, g2_zeros))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{HidingProof, M1Hiding};
+    use crate::{test_rng, traits::Committer, Error};
+    use ark_bls12_381::{Bls12_381, Fr};
+    use ark_poly::{univariate::DensePolynomial, DenseUVPolynomial, Polynomial};
+    use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+    use ark_std::UniformRand;
+    use merlin::Transcript;
+
+    #[test]
+    fn test_hiding_open_verify() {
+        let s = M1Hiding::<Bls12_381>::new(64, 8.into(), &mut test_rng()).unwrap();
+        let points = (0..4)
+            .map(|_| Fr::rand(&mut test_rng()))
+            .collect::<Vec<_>>();
+        let polys = (0..3)
+            .map(|_| DensePolynomial::<Fr>::rand(40, &mut test_rng()))
+            .collect::<Vec<_>>();
+        let evals = polys
+            .iter()
+            .map(|p| points.iter().map(|x| p.evaluate(x)).collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        let coeffs = polys.iter().map(|p| &p.coeffs).collect::<Vec<_>>();
+
+        let open_verify = |evals: &[Vec<Fr>]| {
+            let (commits, blindings): (Vec<_>, Vec<_>) = coeffs
+                .iter()
+                .map(|p| s.commit_hiding(p, &mut test_rng()).unwrap())
+                .unzip();
+            let proof = s
+                .open_hiding(
+                    &mut Transcript::new(b"testing"),
+                    evals,
+                    &coeffs,
+                    &blindings,
+                    &points,
+                )
+                .unwrap();
+            let verified = s.verify_hiding(
+                &mut Transcript::new(b"testing"),
+                &commits,
+                &points,
+                evals,
+                &proof,
+            );
+            (commits, proof, verified)
+        };
+
+        let (commits_a, proof, verified) = open_verify(&evals);
+        assert_eq!(Ok(true), verified);
+        let (commits_b, _, verified) = open_verify(&evals);
+        assert_eq!(Ok(true), verified);
+        // Same polynomials, different commitments, and neither is the plain commitment
+        for ((a, b), p) in commits_a.iter().zip(&commits_b).zip(&coeffs) {
+            assert_ne!(a.0, b.0);
+            assert_ne!(a.0, s.inner.commit(p).unwrap().0);
+        }
+
+        let mut bytes = Vec::new();
+        proof.serialize_compressed(&mut bytes).unwrap();
+        let proof2 = HidingProof::<Bls12_381>::deserialize_compressed(&bytes[..]).unwrap();
+        assert_eq!(
+            Ok(true),
+            s.verify_hiding(
+                &mut Transcript::new(b"testing"),
+                &commits_a,
+                &points,
+                &evals,
+                &proof2,
+            )
+        );
+
+        let mut wrong_evals = evals.clone();
+        wrong_evals[1][2] += Fr::from(1u64);
+        assert_eq!(
+            Ok(false),
+            s.verify_hiding(
+                &mut Transcript::new(b"testing"),
+                &commits_a,
+                &points,
+                &wrong_evals,
+                &proof,
+            )
+        );
+    }
+
+    #[test]
+    fn test_hiding_errors() {
+        let s = M1Hiding::<Bls12_381>::new(16, 4.into(), &mut test_rng()).unwrap();
+        let poly = DensePolynomial::<Fr>::rand(10, &mut test_rng());
+        assert_eq!(
+            Err(Error::BlindingsIncorrectSize { n: 0, expected: 1 }),
+            s.open_hiding(
+                &mut Transcript::new(b"testing"),
+                &[[poly.evaluate(&Fr::from(1u64))]],
+                &[&poly.coeffs],
+                &Vec::<Vec<Fr>>::new(),
+                &[Fr::from(1u64)],
+            )
+            .map(|_| ())
+        );
+
+        // There are 5 blinding powers
+        assert_eq!(
+            Err(Error::BlindingTooLarge {
+                index: 0,
+                n_coeffs: 6,
+                expected_max: 5
+            }),
+            s.open_hiding(
+                &mut Transcript::new(b"testing"),
+                &[[poly.evaluate(&Fr::from(1u64))]],
+                &[&poly.coeffs],
+                &[vec![Fr::from(1u64); 6]],
+                &[Fr::from(1u64)],
+            )
+            .map(|_| ())
+        );
+
+        assert_eq!(
+            Err(Error::NoBlindingGenerator),
+            M1Hiding::from_parts(s.inner, Vec::new()).map(|_| ())
+        );
+    }
+}
@@ -3,21 +3,41 @@ use crate::{
     traits::{Committer, PolyMultiProofNoPrecomp},
 };
 use ark_poly::univariate::DensePolynomial;
-use ark_std::UniformRand;
+use ark_serialize::{
+    CanonicalDeserialize, CanonicalSerialize, Compress, Read, SerializationError, Valid, Validate,
+    Write,
+};
+use ark_std::{UniformRand, Zero};
 use merlin::Transcript;
 use std::usize;
 
-use ark_ec::{pairing::Pairing, CurveGroup};
+use ark_ec::{
+    pairing::{Pairing, PairingOutput},
+    AffineRepr, CurveGroup,
+};
+use ark_ff::PrimeField;
 use ark_std::rand::RngCore;
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
 
-use crate::{get_challenge, get_field_size, transcribe_points_and_evals, Commitment};
+use crate::{
+    cfg_iter, check_eval_shape, check_polys_fit, evals_all_zero, get_challenge, get_field_size,
+    transcribe_header, transcribe_points_and_evals, transcript::ProofTranscript, Commitment,
+    VerifyFailure, METHOD1_TRANSCRIPT_TAG,
+};
 
 use super::{
-    gen_curve_powers, gen_powers, linear_combination, poly_div_q_r, vanishing_polynomial, Error,
+    gen_curve_powers, gen_curve_powers_with_generator, gen_powers, gen_powers_small,
+    linear_combination, poly_div_q_r, vanishing_polynomial, Error,
 };
 
+mod heterogeneous;
+pub mod hiding;
 pub mod precompute;
 
+/// The transcripts [`M1NoPrecomp::open_single`] and [`M1NoPrecomp::verify_single`] start from
+pub const SINGLE_OPEN_TRANSCRIPT: ProofTranscript = ProofTranscript::new(b"method1 single open");
+
 #[derive(Clone, Debug)]
 pub struct M1NoPrecomp<E: Pairing> {
     pub powers_of_g1: Vec<E::G1Affine>,
@@ -27,8 +47,299 @@ pub struct M1NoPrecomp<E: Pairing> {
 #[derive(Debug, Clone)]
 pub struct Proof<E: Pairing>(E::G1Affine);
 
+impl<E: Pairing> Proof<E> {
+    /// The number of bytes a proof serializes to, without needing a proof at hand
+    pub fn size_in_bytes(compress: Compress) -> usize {
+        E::G1Affine::generator().serialized_size(compress)
+    }
+
+    /// Whether the proofs are the same group element, regardless of how the points are represented
+    pub fn eq_point(&self, other: &Self) -> bool {
+        self.0.into_group() == other.0.into_group()
+    }
+
+    /// The commitment to the quotient polynomial, which is all the proof consists of
+    pub fn quotient_commitment(&self) -> E::G1Affine {
+        self.0
+    }
+
+    /// The compressed serialization, 48 bytes for BLS12-381
+    pub fn to_compressed_bytes(&self) -> Vec<u8> {
+        crate::to_compressed_bytes(self)
+    }
+
+    /// Reads a proof from exactly its compressed serialization. Errors with
+    /// [`Error::InvalidPoint`] if its points aren't on the curve or in the prime order subgroup,
+    /// and [`Error::MalformedProof`] for anything else.
+    pub fn from_compressed_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        crate::from_exact_compressed(bytes, Error::MalformedProof)
+    }
+}
+
+/// The proof-independent side of a verification, from [`M1NoPrecomp::prepare_verify`]
+#[derive(Debug, Clone)]
+pub struct VerifyState<E: Pairing> {
+    lhs: PairingOutput<E>,
+    g2_zeros: E::G2,
+}
+
+impl<E: Pairing> VerifyState<E> {
+    fn finish_detailed(&self, proof: &Proof<E>) -> Result<(), VerifyFailure<E>> {
+        let rhs = E::pairing(proof.0, self.g2_zeros);
+        if self.lhs != rhs {
+            return Err(VerifyFailure::PairingMismatch { lhs: self.lhs, rhs });
+        }
+        Ok(())
+    }
+}
+
+/// A commitment to $f$ along with a commitment to $X^{n - b} f$, where $n$ is the number of g1
+/// powers and $b$ the bound. The shifted polynomial only fits in the powers when
+/// $\deg f < b$, which [`M1NoPrecomp::verify_degree_bound`] checks. The bound isn't carried
+/// here, the verifier has to supply the one it expects.
+#[derive(Debug, Clone)]
+pub struct DegreeBoundedCommitment<E: Pairing> {
+    pub commit: Commitment<E>,
+    pub shifted: Commitment<E>,
+}
+
+impl<E: Pairing> std::fmt::Display for Proof<E> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        crate::fmt_compressed_hex(f, "Proof", self)
+    }
+}
+
+impl<E: Pairing> CanonicalSerialize for Proof<E> {
+    fn serialize_with_mode<W: Write>(
+        &self,
+        writer: W,
+        compress: Compress,
+    ) -> Result<(), SerializationError> {
+        self.0.serialize_with_mode(writer, compress)
+    }
+
+    fn serialized_size(&self, compress: Compress) -> usize {
+        self.0.serialized_size(compress)
+    }
+}
+
+impl<E: Pairing> Valid for Proof<E> {
+    fn check(&self) -> Result<(), SerializationError> {
+        self.0.check()
+    }
+}
+
+impl<E: Pairing> CanonicalDeserialize for Proof<E> {
+    fn deserialize_with_mode<R: Read>(
+        reader: R,
+        compress: Compress,
+        validate: Validate,
+    ) -> Result<Self, SerializationError> {
+        Ok(Proof(E::G1Affine::deserialize_with_mode(
+            reader, compress, validate,
+        )?))
+    }
+}
+
 impl<E: Pairing> M1NoPrecomp<E> {
-    fn open_with_vanishing_poly(
+    /// The g1 generator raised to the powers of the secret
+    pub fn powers_of_g1(&self) -> &[E::G1Affine] {
+        &self.powers_of_g1
+    }
+
+    /// The g2 generator raised to the powers of the secret
+    pub fn powers_of_g2(&self) -> &[E::G2Affine] {
+        &self.powers_of_g2
+    }
+
+    /// **Insecure, for tests only.** Builds the same shape of setup as
+    /// [`PolyMultiProofNoPrecomp::new`], but from the given secret `tau` and generators, so the
+    /// powers, and so every commitment and proof, can be reproduced exactly, e.g. to match test
+    /// vectors from another implementation. Anyone who knows `tau` can forge proofs.
+    ///
+    /// There are no blinding powers, their generator would need an unknown discrete log.
+    pub fn new_from_secret(
+        max_coeffs: usize,
+        max_pts: Option<usize>,
+        tau: E::ScalarField,
+        g1_gen: E::G1Affine,
+        g2_gen: E::G2Affine,
+    ) -> Self {
+        let max_pts = max_pts.unwrap_or(max_coeffs) + 1;
+        let x_powers = gen_powers(tau, std::cmp::max(max_coeffs, max_pts));
+        M1NoPrecomp {
+            powers_of_g1: gen_curve_powers_with_generator(&x_powers, g1_gen.into_group()),
+            powers_of_g2: gen_curve_powers_with_generator(
+                &x_powers[..max_pts],
+                g2_gen.into_group(),
+            ),
+        }
+    }
+
+    /// Checks that the powers are consistent powers of one secret on each generator, e.g. after
+    /// loading them from an untrusted source. Errors with [`Error::InconsistentSrs`] if not.
+    pub fn validate_srs(&self, rng: &mut impl RngCore) -> Result<(), Error> {
+        crate::validate_powers::<E>(&self.powers_of_g1, &self.powers_of_g2, rng)
+    }
+
+    /// Appends `additional_g2`, the next g2 powers of the secret, so openings can be at more
+    /// points than the setup was made for. Only whoever knows the secret, e.g. in tests or
+    /// during a ceremony, can compute them. Each one is checked to be the previous power times
+    /// the secret with $e(g_1^x, g_2^{x^{i-1}}) = e(g_1, g_2^{x^i})$, erroring with
+    /// [`Error::InconsistentSrs`] without changing the setup if not. That takes $g_1^x$, so it
+    /// also errors if there is only one g1 power.
+    pub fn extend_g2_powers(&mut self, additional_g2: Vec<E::G2Affine>) -> Result<(), Error> {
+        let (g1, g1x) = match self.powers_of_g1.as_slice() {
+            [g1, g1x, ..] => (*g1, *g1x),
+            _ => return Err(Error::InconsistentSrs),
+        };
+        let mut prev = *self.powers_of_g2.last().ok_or(Error::EmptyPowers)?;
+        for next in &additional_g2 {
+            let check = E::multi_pairing([g1x.into_group(), -g1.into_group()], [prev, *next]);
+            if !check.is_zero() {
+                return Err(Error::InconsistentSrs);
+            }
+            prev = *next;
+        }
+        self.powers_of_g2.extend(additional_g2);
+        Ok(())
+    }
+
+    /// Commits to `poly` in G2 with the g2 powers, for protocols that pair the commitment against
+    /// a G1 element. There are usually far fewer g2 powers than g1 powers, so this errors with
+    /// [`Error::PolynomialTooLarge`] for polynomials a G1 commitment could take.
+    pub fn commit_g2(&self, poly: impl AsRef<[E::ScalarField]>) -> Result<E::G2Affine, Error> {
+        Ok(super::curve_msm::<E::G2>(&self.powers_of_g2, poly.as_ref())?.into_affine())
+    }
+
+    /// Given a commitment `old` to $f$, returns a commitment to $f + \delta x^{index}$ without
+    /// recommitting to the whole polynomial
+    pub fn update_commitment(
+        &self,
+        old: &Commitment<E>,
+        index: usize,
+        delta: E::ScalarField,
+    ) -> Result<Commitment<E>, Error> {
+        crate::update_commitment(&self.powers_of_g1, old, index, delta)
+    }
+
+    /// Same as [`M1NoPrecomp::update_commitment`], for when coefficient `index` is overwritten,
+    /// going from `old_coeff` to `new_coeff`
+    pub fn replace_coefficient(
+        &self,
+        old: &Commitment<E>,
+        index: usize,
+        old_coeff: E::ScalarField,
+        new_coeff: E::ScalarField,
+    ) -> Result<Commitment<E>, Error> {
+        self.update_commitment(old, index, new_coeff - old_coeff)
+    }
+
+    /// Commits to `poly`, which must have at most `bound` coefficients, along with
+    /// $X^{n - \text{bound}}$ `poly`, where $n$ is the number of g1 powers.
+    ///
+    /// The powers only go up to $x^{n - 1}$, so a prover can only commit to the shifted
+    /// polynomial if `poly` really has fewer than `bound` coefficients. The shift also needs the
+    /// g2 power $x^{n - \text{bound}}$ for the verifier, so `bound` can't be further than the
+    /// number of g2 powers below $n$.
+    pub fn commit_degree_bounded(
+        &self,
+        poly: impl AsRef<[E::ScalarField]>,
+        bound: usize,
+    ) -> Result<DegreeBoundedCommitment<E>, Error> {
+        let shift = self.degree_bound_shift(bound)?;
+        let poly = crate::trim_zeros(poly.as_ref());
+        if poly.len() > bound {
+            return Err(Error::PolynomialTooLarge {
+                n_coeffs: poly.len(),
+                expected_max: bound,
+            });
+        }
+        Ok(DegreeBoundedCommitment {
+            commit: self.commit(poly)?,
+            shifted: Commitment(
+                crate::curve_msm::<E::G1>(&self.powers_of_g1[shift..], poly)?.into_affine(),
+            ),
+        })
+    }
+
+    /// Checks the degree bound of a [`DegreeBoundedCommitment`] with
+    /// $e(C_{\text{shifted}}, g_2) = e(C, g_2^{x^{n - \text{bound}}})$. `bound` is the bound the
+    /// verifier expects, it mustn't come from the prover. Only the bound is checked, openings are
+    /// against `commit` as usual.
+    pub fn verify_degree_bound(
+        &self,
+        c: &DegreeBoundedCommitment<E>,
+        bound: usize,
+    ) -> Result<bool, Error> {
+        let shift = self.degree_bound_shift(bound)?;
+        Ok(E::pairing(c.shifted.0, self.powers_of_g2[0])
+            == E::pairing(c.commit.0, self.powers_of_g2[shift]))
+    }
+
+    fn degree_bound_shift(&self, bound: usize) -> Result<usize, Error> {
+        let n = self.powers_of_g1.len();
+        let shift = n.checked_sub(bound).ok_or(Error::PolynomialTooLarge {
+            n_coeffs: bound,
+            expected_max: n,
+        })?;
+        if shift >= self.powers_of_g2.len() {
+            return Err(Error::NotEnoughG2Powers {
+                needed: shift + 1,
+                have: self.powers_of_g2.len(),
+            });
+        }
+        Ok(shift)
+    }
+
+    /// Commits to the polynomial whose coefficients are the concatenation of `chunks`. Only one
+    /// chunk is held in memory at a time.
+    pub fn commit_streaming(
+        &self,
+        chunks: impl Iterator<Item = Vec<E::ScalarField>>,
+    ) -> Result<Commitment<E>, Error> {
+        let res = crate::curve_msm_streaming::<E::G1>(&self.powers_of_g1, chunks)?;
+        Ok(Commitment(res.into_affine()))
+    }
+
+    /// Commits to the polynomial whose coefficients are yielded by `coeffs`, such as while
+    /// deserializing them from disk, without collecting them first. Returns the first error
+    /// `coeffs` yields, if any.
+    pub fn commit_try_iter(
+        &self,
+        coeffs: impl Iterator<Item = Result<E::ScalarField, Error>>,
+    ) -> Result<Commitment<E>, Error> {
+        let res = crate::curve_msm_try_iter::<E::G1>(&self.powers_of_g1, coeffs)?;
+        Ok(Commitment(res.into_affine()))
+    }
+
+    /// Same as [`PolyMultiProofNoPrecomp::open`], but takes the vanishing polynomial of `points`,
+    /// so it can be computed once with [`vanishing_polynomial`](crate::vanishing_polynomial) and
+    /// reused across many openings at the same points. `vp` must be the vanishing polynomial of
+    /// exactly these `points`. It isn't checked, and a proof made with any other polynomial won't
+    /// verify.
+    pub fn open_with_vanishing_poly(
+        &self,
+        transcript: &mut Transcript,
+        evals: &[impl AsRef<[E::ScalarField]>],
+        polys: &[impl AsRef<[E::ScalarField]>],
+        points: &[E::ScalarField],
+        vp: &DensePolynomial<E::ScalarField>,
+    ) -> Result<Proof<E>, Error> {
+        self.open_with_quotient(transcript, evals, polys, points, vp)
+            .map(|(proof, _)| proof)
+    }
+
+    /// The vanishing polynomial of `points`, to compute once and pass to
+    /// [`M1NoPrecomp::open_precomputed`] for every opening at those points
+    pub fn precompute_vanishing(points: &[E::ScalarField]) -> DensePolynomial<E::ScalarField> {
+        vanishing_polynomial(points)
+    }
+
+    /// Same as [`M1NoPrecomp::open_with_vanishing_poly`]. `vp` must be
+    /// [`M1NoPrecomp::precompute_vanishing`] of exactly these `points`, it isn't checked, and a
+    /// proof made with any other polynomial won't verify.
+    pub fn open_precomputed(
         &self,
         transcript: &mut Transcript,
         evals: &[impl AsRef<[E::ScalarField]>],
@@ -36,8 +347,229 @@ impl<E: Pairing> M1NoPrecomp<E> {
         points: &[E::ScalarField],
         vp: &DensePolynomial<E::ScalarField>,
     ) -> Result<Proof<E>, Error> {
+        self.open_with_vanishing_poly(transcript, evals, polys, points, vp)
+    }
+
+    /// The most points that can be opened at and verified, one less than the number of g2
+    /// powers since the vanishing polynomial has a coefficient more than there are points
+    pub fn max_points(&self) -> usize {
+        self.powers_of_g2.len().saturating_sub(1)
+    }
+
+    /// Same as [`PolyMultiProofNoPrecomp::verify`], but takes the vanishing polynomial of
+    /// `points`, which must be the one the prover opened with
+    pub fn verify_with_vanishing_poly(
+        &self,
+        transcript: &mut Transcript,
+        commits: impl AsRef<[Commitment<E>]>,
+        points: &[E::ScalarField],
+        evals: &[impl AsRef<[E::ScalarField]>],
+        proof: &Proof<E>,
+        vp: &DensePolynomial<E::ScalarField>,
+    ) -> Result<bool, Error> {
+        crate::traits::check_points(commits.as_ref(), proof)?;
+        self.verify_with_vanishing_poly_unchecked(
+            transcript,
+            commits.as_ref(),
+            points,
+            evals,
+            proof,
+            vp,
+        )
+    }
+
+    fn verify_with_vanishing_poly_unchecked(
+        &self,
+        transcript: &mut Transcript,
+        commits: &[Commitment<E>],
+        points: &[E::ScalarField],
+        evals: &[impl AsRef<[E::ScalarField]>],
+        proof: &Proof<E>,
+        vp: &DensePolynomial<E::ScalarField>,
+    ) -> Result<bool, Error> {
+        let g2_zeros = self.g2_zeros(points, vp)?;
+        let lag_ctx = LagrangeInterpContext::new_from_points(points)?;
+        self.verify_with_lag_ctx_g2_zeros(
+            transcript, commits, points, evals, proof, &lag_ctx, &g2_zeros,
+        )
+    }
+
+    /// Same as [`PolyMultiProofNoPrecomp::verify`], but says why a proof was rejected: a
+    /// [`VerifyFailure::PairingMismatch`] with both sides of the pairing check if the proof
+    /// doesn't match the evals, or [`VerifyFailure::Input`] if the inputs couldn't be checked
+    pub fn verify_detailed(
+        &self,
+        transcript: &mut Transcript,
+        commits: impl AsRef<[Commitment<E>]>,
+        points: &[E::ScalarField],
+        evals: &[impl AsRef<[E::ScalarField]>],
+        proof: &Proof<E>,
+    ) -> Result<(), VerifyFailure<E>> {
+        crate::traits::check_points(commits.as_ref(), proof)?;
+        let g2_zeros = self.g2_zeros(points, &vanishing_polynomial(points))?;
+        let lag_ctx = LagrangeInterpContext::new_from_points(points)?;
+        self.verify_detailed_with_lag_ctx_g2_zeros(
+            transcript,
+            commits.as_ref(),
+            points,
+            evals,
+            proof,
+            &lag_ctx,
+            &g2_zeros,
+        )
+    }
+
+    /// Opens every one of `polys` at the single point `z`, where `evals_at_z[i]` is `polys[i]`
+    /// evaluated at `z`. This is the same proof as [`PolyMultiProofNoPrecomp::open`] at `[z]`, but
+    /// divides by $X - z$ directly instead of going through the general vanishing polynomial.
+    pub fn open_shared_point(
+        &self,
+        transcript: &mut Transcript,
+        z: E::ScalarField,
+        polys: &[impl AsRef<[E::ScalarField]>],
+        evals_at_z: &[E::ScalarField],
+    ) -> Result<Proof<E>, Error> {
+        let evals = evals_at_z.iter().map(|e| [*e]).collect::<Vec<_>>();
+        check_eval_shape(&evals, polys.len(), 1)?;
+        check_polys_fit(polys, self.powers_of_g1.len())?;
+        let field_size_bytes = get_field_size::<E::ScalarField>();
+        transcribe_header(transcript, METHOD1_TRANSCRIPT_TAG);
+        transcribe_points_and_evals(transcript, &[z], &evals, field_size_bytes)?;
+        let gamma = get_challenge::<E::ScalarField>(transcript, b"open gamma", field_size_bytes);
+        let gammas = gen_powers::<E::ScalarField>(gamma, polys.len());
+        let fsum = linear_combination::<E::ScalarField>(polys, &gammas)?;
+        let q = crate::div_by_linear(&fsum, z);
+        Ok(Proof(
+            super::curve_msm::<E::G1>(&self.powers_of_g1, &q)?.into_affine(),
+        ))
+    }
+
+    /// Verifies a proof made with [`M1NoPrecomp::open_shared_point`], or with
+    /// [`PolyMultiProofNoPrecomp::open`] at `[z]`. Each $r_i$ is the constant `evals_at_z[i]`, so
+    /// this skips the lagrange interpolation and the msm over the $r_i$.
+    pub fn verify_shared_point(
+        &self,
+        transcript: &mut Transcript,
+        commits: impl AsRef<[Commitment<E>]>,
+        z: E::ScalarField,
+        evals_at_z: &[E::ScalarField],
+        proof: &Proof<E>,
+    ) -> Result<bool, Error> {
+        crate::traits::check_points(commits.as_ref(), proof)?;
+        let evals = evals_at_z.iter().map(|e| [*e]).collect::<Vec<_>>();
+        check_eval_shape(&evals, commits.as_ref().len(), 1)?;
+        let g2 = self.powers_of_g2[0];
+        let g2x = *self.powers_of_g2.get(1).ok_or(Error::NotEnoughG2Powers {
+            needed: 2,
+            have: self.powers_of_g2.len(),
+        })?;
+        // The commitment to X - z
+        let g2_zeros = g2x.into_group() - g2 * z;
+        if g2_zeros.is_zero() {
+            return Err(Error::PointIsSecret);
+        }
+        let field_size_bytes = get_field_size::<E::ScalarField>();
+        transcribe_header(transcript, METHOD1_TRANSCRIPT_TAG);
+        transcribe_points_and_evals(transcript, &[z], &evals, field_size_bytes)?;
+        let gamma = get_challenge::<E::ScalarField>(transcript, b"open gamma", field_size_bytes);
+        let gammas = gen_powers_small(gamma, evals_at_z.len());
+        let gamma_evals = evals_at_z
+            .iter()
+            .zip(&gammas)
+            .map(|(e, g)| *e * g)
+            .sum::<E::ScalarField>();
+        let gamma_cm_pt = crate::gamma_commit_combo(commits.as_ref(), &gammas)?;
+        let lhs = gamma_cm_pt - self.powers_of_g1[0] * gamma_evals;
+        Ok(E::pairing(lhs, g2) == E::pairing(proof.0, g2_zeros))
+    }
+
+    /// Classic single point KZG: opens `poly` at `point`, returning its evaluation there and the
+    /// proof. This is [`PolyMultiProofNoPrecomp::open`] with one polynomial and one point on a
+    /// transcript from [`SINGLE_OPEN_TRANSCRIPT`].
+    pub fn open_single(
+        &self,
+        poly: &[E::ScalarField],
+        point: E::ScalarField,
+    ) -> Result<(E::ScalarField, Proof<E>), Error> {
+        let (evals, proof) = self.evaluate_and_open(
+            &mut SINGLE_OPEN_TRANSCRIPT.transcript().into_inner(),
+            &[poly],
+            &[point],
+        )?;
+        Ok((evals[0][0], proof))
+    }
+
+    /// Verifies a proof from [`M1NoPrecomp::open_single`] that the polynomial committed to in
+    /// `commit` evaluates to `eval` at `point`
+    pub fn verify_single(
+        &self,
+        commit: &Commitment<E>,
+        point: E::ScalarField,
+        eval: E::ScalarField,
+        proof: &Proof<E>,
+    ) -> Result<bool, Error> {
+        self.verify_pinned(
+            SINGLE_OPEN_TRANSCRIPT.transcript(),
+            std::slice::from_ref(commit),
+            &[point],
+            &[[eval]],
+            proof,
+        )
+    }
+
+    /// The commitment in G2 to `vp`, the vanishing polynomial of `points`
+    fn g2_zeros(
+        &self,
+        points: &[E::ScalarField],
+        vp: &DensePolynomial<E::ScalarField>,
+    ) -> Result<E::G2, Error> {
+        // The vanishing polynomial has one more coefficient than there are points
+        if points.len() >= self.powers_of_g2.len() {
+            return Err(Error::NotEnoughG2Powers {
+                needed: points.len() + 1,
+                have: self.powers_of_g2.len(),
+            });
+        }
+        super::curve_msm::<E::G2>(&self.powers_of_g2, vp)
+    }
+
+    /// Same as [`PolyMultiProofNoPrecomp::verify`], but always computes both sides of the
+    /// pairing check and compares them without stopping at the first difference, so a valid and
+    /// an invalid proof take the same path. This only removes the obvious branch: the arkworks
+    /// arithmetic underneath isn't constant time, so this is hardening, not a guarantee.
+    pub fn verify_ct(
+        &self,
+        transcript: &mut Transcript,
+        commits: impl AsRef<[Commitment<E>]>,
+        points: &[E::ScalarField],
+        evals: &[impl AsRef<[E::ScalarField]>],
+        proof: &Proof<E>,
+    ) -> Result<bool, Error> {
+        crate::traits::check_points(commits.as_ref(), proof)?;
+        let g2_zeros = self.g2_zeros(points, &vanishing_polynomial(points))?;
+        if g2_zeros.is_zero() {
+            return Err(Error::PointIsSecret);
+        }
+        let lag_ctx = LagrangeInterpContext::new_from_points(points)?;
+        let lhs = self.verify_lhs(transcript, commits.as_ref(), points, evals, &lag_ctx)?;
+        let g2 = self.powers_of_g2[0];
+        crate::ct_eq(&E::pairing(lhs, g2), &E::pairing(proof.0, g2_zeros))
+    }
+
+    /// Opens, additionally returning the quotient polynomial `q` that the proof commits to
+    fn open_with_quotient(
+        &self,
+        transcript: &mut Transcript,
+        evals: &[impl AsRef<[E::ScalarField]>],
+        polys: &[impl AsRef<[E::ScalarField]>],
+        points: &[E::ScalarField],
+        vp: &DensePolynomial<E::ScalarField>,
+    ) -> Result<(Proof<E>, DensePolynomial<E::ScalarField>), Error> {
+        check_eval_shape(evals, polys.len(), points.len())?;
+        check_polys_fit(polys, self.powers_of_g1.len())?;
         // Commit the evals and the points to the transcript
         let field_size_bytes = get_field_size::<E::ScalarField>();
+        transcribe_header(transcript, METHOD1_TRANSCRIPT_TAG);
         transcribe_points_and_evals(transcript, points, evals, field_size_bytes)?;
 
         // Read the challenge
@@ -45,16 +577,28 @@ impl<E: Pairing> M1NoPrecomp<E> {
         // Make the gamma powers
         let gammas = gen_powers::<E::ScalarField>(gamma, self.powers_of_g1.len());
         // Take a linear combo of gammas with the polynomials
-        let fsum = linear_combination::<E::ScalarField>(polys, &gammas)
-            .ok_or(Error::NoPolynomialsGiven)?;
+        let fsum = linear_combination::<E::ScalarField>(polys, &gammas)?;
 
         // Polynomial divide, the remained would contain the gamma * ri_s,
         // The result is the correct quotient
         let (q, _) = poly_div_q_r(DensePolynomial { coeffs: fsum }.into(), vp.into())?;
         // Open to the resulting polynomial
-        Ok(Proof(
-            super::curve_msm::<E::G1>(&self.powers_of_g1, &q)?.into_affine(),
-        ))
+        let proof = Proof(super::curve_msm::<E::G1>(&self.powers_of_g1, &q)?.into_affine());
+        Ok((proof, DensePolynomial { coeffs: q }))
+    }
+
+    /// Same as [`PolyMultiProofNoPrecomp::open`], but also returns the quotient polynomial `q`
+    /// so tests can inspect the prover's intermediate values.
+    #[cfg(feature = "debug")]
+    pub fn open_debug(
+        &self,
+        transcript: &mut Transcript,
+        evals: &[impl AsRef<[E::ScalarField]>],
+        polys: &[impl AsRef<[E::ScalarField]>],
+        points: &[E::ScalarField],
+    ) -> Result<(Proof<E>, DensePolynomial<E::ScalarField>), Error> {
+        let vp = vanishing_polynomial(points);
+        self.open_with_quotient(transcript, evals, polys, points, &vp)
     }
 
     fn verify_with_lag_ctx_g2_zeros(
@@ -67,87 +611,309 @@ impl<E: Pairing> M1NoPrecomp<E> {
         lag_ctx: &LagrangeInterpContext<E::ScalarField>,
         g2_zeros: &E::G2,
     ) -> Result<bool, Error> {
-        let field_size_bytes = get_field_size::<E::ScalarField>();
-        transcribe_points_and_evals(transcript, points, evals, field_size_bytes)?;
-        let gamma = get_challenge(transcript, b"open gamma", field_size_bytes);
-        // Aggregate the r_is and then do a single msm of just the ri's and gammas
-        let gammas = gen_powers(gamma, evals.len());
-
-        // Get the gamma^i r_i polynomials with lagrange interp. This does both the lagrange interp
-        // and the gamma mul in one step so we can just lagrange interp once.
-        let gamma_ris = lag_ctx.lagrange_interp_linear_combo(evals, &gammas)?.coeffs;
-        let gamma_ris_pt = super::curve_msm::<E::G1>(&self.powers_of_g1, gamma_ris.as_ref())?;
-
-        // Then do a single msm of the gammas and commitments
-        let cms = commits.iter().map(|i| i.0).collect::<Vec<_>>();
-        let gamma_cm_pt = super::curve_msm::<E::G1>(&cms, gammas.as_ref())?;
-
-        let g2 = self.powers_of_g2[0];
-
-        Ok(E::pairing(gamma_cm_pt - gamma_ris_pt, g2) == E::pairing(proof.0, g2_zeros))
+        VerifyFailure::into_verify_result(self.verify_detailed_with_lag_ctx_g2_zeros(
+            transcript, commits, points, evals, proof, lag_ctx, g2_zeros,
+        ))
     }
-}
 
-impl<E: Pairing> Committer<E> for M1NoPrecomp<E> {
-    fn commit(&self, poly: impl AsRef<[E::ScalarField]>) -> Result<Commitment<E>, Error> {
-        let res = super::curve_msm::<E::G1>(&self.powers_of_g1, poly.as_ref())?;
-        Ok(Commitment(res.into_affine()))
+    fn verify_detailed_with_lag_ctx_g2_zeros(
+        &self,
+        transcript: &mut Transcript,
+        commits: &[Commitment<E>],
+        points: &[E::ScalarField],
+        evals: &[impl AsRef<[E::ScalarField]>],
+        proof: &Proof<E>,
+        lag_ctx: &LagrangeInterpContext<E::ScalarField>,
+        g2_zeros: &E::G2,
+    ) -> Result<(), VerifyFailure<E>> {
+        let state = self
+            .prepare_with_lag_ctx_g2_zeros(transcript, commits, points, evals, lag_ctx, g2_zeros)?;
+        state.finish_detailed(proof)
     }
-}
-
-impl<E: Pairing> PolyMultiProofNoPrecomp<E> for M1NoPrecomp<E> {
-    type Proof = Proof<E>;
-    fn new(max_coeffs: usize, max_pts: Option<usize>, rng: &mut impl RngCore) -> Result<Self, Error> {
-        let x = E::ScalarField::rand(rng);
-        let max_pts = max_pts.unwrap_or(max_coeffs) + 1;
-        let x_powers = gen_powers(x, std::cmp::max(max_coeffs, max_pts));
-
-        let powers_of_g1 = gen_curve_powers::<E::G1>(x_powers.as_ref(), rng);
-        let powers_of_g2 = gen_curve_powers::<E::G2>(x_powers[..max_pts].as_ref(), rng);
 
-        Ok(M1NoPrecomp {
-            powers_of_g1,
-            powers_of_g2,
+    fn prepare_with_lag_ctx_g2_zeros(
+        &self,
+        transcript: &mut Transcript,
+        commits: &[Commitment<E>],
+        points: &[E::ScalarField],
+        evals: &[impl AsRef<[E::ScalarField]>],
+        lag_ctx: &LagrangeInterpContext<E::ScalarField>,
+        g2_zeros: &E::G2,
+    ) -> Result<VerifyState<E>, Error> {
+        // A point equal to the secret x makes the vanishing polynomial zero at x, which would
+        // reduce the pairing check to a comparison against the identity
+        if g2_zeros.is_zero() {
+            return Err(Error::PointIsSecret);
+        }
+        let lhs = self.verify_lhs(transcript, commits, points, evals, lag_ctx)?;
+        Ok(VerifyState {
+            lhs: E::pairing(lhs, self.powers_of_g2[0]),
+            g2_zeros: *g2_zeros,
         })
     }
 
-    fn open(
+    /// The first half of [`PolyMultiProofNoPrecomp::verify`]: runs the transcript and computes
+    /// the side of the pairing check that depends only on the commitments, points and evals. The
+    /// result can be cached and checked against any number of proofs with
+    /// [`M1NoPrecomp::finish_verify`], which only does one pairing each.
+    pub fn prepare_verify(
         &self,
         transcript: &mut Transcript,
-        evals: &[impl AsRef<[E::ScalarField]>],
-        polys: &[impl AsRef<[E::ScalarField]>],
+        commits: impl AsRef<[Commitment<E>]>,
         points: &[E::ScalarField],
-    ) -> Result<Proof<E>, Error> {
-        let vp = vanishing_polynomial(points.as_ref());
-        self.open_with_vanishing_poly(transcript, evals, polys, points, &vp)
+        evals: &[impl AsRef<[E::ScalarField]>],
+    ) -> Result<VerifyState<E>, Error> {
+        Commitment::<E>::batch_check(commits.as_ref().iter()).map_err(|_| Error::InvalidPoint)?;
+        let g2_zeros = self.g2_zeros(points, &vanishing_polynomial(points))?;
+        let lag_ctx = LagrangeInterpContext::new_from_points(points)?;
+        self.prepare_with_lag_ctx_g2_zeros(
+            transcript,
+            commits.as_ref(),
+            points,
+            evals,
+            &lag_ctx,
+            &g2_zeros,
+        )
+    }
+
+    /// The second half of [`PolyMultiProofNoPrecomp::verify`]: whether `proof` is valid for the
+    /// commitments, points and evals `state` was prepared from
+    pub fn finish_verify(&self, state: &VerifyState<E>, proof: &Proof<E>) -> Result<bool, Error> {
+        proof.check().map_err(|_| Error::InvalidPoint)?;
+        VerifyFailure::into_verify_result(state.finish_detailed(proof))
     }
 
-    fn verify(
+    /// Runs the transcript and computes $\sum_i \gamma^i (C_i - g_1^{r_i(x)})$, the G1 point
+    /// that is paired with $g_2$
+    fn verify_lhs(
         &self,
         transcript: &mut Transcript,
         commits: &[Commitment<E>],
         points: &[E::ScalarField],
         evals: &[impl AsRef<[E::ScalarField]>],
-        proof: &Proof<E>,
-    ) -> Result<bool, Error> {
-        let vp = vanishing_polynomial(points);
-        let g2_zeros = super::curve_msm::<E::G2>(&self.powers_of_g2, &vp)?;
-        let lag_ctx = LagrangeInterpContext::new_from_points(points)?;
-        self.verify_with_lag_ctx_g2_zeros(
-            transcript, commits, points, evals, proof, &lag_ctx, &g2_zeros,
-        )
+        lag_ctx: &LagrangeInterpContext<E::ScalarField>,
+    ) -> Result<E::G1, Error> {
+        let gamma = verifier_gamma(transcript, points, evals)?;
+        self.verify_lhs_with_gamma(commits, points.len(), evals, lag_ctx, gamma)
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::M1NoPrecomp;
-    use crate::{
-        test_rng,
-        traits::{Committer, PolyMultiProofNoPrecomp},
-    };
-    use ark_bls12_381::{Bls12_381, Fr};
+    /// The G1 point that is paired with $g_2$, for a `gamma` already drawn from the transcript
+    fn verify_lhs_with_gamma(
+        &self,
+        commits: &[Commitment<E>],
+        n_points: usize,
+        evals: &[impl AsRef<[E::ScalarField]>],
+        lag_ctx: &LagrangeInterpContext<E::ScalarField>,
+        gamma: E::ScalarField,
+    ) -> Result<E::G1, Error> {
+        check_eval_shape(evals, commits.len(), n_points)?;
+        // Aggregate the r_is and then do a single msm of just the ri's and gammas
+        let gammas = gen_powers_small(gamma, evals.len());
+        let gamma_ris_pt = self.gamma_ris_pt(evals, lag_ctx, &gammas)?;
+
+        // Then combine the commitments with the gammas
+        let gamma_cm_pt = crate::gamma_commit_combo(commits, gammas.as_ref())?;
+
+        Ok(gamma_cm_pt - gamma_ris_pt)
+    }
+
+    /// Commits to $\sum_i \gamma^i r_i$, where $r_i$ interpolates the `i`th evals
+    fn gamma_ris_pt(
+        &self,
+        evals: &[impl AsRef<[E::ScalarField]>],
+        lag_ctx: &LagrangeInterpContext<E::ScalarField>,
+        gammas: &[E::ScalarField],
+    ) -> Result<E::G1, Error> {
+        // Get the gamma^i r_i polynomials with lagrange interp. This does both the lagrange interp
+        // and the gamma mul in one step so we can just lagrange interp once.
+        // If all the evals are zero, so are the r_i, and we can skip the interp and msm.
+        if evals_all_zero(evals) {
+            return Ok(E::G1::zero());
+        }
+        let gamma_ris = lag_ctx.lagrange_interp_linear_combo(evals, gammas)?.coeffs;
+        super::curve_msm::<E::G1>(&self.powers_of_g1, gamma_ris.as_ref())
+    }
+
+    /// The polynomial $\sum_i \gamma^i r_i$ that the verifier interpolates from `evals`, with
+    /// the gamma that `transcript` draws for `points` and `evals`. It only depends on those, so
+    /// it can be computed once and passed to [`M1NoPrecomp::verify_with_gamma_ris`] for every
+    /// proof over the same evals, such as openings of different commitments under a fixed
+    /// transcript.
+    pub fn gamma_ris(
+        &self,
+        transcript: &Transcript,
+        points: &[E::ScalarField],
+        evals: &[impl AsRef<[E::ScalarField]>],
+    ) -> Result<DensePolynomial<E::ScalarField>, Error> {
+        let gamma = verifier_gamma(&mut transcript.clone(), points, evals)?;
+        let lag_ctx = LagrangeInterpContext::new_from_points(points)?;
+        lag_ctx.lagrange_interp_linear_combo(evals, &gen_powers_small(gamma, evals.len()))
+    }
+
+    /// Same as [`PolyMultiProofNoPrecomp::verify`], but skips the interpolation, taking its
+    /// result from [`M1NoPrecomp::gamma_ris`] instead. The evals are still needed for the
+    /// transcript. `gamma_ris` isn't checked against them, so it must have been computed with
+    /// the same transcript, points and evals, otherwise the result is meaningless. It can't have
+    /// more coefficients than there are points.
+    pub fn verify_with_gamma_ris(
+        &self,
+        transcript: &mut Transcript,
+        commits: impl AsRef<[Commitment<E>]>,
+        points: &[E::ScalarField],
+        evals: &[impl AsRef<[E::ScalarField]>],
+        proof: &Proof<E>,
+        gamma_ris: &DensePolynomial<E::ScalarField>,
+    ) -> Result<bool, Error> {
+        crate::traits::check_points(commits.as_ref(), proof)?;
+        check_eval_shape(evals, commits.as_ref().len(), points.len())?;
+        if gamma_ris.coeffs.len() > points.len() {
+            return Err(Error::PolynomialTooLarge {
+                n_coeffs: gamma_ris.coeffs.len(),
+                expected_max: points.len(),
+            });
+        }
+        let g2_zeros = self.g2_zeros(points, &vanishing_polynomial(points))?;
+        if g2_zeros.is_zero() {
+            return Err(Error::PointIsSecret);
+        }
+        let gamma = verifier_gamma(transcript, points, evals)?;
+        let gammas = gen_powers_small(gamma, evals.len());
+        let gamma_ris_pt = super::curve_msm::<E::G1>(&self.powers_of_g1, &gamma_ris.coeffs)?;
+        let gamma_cm_pt = crate::gamma_commit_combo(commits.as_ref(), gammas.as_ref())?;
+
+        let g2 = self.powers_of_g2[0];
+        Ok(E::pairing(gamma_cm_pt - gamma_ris_pt, g2) == E::pairing(proof.0, g2_zeros))
+    }
+
+    /// Same as [`PolyMultiProofNoPrecomp::verify`], but takes the commitments as an iterator, e.g.
+    /// as they arrive over the network, and folds them into the check in chunks instead of
+    /// collecting them first. There must be exactly one commitment per entry of `evals`.
+    pub fn verify_streaming(
+        &self,
+        transcript: &mut Transcript,
+        commits: impl IntoIterator<Item = Commitment<E>>,
+        points: &[E::ScalarField],
+        evals: &[impl AsRef<[E::ScalarField]>],
+        proof: &Proof<E>,
+    ) -> Result<bool, Error> {
+        proof.check().map_err(|_| Error::InvalidPoint)?;
+        let g2_zeros = self.g2_zeros(points, &vanishing_polynomial(points))?;
+        if g2_zeros.is_zero() {
+            return Err(Error::PointIsSecret);
+        }
+        let lag_ctx = LagrangeInterpContext::new_from_points(points)?;
+
+        // The commitments aren't in the transcript, so gamma is known before any of them arrive
+        let gamma = verifier_gamma(transcript, points, evals)?;
+        let gammas = gen_powers_small(gamma, evals.len());
+        let gamma_ris_pt = self.gamma_ris_pt(evals, &lag_ctx, &gammas)?;
+        let gamma_cm_pt =
+            crate::gamma_commit_combo_streaming(commits.into_iter(), gamma, evals.len())?;
+
+        let g2 = self.powers_of_g2[0];
+        Ok(E::pairing(gamma_cm_pt - gamma_ris_pt, g2) == E::pairing(proof.0, g2_zeros))
+    }
+}
+
+/// Runs the verifier's transcript up to and including drawing gamma
+fn verifier_gamma<F: PrimeField>(
+    transcript: &mut Transcript,
+    points: &[F],
+    evals: &[impl AsRef<[F]>],
+) -> Result<F, Error> {
+    let field_size_bytes = get_field_size::<F>();
+    transcribe_header(transcript, METHOD1_TRANSCRIPT_TAG);
+    transcribe_points_and_evals(transcript, points, evals, field_size_bytes)?;
+    Ok(get_challenge(transcript, b"open gamma", field_size_bytes))
+}
+
+impl<E: Pairing> Committer<E> for M1NoPrecomp<E> {
+    fn commit_projective(&self, poly: impl AsRef<[E::ScalarField]>) -> Result<E::G1, Error> {
+        crate::curve_msm::<E::G1>(&self.powers_of_g1, poly.as_ref())
+    }
+
+    /// Runs the msms of the rows in parallel with the `parallel` feature, then normalizes all
+    /// the commitments together
+    fn commit_many(
+        &self,
+        polys: &[impl AsRef<[E::ScalarField]> + Sync],
+    ) -> Result<Vec<Commitment<E>>, Error> {
+        let projective = cfg_iter!(polys)
+            .map(|(_, p)| self.commit_projective(p))
+            .collect::<Result<Vec<_>, Error>>()?;
+        Ok(E::G1::normalize_batch(&projective)
+            .into_iter()
+            .map(Commitment)
+            .collect())
+    }
+    fn max_coeffs(&self) -> usize {
+        self.powers_of_g1.len()
+    }
+}
+
+impl<E: Pairing> PolyMultiProofNoPrecomp<E> for M1NoPrecomp<E> {
+    type Proof = Proof<E>;
+    fn new(
+        max_coeffs: usize,
+        max_pts: Option<usize>,
+        rng: &mut impl RngCore,
+    ) -> Result<Self, Error> {
+        let x = E::ScalarField::rand(rng);
+        let max_pts = max_pts.unwrap_or(max_coeffs) + 1;
+        let x_powers = gen_powers(x, std::cmp::max(max_coeffs, max_pts));
+
+        let powers_of_g1 = gen_curve_powers::<E::G1>(x_powers.as_ref(), rng);
+        let powers_of_g2 = gen_curve_powers::<E::G2>(x_powers[..max_pts].as_ref(), rng);
+
+        Ok(M1NoPrecomp {
+            powers_of_g1,
+            powers_of_g2,
+        })
+    }
+
+    fn open(
+        &self,
+        transcript: &mut Transcript,
+        evals: &[impl AsRef<[E::ScalarField]>],
+        polys: &[impl AsRef<[E::ScalarField]>],
+        points: &[E::ScalarField],
+    ) -> Result<Proof<E>, Error> {
+        let vp = vanishing_polynomial(points.as_ref());
+        self.open_with_vanishing_poly(transcript, evals, polys, points, &vp)
+    }
+
+    fn verify_unchecked(
+        &self,
+        transcript: &mut Transcript,
+        commits: impl AsRef<[Commitment<E>]>,
+        points: &[E::ScalarField],
+        evals: &[impl AsRef<[E::ScalarField]>],
+        proof: &Proof<E>,
+    ) -> Result<bool, Error> {
+        let vp = vanishing_polynomial(points);
+        self.verify_with_vanishing_poly_unchecked(
+            transcript,
+            commits.as_ref(),
+            points,
+            evals,
+            proof,
+            &vp,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{M1NoPrecomp, Proof, SINGLE_OPEN_TRANSCRIPT};
+    use crate::{
+        deserialize_proofs, deserialize_proofs_with_mode, serialize_proofs,
+        serialize_proofs_with_mode, test_rng,
+        traits::{Committer, PolyMultiProofNoPrecomp},
+        Commitment, Error, VerifyFailure,
+    };
+    use ark_bls12_381::{Bls12_381, Fr};
     use ark_poly::{univariate::DensePolynomial, DenseUVPolynomial, Polynomial};
+    use ark_serialize::{CanonicalSerialize, Compress};
     use ark_std::UniformRand;
     use merlin::Transcript;
 
@@ -178,5 +944,1517 @@ mod tests {
             Ok(true),
             s.verify(&mut transcript, &commits, &points, &evals, &open)
         );
+        // Commitments can also be handed over by value or behind an Arc
+        let shared: std::sync::Arc<[_]> = commits.into();
+        assert_eq!(
+            Ok(true),
+            s.verify(
+                &mut Transcript::new(b"testing"),
+                shared.clone(),
+                &points,
+                &evals,
+                &open
+            )
+        );
+    }
+
+    #[test]
+    fn test_verify_checks_points() {
+        use ark_bls12_381::{Fq, G1Affine};
+        let s = M1NoPrecomp::<Bls12_381>::new(32, 4.into(), &mut test_rng()).unwrap();
+        let points = (0..4)
+            .map(|_| Fr::rand(&mut test_rng()))
+            .collect::<Vec<_>>();
+        let poly = DensePolynomial::<Fr>::rand(20, &mut test_rng());
+        let evals = vec![points.iter().map(|x| poly.evaluate(x)).collect::<Vec<_>>()];
+        let commits = vec![s.commit(&poly.coeffs).unwrap()];
+        let open = s
+            .open(
+                &mut Transcript::new(b"testing"),
+                &evals,
+                &[&poly.coeffs],
+                &points,
+            )
+            .unwrap();
+        assert_eq!(
+            Ok(true),
+            s.verify_unchecked(
+                &mut Transcript::new(b"testing"),
+                &commits,
+                &points,
+                &evals,
+                &open
+            )
+        );
+
+        // Almost certainly not on the curve
+        let bad = G1Affine::new_unchecked(Fq::rand(&mut test_rng()), Fq::rand(&mut test_rng()));
+        assert_eq!(
+            Err(Error::InvalidPoint),
+            s.verify(
+                &mut Transcript::new(b"testing"),
+                [crate::Commitment(bad)],
+                &points,
+                &evals,
+                &open
+            )
+        );
+        assert_eq!(
+            Err(Error::InvalidPoint),
+            s.verify(
+                &mut Transcript::new(b"testing"),
+                &commits,
+                &points,
+                &evals,
+                &Proof(bad)
+            )
+        );
+    }
+
+    #[test]
+    fn test_verify_from_bytes() {
+        let s = M1NoPrecomp::<Bls12_381>::new(32, 4.into(), &mut test_rng()).unwrap();
+        let points = (0..4)
+            .map(|_| Fr::rand(&mut test_rng()))
+            .collect::<Vec<_>>();
+        let polys = (0..2)
+            .map(|_| DensePolynomial::<Fr>::rand(20, &mut test_rng()))
+            .collect::<Vec<_>>();
+        let evals: Vec<Vec<_>> = polys
+            .iter()
+            .map(|p| points.iter().map(|x| p.evaluate(x)).collect())
+            .collect();
+        let coeffs = polys.iter().map(|p| p.coeffs.clone()).collect::<Vec<_>>();
+        let commits = s.commit_many(&coeffs).unwrap();
+        let open = s
+            .open(&mut Transcript::new(b"testing"), &evals, &coeffs, &points)
+            .unwrap();
+        let commit_bytes = commits
+            .iter()
+            .map(|c| {
+                let mut bytes = Vec::new();
+                c.serialize_compressed(&mut bytes).unwrap();
+                bytes
+            })
+            .collect::<Vec<_>>();
+        assert!(commit_bytes.iter().all(|b| b.len() == 48));
+        let mut proof_bytes = Vec::new();
+        open.serialize_compressed(&mut proof_bytes).unwrap();
+        let verify = |commits: &[Vec<u8>], proof: &[u8], evals: &[Vec<Fr>]| {
+            s.verify_from_bytes(
+                &mut Transcript::new(b"testing"),
+                commits,
+                &points,
+                evals,
+                proof,
+            )
+        };
+        assert_eq!(Ok(true), verify(&commit_bytes, &proof_bytes, &evals));
+
+        let mut bad_evals = evals.clone();
+        bad_evals[0][0] += Fr::from(1u64);
+        assert_eq!(Ok(false), verify(&commit_bytes, &proof_bytes, &bad_evals));
+
+        let mut truncated = commit_bytes.clone();
+        truncated[1].pop();
+        assert_eq!(
+            Err(Error::MalformedCommitment { index: 1 }),
+            verify(&truncated, &proof_bytes, &evals)
+        );
+        let mut trailing = commit_bytes.clone();
+        trailing[0].push(0);
+        assert_eq!(
+            Err(Error::MalformedCommitment { index: 0 }),
+            verify(&trailing, &proof_bytes, &evals)
+        );
+        assert_eq!(
+            Err(Error::MalformedProof),
+            verify(&commit_bytes, &proof_bytes[1..], &evals)
+        );
+    }
+
+    #[test]
+    fn test_reuse_vanishing_poly() {
+        let s = M1NoPrecomp::<Bls12_381>::new(64, 8.into(), &mut test_rng()).unwrap();
+        let points = (0..8)
+            .map(|_| Fr::rand(&mut test_rng()))
+            .collect::<Vec<_>>();
+        let vp = crate::vanishing_polynomial(&points);
+        for _ in 0..2 {
+            let poly = DensePolynomial::<Fr>::rand(40, &mut test_rng());
+            let evals = vec![points.iter().map(|x| poly.evaluate(x)).collect::<Vec<_>>()];
+            let commits = vec![s.commit(&poly.coeffs).unwrap()];
+            let open = s
+                .open_with_vanishing_poly(
+                    &mut Transcript::new(b"testing"),
+                    &evals,
+                    &[&poly.coeffs],
+                    &points,
+                    &vp,
+                )
+                .unwrap();
+            let expected = s
+                .open(
+                    &mut Transcript::new(b"testing"),
+                    &evals,
+                    &[&poly.coeffs],
+                    &points,
+                )
+                .unwrap();
+            assert!(expected.eq_point(&open));
+            assert_eq!(
+                Ok(true),
+                s.verify_with_vanishing_poly(
+                    &mut Transcript::new(b"testing"),
+                    &commits,
+                    &points,
+                    &evals,
+                    &open,
+                    &vp
+                )
+            );
+        }
+    }
+
+    #[test]
+    fn test_degree_bound() {
+        use super::DegreeBoundedCommitment;
+        let s = M1NoPrecomp::<Bls12_381>::new(64, 16.into(), &mut test_rng()).unwrap();
+        let poly = DensePolynomial::<Fr>::rand(49, &mut test_rng());
+        let c = s.commit_degree_bounded(&poly.coeffs, 50).unwrap();
+        assert_eq!(s.commit(&poly.coeffs).unwrap().0, c.commit.0);
+        assert_eq!(Ok(true), s.verify_degree_bound(&c, 50));
+        assert!(matches!(
+            s.commit_degree_bounded(&poly.coeffs, 49),
+            Err(Error::PolynomialTooLarge { .. })
+        ));
+        // There are 17 g2 powers, so a shift of 24 can't be verified
+        assert_eq!(
+            Err(Error::NotEnoughG2Powers {
+                needed: 25,
+                have: 17
+            }),
+            s.commit_degree_bounded(&poly.coeffs, 40)
+                .map(|c| c.commit.0)
+        );
+
+        // Checking against a different bound than the shifted commitment was made for fails
+        assert_eq!(Ok(false), s.verify_degree_bound(&c, 55));
+        assert_eq!(Ok(false), s.verify_degree_bound(&c, 52));
+
+        // A prover can't pass off a looser bound's shift as a tighter one
+        let loose = s.commit_degree_bounded(&poly.coeffs, 60).unwrap();
+        let forged = DegreeBoundedCommitment {
+            commit: Commitment(loose.commit.0),
+            shifted: Commitment(loose.shifted.0),
+        };
+        assert_eq!(Ok(false), s.verify_degree_bound(&forged, 50));
+    }
+
+    #[test]
+    fn test_verify_with_gamma_ris() {
+        let s = M1NoPrecomp::<Bls12_381>::new(64, 8.into(), &mut test_rng()).unwrap();
+        let points = (0..8)
+            .map(|_| Fr::rand(&mut test_rng()))
+            .collect::<Vec<_>>();
+        let vp = crate::vanishing_polynomial(&points);
+        let polys = (0..3)
+            .map(|_| DensePolynomial::<Fr>::rand(40, &mut test_rng()))
+            .collect::<Vec<_>>();
+        let evals: Vec<Vec<_>> = polys
+            .iter()
+            .map(|p| points.iter().map(|x| p.evaluate(x)).collect())
+            .collect();
+        let gamma_ris = s
+            .gamma_ris(&Transcript::new(b"testing"), &points, &evals)
+            .unwrap();
+        // Adding multiples of the vanishing polynomial gives different commitments to
+        // polynomials with the same evals
+        for _ in 0..2 {
+            let coeffs = polys
+                .iter()
+                .map(|p| (p + &(&vp * &DensePolynomial::rand(20, &mut test_rng()))).coeffs)
+                .collect::<Vec<_>>();
+            let commits = s.commit_many(&coeffs).unwrap();
+            let open = s
+                .open(&mut Transcript::new(b"testing"), &evals, &coeffs, &points)
+                .unwrap();
+            assert_eq!(
+                Ok(true),
+                s.verify_with_gamma_ris(
+                    &mut Transcript::new(b"testing"),
+                    &commits,
+                    &points,
+                    &evals,
+                    &open,
+                    &gamma_ris
+                )
+            );
+            assert_eq!(
+                Ok(false),
+                s.verify_with_gamma_ris(
+                    &mut Transcript::new(b"other"),
+                    &commits,
+                    &points,
+                    &evals,
+                    &open,
+                    &gamma_ris
+                )
+            );
+        }
+        let too_large = DensePolynomial::<Fr>::rand(8, &mut test_rng());
+        let coeffs = polys.iter().map(|p| p.coeffs.clone()).collect::<Vec<_>>();
+        let commits = s.commit_many(&coeffs).unwrap();
+        let open = s
+            .open(&mut Transcript::new(b"testing"), &evals, &coeffs, &points)
+            .unwrap();
+        assert_eq!(
+            Err(Error::PolynomialTooLarge {
+                n_coeffs: 9,
+                expected_max: 8
+            }),
+            s.verify_with_gamma_ris(
+                &mut Transcript::new(b"testing"),
+                &commits,
+                &points,
+                &evals,
+                &open,
+                &too_large
+            )
+        );
+    }
+
+    #[test]
+    fn test_verify_streaming() {
+        let s = M1NoPrecomp::<Bls12_381>::new(64, 8.into(), &mut test_rng()).unwrap();
+        let points = (0..8)
+            .map(|_| Fr::rand(&mut test_rng()))
+            .collect::<Vec<_>>();
+        let polys = (0..5)
+            .map(|_| DensePolynomial::<Fr>::rand(40, &mut test_rng()))
+            .collect::<Vec<_>>();
+        let evals: Vec<Vec<_>> = polys
+            .iter()
+            .map(|p| points.iter().map(|x| p.evaluate(x)).collect())
+            .collect();
+        let coeffs = polys.iter().map(|p| p.coeffs.clone()).collect::<Vec<_>>();
+        let commits = s.commit_many(&coeffs).unwrap();
+        let open = s
+            .open(&mut Transcript::new(b"testing"), &evals, &coeffs, &points)
+            .unwrap();
+        let verify = |n: usize| {
+            s.verify_streaming(
+                &mut Transcript::new(b"testing"),
+                commits.iter().take(n).map(|c| Commitment(c.0)),
+                &points,
+                &evals,
+                &open,
+            )
+        };
+        assert_eq!(Ok(true), verify(5));
+        assert_eq!(
+            Err(Error::CommitsIncorrectSize { n: 4, expected: 5 }),
+            verify(4)
+        );
+        let swapped = [&commits[1], &commits[0]]
+            .into_iter()
+            .chain(&commits[2..])
+            .map(|c| Commitment(c.0));
+        assert_eq!(
+            Ok(false),
+            s.verify_streaming(
+                &mut Transcript::new(b"testing"),
+                swapped,
+                &points,
+                &evals,
+                &open
+            )
+        );
+    }
+
+    #[test]
+    fn test_open_subset() {
+        let s = M1NoPrecomp::<Bls12_381>::new(64, 8.into(), &mut test_rng()).unwrap();
+        let points = (0..8)
+            .map(|_| Fr::rand(&mut test_rng()))
+            .collect::<Vec<_>>();
+        let polys = (0..6)
+            .map(|_| DensePolynomial::<Fr>::rand(40, &mut test_rng()))
+            .collect::<Vec<_>>();
+        let evals: Vec<Vec<_>> = polys
+            .iter()
+            .map(|p| points.iter().map(|x| p.evaluate(x)).collect())
+            .collect();
+        let coeffs = polys.iter().map(|p| p.coeffs.clone()).collect::<Vec<_>>();
+        let commits = s.commit_many(&coeffs).unwrap();
+        let indices = [4, 1, 2];
+        let open = s
+            .open_subset(
+                &mut Transcript::new(b"testing"),
+                &indices,
+                &evals,
+                &coeffs,
+                &points,
+            )
+            .unwrap();
+        assert_eq!(
+            Ok(true),
+            s.verify_subset(
+                &mut Transcript::new(b"testing"),
+                &indices,
+                &commits,
+                &points,
+                &evals,
+                &open
+            )
+        );
+        // The same as opening and verifying the subset directly
+        let sub_commits = indices
+            .iter()
+            .map(|&i| Commitment(commits[i].0))
+            .collect::<Vec<_>>();
+        let sub_evals = indices.iter().map(|&i| &evals[i]).collect::<Vec<_>>();
+        assert_eq!(
+            Ok(true),
+            s.verify(
+                &mut Transcript::new(b"testing"),
+                &sub_commits,
+                &points,
+                &sub_evals,
+                &open
+            )
+        );
+        assert_eq!(
+            Ok(false),
+            s.verify_subset(
+                &mut Transcript::new(b"testing"),
+                &[4, 1, 3],
+                &commits,
+                &points,
+                &evals,
+                &open
+            )
+        );
+        assert_eq!(
+            Some(Error::PolyIndexOutOfRange { index: 6, n: 6 }),
+            s.open_subset(
+                &mut Transcript::new(b"testing"),
+                &[6],
+                &evals,
+                &coeffs,
+                &points
+            )
+            .err()
+        );
+        assert_eq!(
+            Some(Error::DuplicatePolyIndex { index: 1 }),
+            s.open_subset(
+                &mut Transcript::new(b"testing"),
+                &[1, 1],
+                &evals,
+                &coeffs,
+                &points
+            )
+            .err()
+        );
+    }
+
+    #[test]
+    fn test_verify_ct() {
+        let s = M1NoPrecomp::<Bls12_381>::new(64, 8.into(), &mut test_rng()).unwrap();
+        let points = (0..8)
+            .map(|_| Fr::rand(&mut test_rng()))
+            .collect::<Vec<_>>();
+        let poly = DensePolynomial::<Fr>::rand(40, &mut test_rng());
+        let mut evals = vec![points.iter().map(|x| poly.evaluate(x)).collect::<Vec<_>>()];
+        let commits = vec![s.commit(&poly.coeffs).unwrap()];
+        let open = s
+            .open(
+                &mut Transcript::new(b"testing"),
+                &evals,
+                &[&poly.coeffs],
+                &points,
+            )
+            .unwrap();
+        for _ in 0..2 {
+            assert_eq!(
+                s.verify(
+                    &mut Transcript::new(b"testing"),
+                    &commits,
+                    &points,
+                    &evals,
+                    &open
+                ),
+                s.verify_ct(
+                    &mut Transcript::new(b"testing"),
+                    &commits,
+                    &points,
+                    &evals,
+                    &open
+                )
+            );
+            evals[0][3] += Fr::from(1u64);
+        }
+        assert_eq!(
+            Ok(false),
+            s.verify_ct(
+                &mut Transcript::new(b"testing"),
+                &commits,
+                &points,
+                &evals,
+                &open
+            )
+        );
+        assert!(crate::ct_eq(&Fr::from(5u64), &Fr::from(5u64)).unwrap());
+        assert!(!crate::ct_eq(&Fr::from(5u64), &Fr::from(6u64)).unwrap());
+    }
+
+    #[test]
+    fn test_shared_point() {
+        let s = M1NoPrecomp::<Bls12_381>::new(64, 8.into(), &mut test_rng()).unwrap();
+        let z = Fr::rand(&mut test_rng());
+        let polys = (0..5)
+            .map(|_| DensePolynomial::<Fr>::rand(40, &mut test_rng()))
+            .collect::<Vec<_>>();
+        let mut evals = polys.iter().map(|p| p.evaluate(&z)).collect::<Vec<_>>();
+        let coeffs = polys.iter().map(|p| p.coeffs.clone()).collect::<Vec<_>>();
+        let commits = s.commit_many(&coeffs).unwrap();
+        let open = s
+            .open_shared_point(&mut Transcript::new(b"testing"), z, &coeffs, &evals)
+            .unwrap();
+        let general_evals = evals.iter().map(|e| vec![*e]).collect::<Vec<_>>();
+        let general = s
+            .open(
+                &mut Transcript::new(b"testing"),
+                &general_evals,
+                &coeffs,
+                &[z],
+            )
+            .unwrap();
+        assert!(open.eq_point(&general));
+        assert_eq!(
+            Ok(true),
+            s.verify_shared_point(&mut Transcript::new(b"testing"), &commits, z, &evals, &open)
+        );
+        assert_eq!(
+            Ok(true),
+            s.verify(
+                &mut Transcript::new(b"testing"),
+                &commits,
+                &[z],
+                &general_evals,
+                &open
+            )
+        );
+        // A proof over a subset of the polynomials doesn't cover the other commitments
+        let subset = Err(Error::EvalsIncorrectSize {
+            poly: 4,
+            n: 0,
+            expected: 1,
+        });
+        assert_eq!(
+            subset,
+            s.open_shared_point(&mut Transcript::new(b"testing"), z, &coeffs, &evals[..4])
+                .map(|_| ())
+        );
+        assert_eq!(
+            subset.map(|_: ()| true),
+            s.verify_shared_point(
+                &mut Transcript::new(b"testing"),
+                &commits,
+                z,
+                &evals[..4],
+                &open
+            )
+        );
+        let too_large = vec![DensePolynomial::<Fr>::rand(64, &mut test_rng()).coeffs];
+        assert_eq!(
+            Err(Error::PolynomialTooLarge {
+                n_coeffs: 65,
+                expected_max: 64
+            }),
+            s.open_shared_point(&mut Transcript::new(b"testing"), z, &too_large, &evals[..1])
+                .map(|_| ())
+        );
+        evals[2] += Fr::from(1u64);
+        assert_eq!(
+            Ok(false),
+            s.verify_shared_point(&mut Transcript::new(b"testing"), &commits, z, &evals, &open)
+        );
+    }
+
+    #[test]
+    fn test_evaluate_and_open() {
+        let s = M1NoPrecomp::<Bls12_381>::new(64, 40.into(), &mut test_rng()).unwrap();
+        let points = (0..40)
+            .map(|_| Fr::rand(&mut test_rng()))
+            .collect::<Vec<_>>();
+        let polys = (0..3)
+            .map(|_| DensePolynomial::<Fr>::rand(50, &mut test_rng()))
+            .collect::<Vec<_>>();
+        let coeffs = polys.iter().map(|p| p.coeffs.clone()).collect::<Vec<_>>();
+        let commits = s.commit_many(&coeffs).unwrap();
+        let (evals, open) = s
+            .evaluate_and_open(&mut Transcript::new(b"testing"), &coeffs, &points)
+            .unwrap();
+        for (p, e) in polys.iter().zip(&evals) {
+            assert_eq!(&points.iter().map(|x| p.evaluate(x)).collect::<Vec<_>>(), e);
+        }
+        assert_eq!(
+            Ok(true),
+            s.verify(
+                &mut Transcript::new(b"testing"),
+                &commits,
+                &points,
+                &evals,
+                &open
+            )
+        );
+    }
+
+    #[test]
+    fn test_quotient_commitment() {
+        let s = M1NoPrecomp::<Bls12_381>::new(64, 8.into(), &mut test_rng()).unwrap();
+        let points = (0..8)
+            .map(|_| Fr::rand(&mut test_rng()))
+            .collect::<Vec<_>>();
+        let poly = DensePolynomial::<Fr>::rand(40, &mut test_rng());
+        let evals = vec![points.iter().map(|x| poly.evaluate(x)).collect::<Vec<_>>()];
+        let open = s
+            .open(
+                &mut Transcript::new(b"testing"),
+                &evals,
+                &[&poly.coeffs],
+                &points,
+            )
+            .unwrap();
+        // With a single polynomial gamma doesn't matter, so the quotient is just poly / vp
+        let vp = crate::vanishing_polynomial(&points);
+        let (q, _) = crate::poly_div_q_r((&poly).into(), (&vp).into()).unwrap();
+        assert_eq!(s.commit(&q).unwrap().0, open.quotient_commitment());
+    }
+
+    #[test]
+    fn test_extend_g2_powers() {
+        use crate::{gen_curve_powers_with_generator, gen_powers};
+        use ark_bls12_381::{G1Projective, G2Projective};
+        use ark_ec::Group;
+        let x = Fr::rand(&mut test_rng());
+        let x_powers = gen_powers(x, 32);
+        let mut s = M1NoPrecomp::<Bls12_381> {
+            powers_of_g1: gen_curve_powers_with_generator(&x_powers, G1Projective::generator()),
+            // Supports opening at up to 4 points
+            powers_of_g2: gen_curve_powers_with_generator(
+                &x_powers[..5],
+                G2Projective::generator(),
+            ),
+        };
+        let points = (0..8)
+            .map(|_| Fr::rand(&mut test_rng()))
+            .collect::<Vec<_>>();
+        let poly = DensePolynomial::<Fr>::rand(20, &mut test_rng());
+        let evals = vec![points.iter().map(|x| poly.evaluate(x)).collect::<Vec<_>>()];
+        let commits = vec![s.commit(&poly.coeffs).unwrap()];
+        let open = s
+            .open(
+                &mut Transcript::new(b"testing"),
+                &evals,
+                &[&poly.coeffs],
+                &points,
+            )
+            .unwrap();
+        let verify = |s: &M1NoPrecomp<Bls12_381>| {
+            s.verify(
+                &mut Transcript::new(b"testing"),
+                &commits,
+                &points,
+                &evals,
+                &open,
+            )
+        };
+        assert_eq!(
+            Err(Error::NotEnoughG2Powers { needed: 9, have: 5 }),
+            verify(&s)
+        );
+
+        // Powers of a different secret are rejected, and the setup is left as it was
+        let other = gen_powers(x + Fr::from(1u64), 10);
+        assert_eq!(
+            Err(Error::InconsistentSrs),
+            s.extend_g2_powers(gen_curve_powers_with_generator(
+                &other[5..9],
+                G2Projective::generator()
+            ))
+        );
+        assert_eq!(5, s.powers_of_g2.len());
+        // So is skipping a power
+        assert_eq!(
+            Err(Error::InconsistentSrs),
+            s.extend_g2_powers(gen_curve_powers_with_generator(
+                &x_powers[6..9],
+                G2Projective::generator()
+            ))
+        );
+
+        s.extend_g2_powers(gen_curve_powers_with_generator(
+            &x_powers[5..9],
+            G2Projective::generator(),
+        ))
+        .unwrap();
+        assert_eq!(Ok(true), verify(&s));
+    }
+
+    #[test]
+    fn test_update_commitment() {
+        let s = M1NoPrecomp::<Bls12_381>::new(64, None, &mut test_rng()).unwrap();
+        let mut poly = (0..64)
+            .map(|_| Fr::rand(&mut test_rng()))
+            .collect::<Vec<_>>();
+        let old = s.commit(&poly).unwrap();
+        let delta = Fr::rand(&mut test_rng());
+        poly[17] += delta;
+        let updated = s.update_commitment(&old, 17, delta).unwrap();
+        assert_eq!(s.commit(&poly).unwrap().0, updated.0);
+        assert!(s
+            .update_commitment(&old, s.powers_of_g1.len(), delta)
+            .is_err());
+
+        // Overwrite a few cells one after another
+        let mut commit = updated;
+        for index in [0, 5, 17, 63] {
+            let new_coeff = Fr::rand(&mut test_rng());
+            commit = s
+                .replace_coefficient(&commit, index, poly[index], new_coeff)
+                .unwrap();
+            poly[index] = new_coeff;
+        }
+        assert_eq!(s.commit(&poly).unwrap().0, commit.0);
+        assert_eq!(
+            Err(Error::PolynomialTooLarge {
+                n_coeffs: 65,
+                expected_max: 64
+            }),
+            s.replace_coefficient(&commit, 64, poly[0], poly[1])
+                .map(|c| c.0)
+        );
+        // The largest index errors rather than overflowing
+        assert_eq!(
+            Err(Error::PolynomialTooLarge {
+                n_coeffs: usize::MAX,
+                expected_max: 64
+            }),
+            s.update_commitment(&commit, usize::MAX, poly[0])
+                .map(|c| c.0)
+        );
+    }
+
+    #[test]
+    fn test_verify_strict() {
+        let s = M1NoPrecomp::<Bls12_381>::new(64, 4.into(), &mut test_rng()).unwrap();
+        let points = (0..4)
+            .map(|_| Fr::rand(&mut test_rng()))
+            .collect::<Vec<_>>();
+        let poly = DensePolynomial::<Fr>::rand(30, &mut test_rng());
+        let mut evals = vec![points.iter().map(|x| poly.evaluate(x)).collect::<Vec<_>>()];
+        let coeffs = vec![poly.coeffs.clone()];
+        let commits = vec![s.commit(&poly.coeffs).unwrap()];
+        let open = s
+            .open(&mut Transcript::new(b"testing"), &evals, &coeffs, &points)
+            .expect("Open failed");
+        assert_eq!(
+            Ok(()),
+            s.verify_strict(
+                &mut Transcript::new(b"testing"),
+                &commits,
+                &points,
+                &evals,
+                &open
+            )
+        );
+        evals[0][1] += Fr::from(1u64);
+        assert_eq!(
+            Err(Error::VerificationFailed),
+            s.verify_strict(
+                &mut Transcript::new(b"testing"),
+                &commits,
+                &points,
+                &evals,
+                &open
+            )
+        );
+    }
+
+    #[test]
+    fn test_zero_evals_fast_path() {
+        let s = M1NoPrecomp::<Bls12_381>::new(64, 4.into(), &mut test_rng()).unwrap();
+        let points = (0..4)
+            .map(|_| Fr::rand(&mut test_rng()))
+            .collect::<Vec<_>>();
+        let vp = crate::vanishing_polynomial(&points);
+        // Multiples of the vanishing polynomial are zero on every point
+        let polys = (0..3)
+            .map(|_| &DensePolynomial::<Fr>::rand(20, &mut test_rng()) * &vp)
+            .collect::<Vec<_>>();
+        let evals = vec![vec![Fr::from(0u64); 4]; 3];
+        let coeffs = polys.iter().map(|p| p.coeffs.clone()).collect::<Vec<_>>();
+        let commits = coeffs
+            .iter()
+            .map(|p| s.commit(p).expect("Commit failed"))
+            .collect::<Vec<_>>();
+        let open = s
+            .open(&mut Transcript::new(b"testing"), &evals, &coeffs, &points)
+            .expect("Open failed");
+        assert_eq!(
+            Ok(true),
+            s.verify(
+                &mut Transcript::new(b"testing"),
+                &commits,
+                &points,
+                &evals,
+                &open
+            )
+        );
+        // The fast path gives the same point as interpolating the zero evals
+        let lag_ctx = crate::lagrange::LagrangeInterpContext::new_from_points(&points).unwrap();
+        let gammas = crate::gen_powers(Fr::rand(&mut test_rng()), 3);
+        let general = lag_ctx
+            .lagrange_interp_linear_combo(&evals, &gammas)
+            .unwrap();
+        assert_eq!(
+            crate::curve_msm::<ark_bls12_381::G1Projective>(&s.powers_of_g1, &general.coeffs)
+                .unwrap(),
+            s.gamma_ris_pt(&evals, &lag_ctx, &gammas).unwrap()
+        );
+        // The fast path must still reject evals that aren't actually zero
+        let polys = (0..3)
+            .map(|_| DensePolynomial::<Fr>::rand(20, &mut test_rng()))
+            .collect::<Vec<_>>();
+        let commits = polys
+            .iter()
+            .map(|p| s.commit(&p.coeffs).expect("Commit failed"))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            Ok(false),
+            s.verify(
+                &mut Transcript::new(b"testing"),
+                &commits,
+                &points,
+                &evals,
+                &open
+            )
+        );
+    }
+
+    #[test]
+    fn test_open_empty_polys_errors() {
+        crate::test_helpers::check_open_empty_polys_errors(
+            &M1NoPrecomp::<Bls12_381>::new(64, 4.into(), &mut test_rng()).unwrap(),
+        );
+    }
+
+    #[test]
+    fn test_transposed_evals_errors() {
+        crate::test_helpers::check_transposed_evals_errors(
+            &M1NoPrecomp::<Bls12_381>::new(64, 8.into(), &mut test_rng()).unwrap(),
+        );
+    }
+
+    #[test]
+    fn test_open_empty_points_errors() {
+        let s = M1NoPrecomp::<Bls12_381>::new(64, 4.into(), &mut test_rng()).unwrap();
+        let coeffs = vec![vec![Fr::from(1u64); 8]];
+        let evals: Vec<Vec<Fr>> = vec![vec![]];
+        assert_eq!(
+            Err(Error::NoPointsGiven),
+            s.open(&mut Transcript::new(b"testing"), &evals, &coeffs, &[])
+                .map(|p| p.0)
+        );
+    }
+
+    #[test]
+    fn test_verify_too_many_points() {
+        let s = M1NoPrecomp::<Bls12_381>::new(64, 4.into(), &mut test_rng()).unwrap();
+        let poly = DensePolynomial::<Fr>::rand(30, &mut test_rng());
+        let coeffs = vec![poly.coeffs.clone()];
+        let commits = vec![s.commit(&poly.coeffs).unwrap()];
+        for n_points in [4, 5] {
+            let points = (0..n_points)
+                .map(|_| Fr::rand(&mut test_rng()))
+                .collect::<Vec<_>>();
+            let evals = vec![points.iter().map(|x| poly.evaluate(x)).collect::<Vec<_>>()];
+            let open = s
+                .open(&mut Transcript::new(b"testing"), &evals, &coeffs, &points)
+                .expect("Open failed");
+            let res = s.verify(
+                &mut Transcript::new(b"testing"),
+                &commits,
+                &points,
+                &evals,
+                &open,
+            );
+            if n_points == 4 {
+                assert_eq!(Ok(true), res);
+            } else {
+                assert_eq!(Err(Error::NotEnoughG2Powers { needed: 6, have: 5 }), res);
+            }
+        }
+    }
+
+    #[test]
+    fn test_point_is_secret_errors() {
+        // The setup draws x first, so the same seed recovers it
+        let x = Fr::rand(&mut ark_std::test_rng());
+        let s = M1NoPrecomp::<Bls12_381>::new(64, 4.into(), &mut ark_std::test_rng()).unwrap();
+        let points = vec![Fr::rand(&mut test_rng()), x];
+        let poly = DensePolynomial::<Fr>::rand(30, &mut test_rng());
+        let evals = vec![points.iter().map(|p| poly.evaluate(p)).collect::<Vec<_>>()];
+        let coeffs = vec![poly.coeffs.clone()];
+        let commits = vec![s.commit(&poly.coeffs).unwrap()];
+        let open = s
+            .open(&mut Transcript::new(b"testing"), &evals, &coeffs, &points)
+            .expect("Open failed");
+        assert_eq!(
+            Err(Error::PointIsSecret),
+            s.verify(
+                &mut Transcript::new(b"testing"),
+                &commits,
+                &points,
+                &evals,
+                &open
+            )
+        );
+    }
+
+    #[test]
+    fn test_commit_projective() {
+        use ark_ec::CurveGroup;
+        let s = M1NoPrecomp::<Bls12_381>::new(64, None, &mut test_rng()).unwrap();
+        let poly = (0..40)
+            .map(|_| Fr::rand(&mut test_rng()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            s.commit(&poly).unwrap().0,
+            s.commit_projective(&poly).unwrap().into_affine()
+        );
+    }
+
+    #[test]
+    fn test_validate_srs() {
+        use ark_ec::CurveGroup;
+        let s = M1NoPrecomp::<Bls12_381>::new(32, 4.into(), &mut test_rng()).unwrap();
+        assert_eq!(Ok(()), s.validate_srs(&mut test_rng()));
+
+        for i in [0, 7, 31] {
+            let mut bad = s.clone();
+            bad.powers_of_g1[i] = (bad.powers_of_g1[i] * Fr::from(2u64)).into_affine();
+            assert_eq!(
+                Err(Error::InconsistentSrs),
+                bad.validate_srs(&mut test_rng())
+            );
+        }
+        let mut bad = s.clone();
+        bad.powers_of_g2[3] = (bad.powers_of_g2[3] * Fr::from(2u64)).into_affine();
+        assert_eq!(
+            Err(Error::InconsistentSrs),
+            bad.validate_srs(&mut test_rng())
+        );
+
+        // Powers of a different secret in g2
+        let other = M1NoPrecomp::<Bls12_381>::new(32, 4.into(), &mut test_rng()).unwrap();
+        let mut bad = s.clone();
+        bad.powers_of_g2 = other.powers_of_g2;
+        assert_eq!(
+            Err(Error::InconsistentSrs),
+            bad.validate_srs(&mut test_rng())
+        );
+
+        // Trivial secrets are consistent but still rejected
+        for tau in [Fr::from(0u64), Fr::from(1u64)] {
+            let trivial = M1NoPrecomp::<Bls12_381>::new_from_secret(
+                32,
+                4.into(),
+                tau,
+                s.powers_of_g1[0],
+                s.powers_of_g2[0],
+            );
+            assert_eq!(
+                Err(Error::InconsistentSrs),
+                trivial.validate_srs(&mut test_rng())
+            );
+        }
+    }
+
+    #[test]
+    fn test_commit_many() {
+        let s = M1NoPrecomp::<Bls12_381>::new(64, 8.into(), &mut test_rng()).unwrap();
+        let polys = [40, 64, 1, 0]
+            .iter()
+            .map(|&n| {
+                (0..n)
+                    .map(|_| Fr::rand(&mut test_rng()))
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        let commits = s.commit_many(&polys).unwrap();
+        assert_eq!(polys.len(), commits.len());
+        for (poly, commit) in polys.iter().zip(&commits) {
+            assert_eq!(s.commit(poly).unwrap().0, commit.0);
+        }
+        assert!(s.commit_many(&Vec::<Vec<Fr>>::new()).unwrap().is_empty());
+        assert!(s.commit_many(&[vec![Fr::from(1u64); 65]]).is_err());
+    }
+
+    #[test]
+    fn test_commit_from_evals() {
+        use crate::traits::EvalCommitter;
+        use ark_poly::{EvaluationDomain, GeneralEvaluationDomain, Radix2EvaluationDomain};
+        let s = M1NoPrecomp::<Bls12_381>::new(64, None, &mut test_rng()).unwrap();
+        for size in [1, 2, 16, 64] {
+            let domain = Radix2EvaluationDomain::<Fr>::new(size).unwrap();
+            let evals = (0..size)
+                .map(|_| Fr::rand(&mut test_rng()))
+                .collect::<Vec<_>>();
+            let expected = s.commit(domain.ifft(&evals)).unwrap().0;
+            assert_eq!(expected, s.commit_from_evals(&evals, &domain).unwrap().0);
+            assert_eq!(
+                expected,
+                s.commit_lagrange(&evals, &GeneralEvaluationDomain::Radix2(domain))
+                    .unwrap()
+                    .0
+            );
+        }
+        let domain = Radix2EvaluationDomain::<Fr>::new(16).unwrap();
+        assert_eq!(
+            Err(Error::EvalsIncorrectSize {
+                poly: 0,
+                n: 15,
+                expected: 16
+            }),
+            s.commit_from_evals(&[Fr::from(1u64); 15], &domain)
+                .map(|c| c.0)
+        );
+    }
+
+    #[test]
+    fn test_open_evals() {
+        use ark_poly::{EvaluationDomain, GeneralEvaluationDomain};
+        let s = M1NoPrecomp::<Bls12_381>::new(64, 4.into(), &mut test_rng()).unwrap();
+        let domain = GeneralEvaluationDomain::<Fr>::new(32).unwrap();
+        let points = (0..4)
+            .map(|_| Fr::rand(&mut test_rng()))
+            .collect::<Vec<_>>();
+        let polys = (0..2)
+            .map(|_| DensePolynomial::<Fr>::rand(31, &mut test_rng()))
+            .collect::<Vec<_>>();
+        let evals: Vec<Vec<_>> = polys
+            .iter()
+            .map(|p| points.iter().map(|x| p.evaluate(x)).collect())
+            .collect();
+        let domain_evals = polys
+            .iter()
+            .map(|p| domain.fft(&p.coeffs))
+            .collect::<Vec<_>>();
+        let coeffs = polys.iter().map(|p| p.coeffs.clone()).collect::<Vec<_>>();
+        let open = s
+            .open(&mut Transcript::new(b"testing"), &evals, &coeffs, &points)
+            .unwrap();
+        let open_evals = s
+            .open_evals(
+                &mut Transcript::new(b"testing"),
+                &evals,
+                &domain_evals,
+                &domain,
+                &points,
+            )
+            .unwrap();
+        assert!(open.eq_point(&open_evals));
+
+        let short = vec![domain_evals[0].clone(), domain_evals[1][..31].to_vec()];
+        assert_eq!(
+            Err(Error::EvalsIncorrectSize {
+                poly: 1,
+                n: 31,
+                expected: 32
+            }),
+            s.open_evals(
+                &mut Transcript::new(b"testing"),
+                &evals,
+                &short,
+                &domain,
+                &points
+            )
+            .map(|p| p.0)
+        );
+    }
+
+    #[test]
+    fn test_fits() {
+        let s = M1NoPrecomp::<Bls12_381>::new(64, 4.into(), &mut test_rng()).unwrap();
+        assert_eq!(63, s.max_poly_degree());
+        assert!(s.fits(64));
+        assert!(!s.fits(65));
+        let too_long = vec![Fr::from(1u64); 65];
+        assert!(s.commit(&too_long).is_err());
+
+        let mut empty = s.clone();
+        empty.powers_of_g1.clear();
+        assert_eq!(0, empty.max_coeffs());
+        assert!(empty.fits(0));
+        assert!(!empty.fits(1));
+    }
+
+    #[test]
+    fn test_commit_try_iter() {
+        let s = M1NoPrecomp::<Bls12_381>::new(64, None, &mut test_rng()).unwrap();
+        let poly = (0..60)
+            .map(|_| Fr::rand(&mut test_rng()))
+            .collect::<Vec<_>>();
+        let committed = s.commit_try_iter(poly.iter().map(|x| Ok(*x))).unwrap();
+        assert_eq!(s.commit(&poly).unwrap().0, committed.0);
+
+        // Nothing after the first error is read
+        let mut read = 0;
+        let res = s.commit_try_iter(poly.iter().enumerate().map(|(i, x)| {
+            read += 1;
+            if i == 10 {
+                Err(Error::SerializationError)
+            } else {
+                Ok(*x)
+            }
+        }));
+        assert_eq!(Some(Error::SerializationError), res.err());
+        assert_eq!(11, read);
+
+        let too_long = (0..s.powers_of_g1.len() + 1).map(|_| Ok(Fr::rand(&mut test_rng())));
+        assert!(s.commit_try_iter(too_long).is_err());
+    }
+
+    #[test]
+    fn test_commit_streaming() {
+        let s = M1NoPrecomp::<Bls12_381>::new(64, None, &mut test_rng()).unwrap();
+        let poly = (0..60)
+            .map(|_| Fr::rand(&mut test_rng()))
+            .collect::<Vec<_>>();
+        let streamed = s
+            .commit_streaming(poly.chunks(16).map(|c| c.to_vec()))
+            .unwrap();
+        assert_eq!(s.commit(&poly).unwrap().0, streamed.0);
+
+        let too_long = (0..s.powers_of_g1.len() + 1)
+            .map(|_| Fr::rand(&mut test_rng()))
+            .collect::<Vec<_>>();
+        assert!(s
+            .commit_streaming(too_long.chunks(16).map(|c| c.to_vec()))
+            .is_err());
+    }
+
+    #[test]
+    fn test_serialize_proofs() {
+        let s = M1NoPrecomp::<Bls12_381>::new(64, 4.into(), &mut test_rng()).unwrap();
+        let points = (0..4)
+            .map(|_| Fr::rand(&mut test_rng()))
+            .collect::<Vec<_>>();
+        let proofs = (0..5)
+            .map(|_| {
+                let coeffs = vec![(0..32)
+                    .map(|_| Fr::rand(&mut test_rng()))
+                    .collect::<Vec<_>>()];
+                let poly = DensePolynomial::from_coefficients_slice(&coeffs[0]);
+                let evals = vec![points.iter().map(|x| poly.evaluate(x)).collect::<Vec<_>>()];
+                s.open(&mut Transcript::new(b"testing"), &evals, &coeffs, &points)
+                    .expect("Open failed")
+            })
+            .collect::<Vec<_>>();
+        for compress in [Compress::Yes, Compress::No] {
+            let bytes = serialize_proofs_with_mode(&proofs, compress).unwrap();
+            let read = deserialize_proofs_with_mode::<Proof<Bls12_381>>(&bytes, compress).unwrap();
+            assert_eq!(
+                proofs.iter().map(|p| p.0).collect::<Vec<_>>(),
+                read.iter().map(|p| p.0).collect::<Vec<_>>()
+            );
+            assert!(deserialize_proofs_with_mode::<Proof<Bls12_381>>(
+                &bytes[..bytes.len() - 1],
+                compress
+            )
+            .is_err());
+        }
+        let bytes = serialize_proofs(&proofs).unwrap();
+        assert_eq!(
+            5,
+            deserialize_proofs::<Proof<Bls12_381>>(&bytes)
+                .unwrap()
+                .len()
+        );
+
+        assert_eq!(48, Proof::<Bls12_381>::size_in_bytes(Compress::Yes));
+        assert_eq!(96, Proof::<Bls12_381>::size_in_bytes(Compress::No));
+        for compress in [Compress::Yes, Compress::No] {
+            assert_eq!(
+                proofs[0].serialized_size(compress),
+                Proof::<Bls12_381>::size_in_bytes(compress)
+            );
+        }
+    }
+
+    #[cfg(feature = "debug")]
+    #[test]
+    fn test_debug_check_consistency() {
+        let s = M1NoPrecomp::<Bls12_381>::new(64, 4.into(), &mut test_rng()).unwrap();
+        let points = (0..4)
+            .map(|_| Fr::rand(&mut test_rng()))
+            .collect::<Vec<_>>();
+        let poly = DensePolynomial::<Fr>::rand(30, &mut test_rng());
+        let other = DensePolynomial::<Fr>::rand(30, &mut test_rng());
+        let mut evals = points.iter().map(|x| poly.evaluate(x)).collect::<Vec<_>>();
+        let commit = s.commit(&poly.coeffs).unwrap();
+        assert_eq!(
+            Ok(()),
+            s.debug_check_consistency(&poly.coeffs, &commit, &points, &evals)
+        );
+        assert_eq!(
+            Err(Error::CommitmentMismatch),
+            s.debug_check_consistency(&other.coeffs, &commit, &points, &evals)
+        );
+        evals[2] += Fr::from(1u64);
+        assert_eq!(
+            Err(Error::EvalMismatch { index: 2 }),
+            s.debug_check_consistency(&poly.coeffs, &commit, &points, &evals)
+        );
+    }
+
+    #[test]
+    fn test_context_separates_instances() {
+        let s = M1NoPrecomp::<Bls12_381>::new(32, 4.into(), &mut test_rng()).unwrap();
+        let points = (0..4)
+            .map(|_| Fr::rand(&mut test_rng()))
+            .collect::<Vec<_>>();
+        // With a single polynomial gamma wouldn't matter
+        let polys = (0..2)
+            .map(|_| DensePolynomial::<Fr>::rand(20, &mut test_rng()).coeffs)
+            .collect::<Vec<_>>();
+        let evals = polys
+            .iter()
+            .map(|p| crate::multi_evaluate(p, &points))
+            .collect::<Vec<_>>();
+        let commits = s.commit_many(&polys).unwrap();
+
+        // The same inputs under two labels draw different gammas, and so give different proofs
+        let labeled = |label: &[u8]| {
+            let mut transcript = Transcript::new(b"testing");
+            crate::transcribe_context(&mut transcript, label);
+            transcript
+        };
+        let gamma_ris = |label: &[u8]| s.gamma_ris(&labeled(label), &points, &evals).unwrap();
+        assert_ne!(gamma_ris(b"instance a"), gamma_ris(b"instance b"));
+        let open = |label: &[u8]| {
+            s.open_with_context(
+                &mut Transcript::new(b"testing"),
+                label,
+                &evals,
+                &polys,
+                &points,
+            )
+            .unwrap()
+        };
+        let (open_a, open_b) = (open(b"instance a"), open(b"instance b"));
+        assert!(!open_a.eq_point(&open_b));
+        for (label, expected) in [(&b"instance a"[..], true), (&b"instance b"[..], false)] {
+            assert_eq!(
+                Ok(expected),
+                s.verify_with_context(
+                    &mut Transcript::new(b"testing"),
+                    label,
+                    &commits,
+                    &points,
+                    &evals,
+                    &open_a,
+                )
+            );
+        }
+    }
+
+    #[test]
+    fn test_open_single() {
+        let s = M1NoPrecomp::<Bls12_381>::new(32, 4.into(), &mut test_rng()).unwrap();
+        let poly = DensePolynomial::<Fr>::rand(20, &mut test_rng());
+        let point = Fr::rand(&mut test_rng());
+        let commit = s.commit(&poly.coeffs).unwrap();
+
+        let (eval, proof) = s.open_single(&poly.coeffs, point).unwrap();
+        assert_eq!(poly.evaluate(&point), eval);
+        assert_eq!(Ok(true), s.verify_single(&commit, point, eval, &proof));
+        assert_eq!(
+            Ok(false),
+            s.verify_single(&commit, point, eval + Fr::from(1u64), &proof)
+        );
+
+        // The same as a manual open with one element slices
+        let manual = s
+            .open_pinned(
+                SINGLE_OPEN_TRANSCRIPT.transcript(),
+                &[[eval]],
+                &[&poly.coeffs],
+                &[point],
+            )
+            .unwrap();
+        assert!(manual.eq_point(&proof));
+        assert_eq!(
+            Ok(true),
+            s.verify_pinned(
+                SINGLE_OPEN_TRANSCRIPT.transcript(),
+                &[commit],
+                &[point],
+                &[[eval]],
+                &proof,
+            )
+        );
+    }
+
+    #[cfg(feature = "debug")]
+    #[test]
+    fn test_open_debug_matches_open() {
+        let s = M1NoPrecomp::<Bls12_381>::new(256, 30.into(), &mut test_rng()).unwrap();
+        let points = (0..30)
+            .map(|_| Fr::rand(&mut test_rng()))
+            .collect::<Vec<_>>();
+        let polys = (0..20)
+            .map(|_| DensePolynomial::<Fr>::rand(50, &mut test_rng()))
+            .collect::<Vec<_>>();
+        let evals: Vec<Vec<_>> = polys
+            .iter()
+            .map(|p| points.iter().map(|x| p.evaluate(x)).collect())
+            .collect();
+        let coeffs = polys.iter().map(|p| p.coeffs.clone()).collect::<Vec<_>>();
+        let open = s
+            .open(&mut Transcript::new(b"testing"), &evals, &coeffs, &points)
+            .expect("Open failed");
+        let (debug_open, q) = s
+            .open_debug(&mut Transcript::new(b"testing"), &evals, &coeffs, &points)
+            .expect("Open failed");
+        assert!(open.eq_point(&debug_open));
+        assert_eq!(s.commit(&q.coeffs).unwrap().0, debug_open.0);
+    }
+
+    #[test]
+    fn test_verify_detailed() {
+        let s = M1NoPrecomp::<Bls12_381>::new(32, 4.into(), &mut test_rng()).unwrap();
+        let points = (0..4)
+            .map(|_| Fr::rand(&mut test_rng()))
+            .collect::<Vec<_>>();
+        let polys = (0..3)
+            .map(|_| DensePolynomial::<Fr>::rand(20, &mut test_rng()))
+            .collect::<Vec<_>>();
+        let evals = polys
+            .iter()
+            .map(|p| points.iter().map(|x| p.evaluate(x)).collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        let coeffs = polys.iter().map(|p| &p.coeffs).collect::<Vec<_>>();
+        let commits = s.commit_many(&coeffs).unwrap();
+        let proof = s
+            .open(&mut Transcript::new(b"testing"), &evals, &coeffs, &points)
+            .unwrap();
+        let detailed = |evals: &[Vec<Fr>]| {
+            s.verify_detailed(
+                &mut Transcript::new(b"testing"),
+                &commits,
+                &points,
+                evals,
+                &proof,
+            )
+        };
+        assert_eq!(Ok(()), detailed(&evals));
+
+        let mut wrong_evals = evals.clone();
+        wrong_evals[0][1] += Fr::from(1u64);
+        match detailed(&wrong_evals) {
+            Err(VerifyFailure::PairingMismatch { lhs, rhs }) => assert_ne!(lhs, rhs),
+            other => panic!("expected a pairing mismatch, got {:?}", other),
+        }
+        assert_eq!(
+            Ok(false),
+            s.verify(
+                &mut Transcript::new(b"testing"),
+                &commits,
+                &points,
+                &wrong_evals,
+                &proof,
+            )
+        );
+
+        assert_eq!(
+            Err(VerifyFailure::Input(Error::EvalsIncorrectSize {
+                poly: 2,
+                n: 0,
+                expected: 4,
+            })),
+            detailed(&evals[..2])
+        );
+    }
+
+    #[test]
+    fn test_open_precomputed() {
+        let s = M1NoPrecomp::<Bls12_381>::new(32, 4.into(), &mut test_rng()).unwrap();
+        let points = (0..4)
+            .map(|_| Fr::rand(&mut test_rng()))
+            .collect::<Vec<_>>();
+        let vp = M1NoPrecomp::<Bls12_381>::precompute_vanishing(&points);
+        assert_eq!(crate::vanishing_polynomial(&points), vp);
+        for _ in 0..3 {
+            let polys = (0..2)
+                .map(|_| DensePolynomial::<Fr>::rand(20, &mut test_rng()).coeffs)
+                .collect::<Vec<_>>();
+            let evals = polys
+                .iter()
+                .map(|p| crate::multi_evaluate(p, &points))
+                .collect::<Vec<_>>();
+            let precomputed = s
+                .open_precomputed(
+                    &mut Transcript::new(b"testing"),
+                    &evals,
+                    &polys,
+                    &points,
+                    &vp,
+                )
+                .unwrap();
+            let expected = s
+                .open(&mut Transcript::new(b"testing"), &evals, &polys, &points)
+                .unwrap();
+            assert_eq!(expected.0, precomputed.0);
+        }
+    }
+
+    #[test]
+    fn test_capacity() {
+        let s = M1NoPrecomp::<Bls12_381>::new(16, 4.into(), &mut test_rng()).unwrap();
+        assert_eq!(15, s.max_poly_degree());
+        assert_eq!(4, s.max_points());
+
+        let poly = DensePolynomial::<Fr>::rand(s.max_poly_degree() + 1, &mut test_rng());
+        let expected = Err(Error::PolynomialTooLarge {
+            n_coeffs: 17,
+            expected_max: 16,
+        });
+        assert_eq!(expected, s.commit(&poly.coeffs).map(|_| ()));
+        let points = [Fr::from(1u64), Fr::from(2u64)];
+        let evals = vec![points.iter().map(|x| poly.evaluate(x)).collect::<Vec<_>>()];
+        assert_eq!(
+            expected,
+            s.open(
+                &mut Transcript::new(b"testing"),
+                &evals,
+                &[&poly.coeffs],
+                &points
+            )
+            .map(|_| ())
+        );
+    }
+
+    #[test]
+    fn test_commit_g2() {
+        use ark_ec::pairing::Pairing;
+        let s = M1NoPrecomp::<Bls12_381>::new(32, 8.into(), &mut test_rng()).unwrap();
+        let poly = DensePolynomial::<Fr>::rand(8, &mut test_rng());
+        let c1 = s.commit(&poly.coeffs).unwrap();
+        let c2 = s.commit_g2(&poly.coeffs).unwrap();
+        assert_eq!(
+            Bls12_381::pairing(c1.0, s.powers_of_g2[0]),
+            Bls12_381::pairing(s.powers_of_g1[0], c2)
+        );
+        let poly = DensePolynomial::<Fr>::rand(9, &mut test_rng());
+        assert_eq!(
+            Err(Error::PolynomialTooLarge {
+                n_coeffs: 10,
+                expected_max: 9
+            }),
+            s.commit_g2(&poly.coeffs)
+        );
+    }
+
+    #[test]
+    fn test_prepare_finish_verify() {
+        let s = M1NoPrecomp::<Bls12_381>::new(32, 4.into(), &mut test_rng()).unwrap();
+        let points = (0..4)
+            .map(|_| Fr::rand(&mut test_rng()))
+            .collect::<Vec<_>>();
+        let polys = (0..3)
+            .map(|_| DensePolynomial::<Fr>::rand(20, &mut test_rng()).coeffs)
+            .collect::<Vec<_>>();
+        let evals = polys
+            .iter()
+            .map(|p| crate::multi_evaluate(p, &points))
+            .collect::<Vec<_>>();
+        let commits = s.commit_many(&polys).unwrap();
+        let proof = s
+            .open(&mut Transcript::new(b"testing"), &evals, &polys, &points)
+            .unwrap();
+        // A proof of other polynomials with the same evals, so the same transcript
+        let vp = crate::vanishing_polynomial(&points);
+        let shifted = polys
+            .iter()
+            .map(|p| (&DensePolynomial::from_coefficients_slice(p) + &vp).coeffs)
+            .collect::<Vec<_>>();
+        let other = s
+            .open(&mut Transcript::new(b"testing"), &evals, &shifted, &points)
+            .unwrap();
+        let mut wrong_evals = evals.clone();
+        wrong_evals[2][0] += Fr::from(1u64);
+        let wrong = s
+            .open(
+                &mut Transcript::new(b"testing"),
+                &wrong_evals,
+                &polys,
+                &points,
+            )
+            .unwrap();
+
+        let state = s
+            .prepare_verify(&mut Transcript::new(b"testing"), &commits, &points, &evals)
+            .unwrap();
+        for p in [&proof, &other, &wrong] {
+            let expected = s.verify(
+                &mut Transcript::new(b"testing"),
+                &commits,
+                &points,
+                &evals,
+                p,
+            );
+            assert_eq!(expected, s.finish_verify(&state, p));
+        }
+        assert_eq!(Ok(true), s.finish_verify(&state, &proof));
+        assert_eq!(Ok(false), s.finish_verify(&state, &other));
+        assert_eq!(Ok(false), s.finish_verify(&state, &wrong));
+    }
+
+    #[test]
+    fn test_new_from_secret() {
+        use ark_bls12_381::{G1Affine, G2Affine};
+        use ark_ec::{AffineRepr, CurveGroup};
+        let tau = Fr::from(1234u64);
+        let new = || {
+            M1NoPrecomp::<Bls12_381>::new_from_secret(
+                16,
+                4.into(),
+                tau,
+                G1Affine::generator(),
+                G2Affine::generator(),
+            )
+        };
+        let (a, b) = (new(), new());
+        assert_eq!(a.powers_of_g1, b.powers_of_g1);
+        assert_eq!(a.powers_of_g2, b.powers_of_g2);
+        assert_eq!(16, a.powers_of_g1.len());
+        assert_eq!(5, a.powers_of_g2.len());
+        assert_eq!(G1Affine::generator(), a.powers_of_g1[0]);
+        assert_eq!(
+            (G1Affine::generator() * tau).into_affine(),
+            a.powers_of_g1[1]
+        );
+        assert_eq!(Ok(()), a.validate_srs(&mut test_rng()));
+
+        let poly = DensePolynomial::<Fr>::rand(15, &mut test_rng());
+        assert_eq!(
+            a.commit(&poly.coeffs).unwrap().0,
+            b.commit(&poly.coeffs).unwrap().0
+        );
     }
 }
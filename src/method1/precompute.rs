@@ -2,7 +2,9 @@ use ark_poly::univariate::DensePolynomial;
 use merlin::Transcript;
 use std::usize;
 
-use ark_ec::pairing::Pairing;
+use ark_ec::{pairing::Pairing, AffineRepr, CurveGroup};
+use ark_serialize::{CanonicalSerialize, Valid};
+use ark_std::{rand::RngCore, UniformRand, Zero};
 
 #[cfg(feature = "parallel")]
 use rayon::prelude::*;
@@ -10,8 +12,15 @@ use rayon::prelude::*;
 use super::{vanishing_polynomial, Error, Proof};
 use crate::lagrange::LagrangeInterpContext;
 use crate::traits::{Committer, PolyMultiProof, PolyMultiProofNoPrecomp};
-use crate::{Commitment, cfg_iter};
+use crate::{
+    cfg_iter, check_disjoint, gen_powers, get_challenge, get_field_size, linear_combination,
+    poly_div_q_r, transcribe_header, transcribe_points_and_evals, Commitment,
+    METHOD1_TRANSCRIPT_TAG,
+};
 
+/// A method 1 setup with everything that only depends on the point sets computed up front: the
+/// vanishing polynomials, their commitments in G2, and the lagrange bases. Verifying then only
+/// does the transcript, the interpolation against the cached basis, the msms, and two pairings.
 #[derive(Clone, Debug)]
 pub struct M1Precomp<E: Pairing> {
     pub inner: super::M1NoPrecomp<E>,
@@ -22,10 +31,45 @@ pub struct M1Precomp<E: Pairing> {
 }
 
 impl<E: Pairing> M1Precomp<E> {
+    /// Same as [`PolyMultiProof::new`], but errors with [`Error::OverlappingPointSets`] if any
+    /// point is in more than one point set
+    pub fn new_strict(
+        max_coeffs: usize,
+        point_sets: Vec<Vec<E::ScalarField>>,
+        r: &mut impl ark_std::rand::RngCore,
+    ) -> Result<Self, Error> {
+        check_disjoint(&point_sets)?;
+        <Self as PolyMultiProof<E>>::new(max_coeffs, point_sets, r)
+    }
+
+    /// Same as [`M1Precomp::from_inner`], but errors with [`Error::OverlappingPointSets`] if any
+    /// point is in more than one point set
+    pub fn from_inner_strict(
+        inner: super::M1NoPrecomp<E>,
+        point_sets: Vec<Vec<E::ScalarField>>,
+    ) -> Result<Self, Error> {
+        check_disjoint(&point_sets)?;
+        Self::from_inner(inner, point_sets)
+    }
+
+    /// The number of point sets this setup can open at
+    pub fn num_point_sets(&self) -> usize {
+        self.point_sets.len()
+    }
+
+    /// The number of points in the point set at `index`, or `None` if there is no such set
+    pub fn point_set_len(&self, index: usize) -> Option<usize> {
+        self.point_sets.get(index).map(|ps| ps.len())
+    }
+
     pub fn from_inner(
         inner: super::M1NoPrecomp<E>,
         point_sets: Vec<Vec<<E as Pairing>::ScalarField>>,
     ) -> Result<Self, Error> {
+        if point_sets.iter().any(|ps| ps.is_empty()) {
+            return Err(Error::NoPointsGiven);
+        }
+        crate::check_point_set_sizes(&point_sets, inner.powers_of_g2.len())?;
         let vanishing_polys: Vec<_> = cfg_iter!(point_sets)
             .map(|(_, ps)| vanishing_polynomial(ps))
             .collect();
@@ -46,12 +90,342 @@ impl<E: Pairing> M1Precomp<E> {
     }
 }
 
+impl<E: Pairing> M1Precomp<E> {
+    /// Verifies many openings, such as every cell of a grid, grouping them by point set. Each
+    /// group is checked with a random linear combination of its items in two pairings, and with
+    /// the `parallel` feature the groups are checked in parallel. Returns the indices of all
+    /// invalid items, in increasing order.
+    pub fn verify_many_by_set<T: AsRef<[E::ScalarField]> + Sync>(
+        &self,
+        items: &[VerifyItem<E, T>],
+        rng: &mut impl RngCore,
+    ) -> Result<Vec<usize>, Error> {
+        let mut groups = vec![Vec::new(); self.point_sets.len()];
+        for (i, item) in items.iter().enumerate() {
+            groups
+                .get_mut(item.point_set_index)
+                .ok_or(Error::PointSetIndexOutOfRange {
+                    index: item.point_set_index,
+                    n: self.point_sets.len(),
+                })?
+                .push(i);
+        }
+        // Drawn up front so the groups don't share the rng
+        let rs = (0..items.len())
+            .map(|_| E::ScalarField::rand(rng))
+            .collect::<Vec<_>>();
+        let failures = cfg_iter!(groups)
+            .map(|(set, group)| self.invalid_in_group(set, group, items, &rs))
+            .collect::<Result<Vec<_>, Error>>()?;
+        let mut failures = failures.concat();
+        failures.sort_unstable();
+        Ok(failures)
+    }
+
+    /// Verifies every cell of a grid, returning whether each of `items` is valid, in the same
+    /// order. Unlike [`M1Precomp::verify_many_by_set`], a cell with malformed inputs, such as
+    /// evals of the wrong size, a point set that doesn't exist, or a commitment or proof outside
+    /// the prime order subgroup, is reported as invalid instead
+    /// of failing the whole grid, so a sampler learns exactly which cells are unavailable or
+    /// corrupt. The valid cells are batched by point set the same way, in parallel with the
+    /// `parallel` feature.
+    pub fn verify_grid_bitmap<T: AsRef<[E::ScalarField]> + Sync>(
+        &self,
+        items: &[VerifyItem<E, T>],
+        rng: &mut impl RngCore,
+    ) -> Result<Vec<bool>, Error> {
+        let rs = (0..items.len())
+            .map(|_| E::ScalarField::rand(rng))
+            .collect::<Vec<_>>();
+        let lhss = cfg_iter!(items)
+            .map(|(_, item)| {
+                if item.point_set_index >= self.point_sets.len() {
+                    return None;
+                }
+                crate::traits::check_points(item.commits, item.proof).ok()?;
+                self.item_lhs(item).ok()
+            })
+            .collect::<Vec<_>>();
+        let mut groups = vec![Vec::new(); self.point_sets.len()];
+        for (i, lhs) in lhss.iter().enumerate() {
+            if let Some(lhs) = lhs {
+                groups[items[i].point_set_index].push((i, *lhs));
+            }
+        }
+        let failures = cfg_iter!(groups)
+            .map(|(set, group)| self.invalid_with_lhss(set, group, items, &rs))
+            .collect::<Result<Vec<_>, Error>>()?;
+
+        let mut valid = lhss.iter().map(Option::is_some).collect::<Vec<_>>();
+        for i in failures.concat() {
+            valid[i] = false;
+        }
+        Ok(valid)
+    }
+
+    /// Opens the same polynomials at several point sets at once, such as every chunk of a grid
+    /// row. `evals_per_set[j]` are the evaluations of `polys` at point set `point_set_indices[j]`.
+    ///
+    /// All the point sets and evaluations go in the transcript before a single challenge is
+    /// drawn, so the linear combination of `polys` is computed once and only the divisions are
+    /// done per set, in parallel with the `parallel` feature. Returns one proof per point set, in
+    /// order. The proofs share the challenge, so they must be checked with
+    /// [`M1Precomp::verify_row`], not [`PolyMultiProof::verify`].
+    pub fn open_row<T: AsRef<[E::ScalarField]>, S: AsRef<[T]>>(
+        &self,
+        transcript: &mut Transcript,
+        polys: &[impl AsRef<[E::ScalarField]>],
+        evals_per_set: &[S],
+        point_set_indices: &[usize],
+    ) -> Result<Vec<Proof<E>>, Error> {
+        let gamma = self.transcribe_row(transcript, evals_per_set, point_set_indices)?;
+        let gammas = gen_powers(gamma, polys.len());
+        let fsum = linear_combination(polys, &gammas)?;
+        let fsum = DensePolynomial { coeffs: fsum };
+        cfg_iter!(point_set_indices)
+            .map(|(_, &set)| {
+                let (q, _) = poly_div_q_r((&fsum).into(), (&self.vanishing_polys[set]).into())?;
+                Ok(Proof(
+                    crate::curve_msm::<E::G1>(&self.inner.powers_of_g1, &q)?.into_affine(),
+                ))
+            })
+            .collect()
+    }
+
+    /// Verifies proofs from [`M1Precomp::open_row`], returning true only if every one of them is
+    /// valid. `commits` are the commitments to the polynomials that were opened.
+    pub fn verify_row<T: AsRef<[E::ScalarField]>, S: AsRef<[T]>>(
+        &self,
+        transcript: &mut Transcript,
+        commits: impl AsRef<[Commitment<E>]>,
+        evals_per_set: &[S],
+        point_set_indices: &[usize],
+        proofs: &[Proof<E>],
+    ) -> Result<bool, Error> {
+        if proofs.len() != point_set_indices.len() {
+            return Err(Error::PointSetCountMismatch {
+                n: proofs.len(),
+                expected: point_set_indices.len(),
+            });
+        }
+        // The commitments are shared by every proof, so only check them once
+        Commitment::<E>::batch_check(commits.as_ref().iter()).map_err(|_| Error::InvalidPoint)?;
+        for proof in proofs {
+            proof.check().map_err(|_| Error::InvalidPoint)?;
+        }
+        let gamma = self.transcribe_row(transcript, evals_per_set, point_set_indices)?;
+        let g2 = self.inner.powers_of_g2[0];
+        for ((evals, &set), proof) in evals_per_set.iter().zip(point_set_indices).zip(proofs) {
+            if self.g2_zeros[set].is_zero() {
+                return Err(Error::PointIsSecret);
+            }
+            let lhs = self.inner.verify_lhs_with_gamma(
+                commits.as_ref(),
+                self.point_sets[set].len(),
+                evals.as_ref(),
+                &self.lagrange_ctxs[set],
+                gamma,
+            )?;
+            if E::pairing(lhs, g2) != E::pairing(proof.0, self.g2_zeros[set]) {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+
+    /// Verifies many openings at point set `point_set_index`, such as the cells of a grid
+    /// column, returning true only if every one of them is valid. Each entry is checked against a
+    /// clone of `transcript`, which should be in the same state as the transcripts given to
+    /// `open`.
+    ///
+    /// The checks are folded into one with the powers of a challenge `batch rho`, drawn from
+    /// `transcript` after absorbing every entry's proof and $\sum_i \gamma^i (C_i - g_1^{r_i(x)})$
+    /// as `batch items`, so the whole batch is two msms and two pairings.
+    pub fn verify_batch<T: AsRef<[E::ScalarField]>>(
+        &self,
+        transcript: &mut Transcript,
+        batch: &[(&[Commitment<E>], &[T], &Proof<E>)],
+        point_set_index: usize,
+    ) -> Result<bool, Error> {
+        if batch.is_empty() {
+            return Err(Error::NoPolynomialsGiven);
+        }
+        let points =
+            self.point_sets
+                .get(point_set_index)
+                .ok_or(Error::PointSetIndexOutOfRange {
+                    index: point_set_index,
+                    n: self.point_sets.len(),
+                })?;
+        let g2_zeros = self.g2_zeros[point_set_index];
+        if g2_zeros.is_zero() {
+            return Err(Error::PointIsSecret);
+        }
+        for &(commits, _, proof) in batch {
+            crate::traits::check_points(commits, proof)?;
+        }
+        let lhss = batch
+            .iter()
+            .map(|&(commits, evals, _)| {
+                self.inner.verify_lhs(
+                    &mut transcript.clone(),
+                    commits,
+                    points,
+                    evals,
+                    &self.lagrange_ctxs[point_set_index],
+                )
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+        let lhss = E::G1::normalize_batch(&lhss);
+        let proofs = batch.iter().map(|&(_, _, p)| p.0).collect::<Vec<_>>();
+
+        let mut item_bytes = Vec::new();
+        for (l, p) in lhss.iter().zip(&proofs) {
+            l.serialize_compressed(&mut item_bytes)?;
+            p.serialize_compressed(&mut item_bytes)?;
+        }
+        transcript.append_message(b"batch items", &item_bytes);
+        let rho = get_challenge(transcript, b"batch rho", get_field_size::<E::ScalarField>());
+        let rhos = gen_powers(rho, batch.len());
+
+        let lhs = crate::curve_msm::<E::G1>(&lhss, &rhos)?;
+        let rhs = crate::curve_msm::<E::G1>(&proofs, &rhos)?;
+        let g2 = self.inner.powers_of_g2[0].into_group();
+        Ok(E::multi_pairing([lhs, -rhs], [g2, g2_zeros]).is_zero())
+    }
+
+    /// Puts every point set of a row and its evaluations in the transcript, and draws the
+    /// challenge they share
+    fn transcribe_row<T: AsRef<[E::ScalarField]>, S: AsRef<[T]>>(
+        &self,
+        transcript: &mut Transcript,
+        evals_per_set: &[S],
+        point_set_indices: &[usize],
+    ) -> Result<E::ScalarField, Error> {
+        if evals_per_set.len() != point_set_indices.len() {
+            return Err(Error::PointSetCountMismatch {
+                n: evals_per_set.len(),
+                expected: point_set_indices.len(),
+            });
+        }
+        if point_set_indices.is_empty() {
+            return Err(Error::NoPointsGiven);
+        }
+        transcribe_header(transcript, METHOD1_TRANSCRIPT_TAG);
+        let field_size_bytes = get_field_size::<E::ScalarField>();
+        for (evals, &set) in evals_per_set.iter().zip(point_set_indices) {
+            let points = self
+                .point_sets
+                .get(set)
+                .ok_or(Error::PointSetIndexOutOfRange {
+                    index: set,
+                    n: self.point_sets.len(),
+                })?;
+            transcribe_points_and_evals(transcript, points, evals.as_ref(), field_size_bytes)?;
+        }
+        Ok(get_challenge(
+            transcript,
+            b"open row gamma",
+            field_size_bytes,
+        ))
+    }
+
+    /// Checks the items at `group`, which all open at point set `set`, returning the invalid ones.
+    /// Errors if any item's commitments or proof aren't valid points.
+    fn invalid_in_group<T: AsRef<[E::ScalarField]>>(
+        &self,
+        set: usize,
+        group: &[usize],
+        items: &[VerifyItem<E, T>],
+        rs: &[E::ScalarField],
+    ) -> Result<Vec<usize>, Error> {
+        let lhss = group
+            .iter()
+            .map(|&i| {
+                crate::traits::check_points(items[i].commits, items[i].proof)?;
+                self.item_lhs(&items[i]).map(|l| (i, l))
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+        self.invalid_with_lhss(set, &lhss, items, rs)
+    }
+
+    /// The G1 point of an item's check that is paired with $g_2$
+    fn item_lhs<T: AsRef<[E::ScalarField]>>(
+        &self,
+        item: &VerifyItem<E, T>,
+    ) -> Result<E::G1, Error> {
+        let set = item.point_set_index;
+        self.inner.verify_lhs(
+            &mut item.transcript.clone(),
+            item.commits,
+            &self.point_sets[set],
+            item.evals,
+            &self.lagrange_ctxs[set],
+        )
+    }
+
+    /// Same as [`M1Precomp::invalid_in_group`], with the lhs of each item in the group already
+    /// computed
+    fn invalid_with_lhss<T: AsRef<[E::ScalarField]>>(
+        &self,
+        set: usize,
+        lhss: &[(usize, E::G1)],
+        items: &[VerifyItem<E, T>],
+        rs: &[E::ScalarField],
+    ) -> Result<Vec<usize>, Error> {
+        if lhss.is_empty() {
+            return Ok(Vec::new());
+        }
+        let g2_zeros = self.g2_zeros[set];
+        if g2_zeros.is_zero() {
+            return Err(Error::PointIsSecret);
+        }
+
+        // Each item checks e(lhs, g2) = e(proof, g2_zeros), so the combination does too
+        let mut lhs = E::G1::zero();
+        let mut rhs = E::G1::zero();
+        for (i, l) in lhss {
+            lhs += *l * rs[*i];
+            rhs += items[*i].proof.0 * rs[*i];
+        }
+        let g2 = self.inner.powers_of_g2[0].into_group();
+        if E::multi_pairing([lhs, -rhs], [g2, g2_zeros]).is_zero() {
+            return Ok(Vec::new());
+        }
+        // Something in the group is invalid, find everything that is
+        Ok(lhss
+            .iter()
+            .filter(|(i, l)| E::pairing(l, g2) != E::pairing(items[*i].proof.0, g2_zeros))
+            .map(|(i, _)| *i)
+            .collect())
+    }
+}
+
+/// One opening in a call to [`M1Precomp::verify_many_by_set`]. `transcript` should be in the
+/// same state as the transcript given to `open`.
+pub struct VerifyItem<'a, E: Pairing, T: AsRef<[E::ScalarField]>> {
+    pub transcript: Transcript,
+    pub commits: &'a [Commitment<E>],
+    pub point_set_index: usize,
+    pub evals: &'a [T],
+    pub proof: &'a Proof<E>,
+}
+
 impl<E: Pairing> Committer<E> for M1Precomp<E> {
-    fn commit(
+    fn commit_projective(
         &self,
         poly: impl AsRef<[<E as Pairing>::ScalarField]>,
-    ) -> Result<Commitment<E>, Error> {
-        self.inner.commit(poly)
+    ) -> Result<E::G1, Error> {
+        self.inner.commit_projective(poly)
+    }
+    fn commit_many(
+        &self,
+        polys: &[impl AsRef<[E::ScalarField]> + Sync],
+    ) -> Result<Vec<Commitment<E>>, Error> {
+        self.inner.commit_many(polys)
+    }
+    fn max_coeffs(&self) -> usize {
+        self.inner.max_coeffs()
     }
 }
 
@@ -83,27 +457,41 @@ impl<E: Pairing> PolyMultiProof<E> for M1Precomp<E> {
         polys: &[impl AsRef<[<E as Pairing>::ScalarField]>],
         point_set_index: usize,
     ) -> Result<Self::Proof, Error> {
+        let points =
+            self.point_sets
+                .get(point_set_index)
+                .ok_or(Error::PointSetIndexOutOfRange {
+                    index: point_set_index,
+                    n: self.point_sets.len(),
+                })?;
         self.inner.open_with_vanishing_poly(
             transcript,
             evals,
             polys,
-            &self.point_sets[point_set_index],
+            points,
             &self.vanishing_polys[point_set_index],
         )
     }
 
-    fn verify(
+    fn verify_unchecked(
         &self,
         transcript: &mut Transcript,
-        commits: &[Commitment<E>],
+        commits: impl AsRef<[Commitment<E>]>,
         point_set_index: usize,
         evals: &[impl AsRef<[<E as Pairing>::ScalarField]>],
         proof: &Self::Proof,
     ) -> Result<bool, Error> {
+        let points =
+            self.point_sets
+                .get(point_set_index)
+                .ok_or(Error::PointSetIndexOutOfRange {
+                    index: point_set_index,
+                    n: self.point_sets.len(),
+                })?;
         self.inner.verify_with_lag_ctx_g2_zeros(
             transcript,
-            commits,
-            &self.point_sets[point_set_index],
+            commits.as_ref(),
+            points,
             evals,
             proof,
             &self.lagrange_ctxs[point_set_index],
@@ -114,10 +502,11 @@ impl<E: Pairing> PolyMultiProof<E> for M1Precomp<E> {
 
 #[cfg(test)]
 mod tests {
-    use super::M1Precomp;
+    use super::{M1Precomp, Proof, VerifyItem};
     use crate::{
         test_rng,
         traits::{Committer, PolyMultiProof},
+        Error,
     };
     use ark_bls12_381::{Bls12_381, Fr};
     use ark_poly::{univariate::DensePolynomial, DenseUVPolynomial, Polynomial};
@@ -152,5 +541,378 @@ mod tests {
             Ok(true),
             s.verify(&mut transcript, &commits, 0, &evals, &open)
         );
+
+        assert_eq!(
+            Some(Error::PointSetIndexOutOfRange { index: 1, n: 1 }),
+            s.open(&mut Transcript::new(b"testing"), &evals, &coeffs, 1)
+                .err()
+        );
+        assert_eq!(
+            Err(Error::PointSetIndexOutOfRange { index: 1, n: 1 }),
+            s.verify(&mut Transcript::new(b"testing"), &commits, 1, &evals, &open)
+        );
+    }
+
+    #[test]
+    fn test_open_row() {
+        let point_sets = (0..4)
+            .map(|_| {
+                (0..4)
+                    .map(|_| Fr::rand(&mut test_rng()))
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        let s = M1Precomp::<Bls12_381>::new(32, point_sets.clone(), &mut test_rng())
+            .expect("Failed to construct");
+        let polys = (0..3)
+            .map(|_| DensePolynomial::<Fr>::rand(15, &mut test_rng()))
+            .collect::<Vec<_>>();
+        let coeffs = polys.iter().map(|p| p.coeffs.clone()).collect::<Vec<_>>();
+        let commits = s.commit_many(&coeffs).unwrap();
+        let indices = [2, 0, 3];
+        let evals_per_set = indices
+            .iter()
+            .map(|&i| {
+                polys
+                    .iter()
+                    .map(|p| {
+                        point_sets[i]
+                            .iter()
+                            .map(|x| p.evaluate(x))
+                            .collect::<Vec<_>>()
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        let proofs = s
+            .open_row(
+                &mut Transcript::new(b"testing"),
+                &coeffs,
+                &evals_per_set,
+                &indices,
+            )
+            .expect("Open failed");
+        assert_eq!(3, proofs.len());
+        let verify = |evals_per_set: &[Vec<Vec<Fr>>], proofs: &[Proof<Bls12_381>]| {
+            s.verify_row(
+                &mut Transcript::new(b"testing"),
+                &commits,
+                evals_per_set,
+                &indices,
+                proofs,
+            )
+        };
+        assert_eq!(Ok(true), verify(&evals_per_set, &proofs));
+
+        let mut bad_evals = evals_per_set.clone();
+        bad_evals[1][2][0] += Fr::from(1u64);
+        assert_eq!(Ok(false), verify(&bad_evals, &proofs));
+        let mut swapped = proofs.clone();
+        swapped.swap(0, 1);
+        assert_eq!(Ok(false), verify(&evals_per_set, &swapped));
+        let mut invalid = proofs.clone();
+        invalid[2] = Proof(crate::test_helpers::non_subgroup_g1());
+        assert_eq!(Err(Error::InvalidPoint), verify(&evals_per_set, &invalid));
+        assert_eq!(
+            Err(Error::PointSetCountMismatch { n: 2, expected: 3 }),
+            verify(&evals_per_set, &proofs[..2])
+        );
+        assert_eq!(
+            Some(Error::PointSetIndexOutOfRange { index: 4, n: 4 }),
+            s.open_row(
+                &mut Transcript::new(b"testing"),
+                &coeffs,
+                &evals_per_set,
+                &[0, 1, 4]
+            )
+            .err()
+        );
+    }
+
+    #[test]
+    fn test_point_set_too_large() {
+        use crate::{method1::M1NoPrecomp, traits::PolyMultiProofNoPrecomp};
+        let inner = M1NoPrecomp::<Bls12_381>::new(32, 4.into(), &mut test_rng()).unwrap();
+        let point_sets = [3, 4, 5]
+            .iter()
+            .map(|&n| {
+                (0..n)
+                    .map(|_| Fr::rand(&mut test_rng()))
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        assert!(M1Precomp::from_inner(inner.clone(), point_sets[..2].to_vec()).is_ok());
+        assert_eq!(
+            Some(Error::NotEnoughG2Powers { needed: 6, have: 5 }),
+            M1Precomp::from_inner(inner, point_sets).err()
+        );
+    }
+
+    #[test]
+    fn test_open_matches_no_precomp() {
+        use crate::traits::PolyMultiProofNoPrecomp;
+        let points = (0..8)
+            .map(|_| Fr::rand(&mut test_rng()))
+            .collect::<Vec<_>>();
+        let s = M1Precomp::<Bls12_381>::new(64, vec![points.clone()], &mut test_rng())
+            .expect("Failed to construct");
+        let poly = DensePolynomial::<Fr>::rand(40, &mut test_rng());
+        let evals = vec![points.iter().map(|x| poly.evaluate(x)).collect::<Vec<_>>()];
+        let open = s
+            .open(&mut Transcript::new(b"testing"), &evals, &[&poly.coeffs], 0)
+            .expect("Open failed");
+        let expected = s
+            .inner
+            .open(
+                &mut Transcript::new(b"testing"),
+                &evals,
+                &[&poly.coeffs],
+                &points,
+            )
+            .expect("Open failed");
+        assert!(open.eq_point(&expected));
+        let other = s
+            .open(&mut Transcript::new(b"other"), &evals, &[&poly.coeffs], 0)
+            .expect("Open failed");
+        assert!(!open.eq_point(&other));
+    }
+
+    #[test]
+    fn test_verify_many_by_set() {
+        let point_sets = (0..3)
+            .map(|_| {
+                (0..4)
+                    .map(|_| Fr::rand(&mut test_rng()))
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        let s = M1Precomp::<Bls12_381>::new(32, point_sets.clone(), &mut test_rng())
+            .expect("Failed to construct");
+        let polys = (0..2)
+            .map(|_| DensePolynomial::<Fr>::rand(20, &mut test_rng()))
+            .collect::<Vec<_>>();
+        let coeffs = polys.iter().map(|p| p.coeffs.clone()).collect::<Vec<_>>();
+        let commits = coeffs
+            .iter()
+            .map(|p| s.commit(p).expect("Commit failed"))
+            .collect::<Vec<_>>();
+        // Two openings per point set
+        let mut evals = (0..6)
+            .map(|i| {
+                polys
+                    .iter()
+                    .map(|p| point_sets[i % 3].iter().map(|x| p.evaluate(x)).collect())
+                    .collect::<Vec<Vec<_>>>()
+            })
+            .collect::<Vec<_>>();
+        let proofs = (0..6)
+            .map(|i| {
+                s.open(&mut Transcript::new(b"testing"), &evals[i], &coeffs, i % 3)
+                    .expect("Open failed")
+            })
+            .collect::<Vec<_>>();
+        let items = |evals: &[Vec<Vec<Fr>>]| -> Vec<_> {
+            (0..6)
+                .map(|i| VerifyItem {
+                    transcript: Transcript::new(b"testing"),
+                    commits: &commits[..],
+                    point_set_index: i % 3,
+                    evals: &evals[i][..],
+                    proof: &proofs[i],
+                })
+                .collect()
+        };
+        assert_eq!(
+            Ok(vec![]),
+            s.verify_many_by_set(&items(&evals), &mut test_rng())
+        );
+
+        evals[1][0][2] += Fr::from(1u64);
+        evals[4][1][0] += Fr::from(1u64);
+        evals[5][0][0] += Fr::from(1u64);
+        assert_eq!(
+            Ok(vec![1, 4, 5]),
+            s.verify_many_by_set(&items(&evals), &mut test_rng())
+        );
+        assert_eq!(
+            Ok(vec![true, false, true, true, false, false]),
+            s.verify_grid_bitmap(&items(&evals), &mut test_rng())
+        );
+
+        // Points outside the subgroup are rejected before any pairing
+        let bad_proof = Proof(crate::test_helpers::non_subgroup_g1());
+        let mut bad = items(&evals);
+        bad[2].proof = &bad_proof;
+        assert_eq!(
+            Err(Error::InvalidPoint),
+            s.verify_many_by_set(&bad, &mut test_rng())
+        );
+        assert_eq!(
+            Ok(vec![true, false, false, true, false, false]),
+            s.verify_grid_bitmap(&bad, &mut test_rng())
+        );
+
+        // Malformed cells are only invalid in the bitmap
+        evals[2][0].pop();
+        let mut malformed = items(&evals);
+        malformed[3].point_set_index = 7;
+        assert!(s.verify_many_by_set(&malformed, &mut test_rng()).is_err());
+        assert_eq!(
+            Ok(vec![true, false, false, false, false, false]),
+            s.verify_grid_bitmap(&malformed, &mut test_rng())
+        );
+    }
+
+    #[test]
+    fn test_verify_batch() {
+        let points = (0..4)
+            .map(|_| Fr::rand(&mut test_rng()))
+            .collect::<Vec<_>>();
+        let s = M1Precomp::<Bls12_381>::new(32, vec![points.clone()], &mut test_rng())
+            .expect("Failed to construct");
+        let polys = (0..50)
+            .map(|_| DensePolynomial::<Fr>::rand(20, &mut test_rng()))
+            .collect::<Vec<_>>();
+        let commits = polys
+            .iter()
+            .map(|p| s.commit(&p.coeffs).expect("Commit failed"))
+            .collect::<Vec<_>>();
+        let mut evals = polys
+            .iter()
+            .map(|p| vec![points.iter().map(|x| p.evaluate(x)).collect::<Vec<_>>()])
+            .collect::<Vec<_>>();
+        let proofs = polys
+            .iter()
+            .zip(&evals)
+            .map(|(p, e)| {
+                s.open(&mut Transcript::new(b"testing"), e, &[&p.coeffs], 0)
+                    .expect("Open failed")
+            })
+            .collect::<Vec<_>>();
+        let batch = |evals: &[Vec<Vec<Fr>>]| -> Vec<_> {
+            (0..50)
+                .map(|i| (&commits[i..i + 1], &evals[i][..], &proofs[i]))
+                .collect()
+        };
+        assert_eq!(
+            Ok(true),
+            s.verify_batch(&mut Transcript::new(b"testing"), &batch(&evals), 0)
+        );
+
+        evals[17][0][3] += Fr::from(1u64);
+        assert_eq!(
+            Ok(false),
+            s.verify_batch(&mut Transcript::new(b"testing"), &batch(&evals), 0)
+        );
+        assert_eq!(
+            Err(Error::PointSetIndexOutOfRange { index: 1, n: 1 }),
+            s.verify_batch(&mut Transcript::new(b"testing"), &batch(&evals), 1)
+        );
+    }
+
+    #[test]
+    fn test_open_with_digest() {
+        let points = (0..4)
+            .map(|_| Fr::rand(&mut test_rng()))
+            .collect::<Vec<_>>();
+        let s = M1Precomp::<Bls12_381>::new(32, vec![points.clone()], &mut test_rng())
+            .expect("Failed to construct");
+        let poly = DensePolynomial::<Fr>::rand(20, &mut test_rng());
+        let mut evals = vec![points.iter().map(|x| poly.evaluate(x)).collect::<Vec<_>>()];
+        let coeffs = vec![poly.coeffs.clone()];
+        let commits = vec![s.commit(&poly.coeffs).unwrap()];
+        let (open, open_digest) = s
+            .open_with_digest(&mut Transcript::new(b"testing"), &evals, &coeffs, 0)
+            .expect("Open failed");
+        let (valid, verify_digest) = s
+            .verify_with_digest(&mut Transcript::new(b"testing"), &commits, 0, &evals, &open)
+            .unwrap();
+        assert!(valid);
+        assert_eq!(open_digest, verify_digest);
+
+        evals[0][0] += Fr::from(1u64);
+        let (valid, verify_digest) = s
+            .verify_with_digest(&mut Transcript::new(b"testing"), &commits, 0, &evals, &open)
+            .unwrap();
+        assert!(!valid);
+        assert_ne!(open_digest, verify_digest);
+    }
+
+    #[test]
+    fn test_open_borrowed_grid_window() {
+        let points = (0..4)
+            .map(|_| Fr::rand(&mut test_rng()))
+            .collect::<Vec<_>>();
+        let s = M1Precomp::<Bls12_381>::new(32, vec![points.clone()], &mut test_rng())
+            .expect("Failed to construct");
+        let polys = (0..6)
+            .map(|_| DensePolynomial::<Fr>::rand(20, &mut test_rng()))
+            .collect::<Vec<_>>();
+        // Each row holds some extra evals on either side of the opened window
+        let grid: Vec<Vec<_>> = polys
+            .iter()
+            .map(|p| {
+                let mut row = vec![Fr::from(7u64)];
+                row.extend(points.iter().map(|x| p.evaluate(x)));
+                row.push(Fr::from(9u64));
+                row
+            })
+            .collect();
+        let evals: Vec<&[Fr]> = grid[1..5].iter().map(|row| &row[1..5]).collect();
+        let coeffs: Vec<&[Fr]> = polys[1..5].iter().map(|p| p.coeffs.as_slice()).collect();
+        let commits = coeffs
+            .iter()
+            .map(|p| s.commit(p).expect("Commit failed"))
+            .collect::<Vec<_>>();
+        let open = s
+            .open(&mut Transcript::new(b"testing"), &evals, &coeffs, 0)
+            .expect("Open failed");
+        assert_eq!(
+            Ok(true),
+            s.verify(&mut Transcript::new(b"testing"), &commits, 0, &evals, &open)
+        );
+    }
+
+    #[test]
+    fn test_point_set_getters() {
+        let point_sets = vec![
+            (0..3)
+                .map(|_| Fr::rand(&mut test_rng()))
+                .collect::<Vec<_>>(),
+            (0..5)
+                .map(|_| Fr::rand(&mut test_rng()))
+                .collect::<Vec<_>>(),
+        ];
+        let s = M1Precomp::<Bls12_381>::new(16, point_sets, &mut test_rng()).unwrap();
+        assert_eq!(2, s.num_point_sets());
+        assert_eq!(Some(3), s.point_set_len(0));
+        assert_eq!(Some(5), s.point_set_len(1));
+        assert_eq!(None, s.point_set_len(2));
+    }
+
+    #[test]
+    fn test_strict_rejects_overlap() {
+        let points = (0..4)
+            .map(|_| Fr::rand(&mut test_rng()))
+            .collect::<Vec<_>>();
+        let disjoint = vec![points[..2].to_vec(), points[2..].to_vec()];
+        let overlapping = vec![points[..3].to_vec(), points[2..].to_vec()];
+        assert!(M1Precomp::<Bls12_381>::new_strict(16, disjoint, &mut test_rng()).is_ok());
+        assert_eq!(
+            Err(Error::OverlappingPointSets),
+            M1Precomp::<Bls12_381>::new_strict(16, overlapping.clone(), &mut test_rng())
+                .map(|_| ())
+        );
+        // Non-strict construction still allows overlap
+        assert!(M1Precomp::<Bls12_381>::new(16, overlapping, &mut test_rng()).is_ok());
+    }
+
+    #[test]
+    fn test_empty_point_set_errors() {
+        let points = vec![Fr::rand(&mut test_rng())];
+        assert_eq!(
+            Err(Error::NoPointsGiven),
+            M1Precomp::<Bls12_381>::new(16, vec![points, vec![]], &mut test_rng()).map(|_| ())
+        );
     }
 }
@@ -0,0 +1,333 @@
+//! Aggregating proofs of unrelated openings from the same prover, so they ship as one proof.
+//!
+//! Each method 2 proof checks that $f_i$, computed by the verifier from the commitments, evals,
+//! and $W_{1,i}$, commits to $l_i = q_i \cdot (X - z_i)$ where $W_{2,i}$ commits to $q_i$. Those
+//! openings at the points $z_i$ are folded as in shplonk: with $T$ the set of the $z_i$ and
+//! challenges $\rho$ then $t$, $W = \sum_i \rho^i W_{2,i}$ commits to $\sum_i \rho^i l_i / (X -
+//! z_i)$, and $W' = \sum_i \rho^i Z_{T \setminus z_i}(t) W_{2,i}$ commits to the quotient of
+//! $L = \sum_i \rho^i Z_{T \setminus z_i}(t) l_i - Z_T(t) \sum_i \rho^i q_i$ by $X - t$. The
+//! verifier checks $e(L + t W', g_2) = e(W', g_2^x)$ with two pairings for the whole batch.
+//!
+//! The aggregate isn't constant size: each $z_i$ is drawn from a transcript that absorbed
+//! $W_{1,i}$, so the verifier needs every $W_{1,i}$ to recompute it. Only the $W_{2,i}$ collapse,
+//! so $k$ proofs take $k + 2$ points instead of $2k$.
+use ark_ec::{pairing::Pairing, AffineRepr, CurveGroup, VariableBaseMSM};
+use ark_ff::{Field, Zero};
+use ark_poly::univariate::DensePolynomial;
+use ark_serialize::Valid;
+use merlin::Transcript;
+
+use super::{M2NoPrecomp, Proof};
+use crate::{
+    gen_powers, get_challenge, get_field_size, lagrange::LagrangeInterpContext, transcribe_commits,
+    transcribe_generic, transcribe_header, transcribe_points_and_evals, vanishing_polynomial,
+    Commitment, Error, METHOD2_TRANSCRIPT_TAG,
+};
+
+/// One opening in an aggregate. `transcript` should be in the same state as the transcript given
+/// to `open`.
+pub struct AggregateItem<'a, E: Pairing, T: AsRef<[E::ScalarField]>> {
+    pub transcript: Transcript,
+    pub commits: &'a [Commitment<E>],
+    pub points: &'a [E::ScalarField],
+    pub evals: &'a [T],
+}
+
+/// Proofs of many openings folded into one by [`M2NoPrecomp::aggregate_proofs`]
+#[derive(Clone, Debug)]
+pub struct AggregateProof<E: Pairing> {
+    /// The `W1` of each proof, in order
+    pub w_1s: Vec<E::G1Affine>,
+    /// $\sum_i \rho^i W_{2,i}$
+    pub w: E::G1Affine,
+    /// $\sum_i \rho^i Z_{T \setminus z_i}(t) W_{2,i}$
+    pub w_prime: E::G1Affine,
+}
+
+impl<E: Pairing> M2NoPrecomp<E> {
+    /// Folds `proofs[i]`, the proof of `items[i]`, into one [`AggregateProof`]. The challenges
+    /// are drawn from `transcript` after absorbing every item's commitments, points, evals, and
+    /// `W1`, so proofs can't be moved between items. The proofs aren't checked here, a batch
+    /// with an invalid one fails [`M2NoPrecomp::verify_aggregate`].
+    pub fn aggregate_proofs<T: AsRef<[E::ScalarField]>>(
+        &self,
+        transcript: &mut Transcript,
+        items: &[AggregateItem<E, T>],
+        proofs: &[Proof<E>],
+    ) -> Result<AggregateProof<E>, Error> {
+        if proofs.len() != items.len() {
+            return Err(Error::PointSetCountMismatch {
+                n: proofs.len(),
+                expected: items.len(),
+            });
+        }
+        let w_1s = proofs.iter().map(|p| p.0).collect::<Vec<_>>();
+        let terms = self.item_terms(items, &w_1s)?;
+        let zs = terms.iter().map(|(_, z)| *z).collect::<Vec<_>>();
+        let w_2s = proofs.iter().map(|p| p.1).collect::<Vec<_>>();
+
+        let rho = transcribe_items(transcript, items, &w_1s)?;
+        let rhos = gen_powers(rho, items.len());
+        let w = E::G1::msm_unchecked(&w_2s, &rhos).into_affine();
+        let t = transcribe_w(transcript, &w)?;
+        let scalars = rhos
+            .iter()
+            .zip(others_vanishing_at(&zs, t))
+            .map(|(r, z)| *r * z)
+            .collect::<Vec<_>>();
+        let w_prime = E::G1::msm_unchecked(&w_2s, &scalars).into_affine();
+        Ok(AggregateProof { w_1s, w, w_prime })
+    }
+
+    /// Verifies an [`AggregateProof`] of `items`, with `transcript` in the same state as the one
+    /// given to [`M2NoPrecomp::aggregate_proofs`]
+    pub fn verify_aggregate<T: AsRef<[E::ScalarField]>>(
+        &self,
+        transcript: &mut Transcript,
+        items: &[AggregateItem<E, T>],
+        proof: &AggregateProof<E>,
+    ) -> Result<bool, Error> {
+        if proof.w_1s.len() != items.len() {
+            return Err(Error::PointSetCountMismatch {
+                n: proof.w_1s.len(),
+                expected: items.len(),
+            });
+        }
+        for item in items {
+            Commitment::<E>::batch_check(item.commits.iter()).map_err(|_| Error::InvalidPoint)?;
+        }
+        let mut points = proof.w_1s.clone();
+        points.extend([proof.w, proof.w_prime]);
+        E::G1Affine::batch_check(points.iter()).map_err(|_| Error::InvalidPoint)?;
+        let terms = self.item_terms(items, &proof.w_1s)?;
+        let (fs, zs): (Vec<_>, Vec<_>) = terms.into_iter().unzip();
+
+        let rho = transcribe_items(transcript, items, &proof.w_1s)?;
+        let rhos = gen_powers(rho, items.len());
+        let t = transcribe_w(transcript, &proof.w)?;
+        let mut scalars = rhos
+            .iter()
+            .zip(others_vanishing_at(&zs, t))
+            .map(|(r, z)| *r * z)
+            .collect::<Vec<_>>();
+        let z_t = zs.iter().map(|z| t - z).product::<E::ScalarField>();
+
+        // L + t W' = sum_i rho^i Z_{T \ z_i}(t) f_i - Z_T(t) W + t W'
+        let mut bases = E::G1::normalize_batch(&fs);
+        bases.extend([proof.w, proof.w_prime]);
+        scalars.extend([-z_t, t]);
+        let lhs = E::G1::msm_unchecked(&bases, &scalars);
+        Ok(E::multi_pairing([lhs, -proof.w_prime.into_group()], [self.g2, self.g2x]).is_zero())
+    }
+
+    /// The `f` and `z` of each item's pairing check, as in `verify`
+    fn item_terms<T: AsRef<[E::ScalarField]>>(
+        &self,
+        items: &[AggregateItem<E, T>],
+        w_1s: &[E::G1Affine],
+    ) -> Result<Vec<(E::G1, E::ScalarField)>, Error> {
+        if items.is_empty() {
+            return Err(Error::NoPolynomialsGiven);
+        }
+        items
+            .iter()
+            .zip(w_1s)
+            .map(|(item, w_1)| {
+                let vp: DensePolynomial<E::ScalarField> = vanishing_polynomial(item.points);
+                let lag_ctx = LagrangeInterpContext::new_from_points(item.points)?;
+                self.verify_terms(
+                    &mut item.transcript.clone(),
+                    item.commits,
+                    item.points,
+                    item.evals,
+                    w_1,
+                    &lag_ctx,
+                    &vp,
+                )
+            })
+            .collect()
+    }
+}
+
+/// Absorbs every item and its `W1`, and draws `rho`
+fn transcribe_items<E: Pairing, T: AsRef<[E::ScalarField]>>(
+    transcript: &mut Transcript,
+    items: &[AggregateItem<E, T>],
+    w_1s: &[E::G1Affine],
+) -> Result<E::ScalarField, Error> {
+    let field_size_bytes = get_field_size::<E::ScalarField>();
+    transcribe_header(transcript, METHOD2_TRANSCRIPT_TAG);
+    transcript.append_u64(b"aggregate", items.len() as u64);
+    for (item, w_1) in items.iter().zip(w_1s) {
+        transcribe_commits(transcript, item.commits)?;
+        transcribe_points_and_evals(transcript, item.points, item.evals, field_size_bytes)?;
+        transcribe_generic(transcript, b"open W1", w_1)?;
+    }
+    Ok(get_challenge(
+        transcript,
+        b"aggregate rho",
+        field_size_bytes,
+    ))
+}
+
+/// Absorbs `W` and draws `t`
+fn transcribe_w<E: Pairing>(
+    transcript: &mut Transcript,
+    w: &E::G1Affine,
+) -> Result<E::ScalarField, Error> {
+    transcribe_generic(transcript, b"aggregate W", w)?;
+    Ok(get_challenge(
+        transcript,
+        b"aggregate t",
+        get_field_size::<E::ScalarField>(),
+    ))
+}
+
+/// $Z_{T \setminus z_i}(t) = \prod_{j \neq i} (t - z_j)$ for each `i`, from prefix and suffix
+/// products so no inverses are needed
+fn others_vanishing_at<F: Field>(zs: &[F], t: F) -> Vec<F> {
+    let mut out = vec![F::one(); zs.len()];
+    let mut acc = F::one();
+    for (o, z) in out.iter_mut().zip(zs) {
+        *o = acc;
+        acc *= t - z;
+    }
+    acc = F::one();
+    for (o, z) in out.iter_mut().zip(zs).rev() {
+        *o *= acc;
+        acc *= t - z;
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{others_vanishing_at, AggregateItem};
+    use crate::{
+        method2::M2NoPrecomp,
+        test_rng,
+        traits::{Committer, PolyMultiProofNoPrecomp},
+        Error,
+    };
+    use ark_bls12_381::{Bls12_381, Fr};
+    use ark_poly::{univariate::DensePolynomial, DenseUVPolynomial};
+    use ark_std::UniformRand;
+    use merlin::Transcript;
+
+    #[test]
+    fn test_others_vanishing_at() {
+        let zs = (0..5)
+            .map(|_| Fr::rand(&mut test_rng()))
+            .collect::<Vec<_>>();
+        let t = Fr::rand(&mut test_rng());
+        for (i, o) in others_vanishing_at(&zs, t).into_iter().enumerate() {
+            let expected = zs
+                .iter()
+                .enumerate()
+                .filter(|(j, _)| *j != i)
+                .map(|(_, z)| t - z)
+                .product::<Fr>();
+            assert_eq!(expected, o);
+        }
+    }
+
+    #[test]
+    fn test_aggregate_proofs() {
+        let s = M2NoPrecomp::<Bls12_381>::new(32, None, &mut test_rng()).unwrap();
+        let n = 10;
+        let points = (0..n)
+            .map(|i| {
+                (0..1 + i % 4)
+                    .map(|_| Fr::rand(&mut test_rng()))
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        let polys = (0..n)
+            .map(|i| {
+                (0..1 + i % 3)
+                    .map(|_| DensePolynomial::<Fr>::rand(20, &mut test_rng()).coeffs)
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        let evals = polys
+            .iter()
+            .zip(&points)
+            .map(|(ps, pts)| {
+                ps.iter()
+                    .map(|p| crate::multi_evaluate(p, pts))
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        let commits = polys
+            .iter()
+            .map(|ps| s.commit_many(ps).unwrap())
+            .collect::<Vec<_>>();
+        let proofs = (0..n)
+            .map(|i| {
+                s.open(
+                    &mut Transcript::new(b"testing"),
+                    &evals[i],
+                    &polys[i],
+                    &points[i],
+                )
+                .unwrap()
+            })
+            .collect::<Vec<_>>();
+        let items = || {
+            (0..n)
+                .map(|i| AggregateItem {
+                    transcript: Transcript::new(b"testing"),
+                    commits: &commits[i],
+                    points: &points[i],
+                    evals: &evals[i],
+                })
+                .collect::<Vec<_>>()
+        };
+
+        let agg = s
+            .aggregate_proofs(&mut Transcript::new(b"aggregate"), &items(), &proofs)
+            .unwrap();
+        assert_eq!(n, agg.w_1s.len());
+        assert_eq!(
+            Ok(true),
+            s.verify_aggregate(&mut Transcript::new(b"aggregate"), &items(), &agg)
+        );
+
+        // Swapping two proofs between items breaks the aggregate
+        let mut swapped = proofs.clone();
+        swapped.swap(3, 7);
+        let agg = s
+            .aggregate_proofs(&mut Transcript::new(b"aggregate"), &items(), &swapped)
+            .unwrap();
+        assert_eq!(
+            Ok(false),
+            s.verify_aggregate(&mut Transcript::new(b"aggregate"), &items(), &agg)
+        );
+
+        // As does a proof of the wrong evals
+        let mut wrong = proofs.clone();
+        let mut wrong_evals = evals[5].clone();
+        wrong_evals[0][0] += Fr::from(1u64);
+        wrong[5] = s
+            .open(
+                &mut Transcript::new(b"testing"),
+                &wrong_evals,
+                &polys[5],
+                &points[5],
+            )
+            .unwrap();
+        let agg = s
+            .aggregate_proofs(&mut Transcript::new(b"aggregate"), &items(), &wrong)
+            .unwrap();
+        assert_eq!(
+            Ok(false),
+            s.verify_aggregate(&mut Transcript::new(b"aggregate"), &items(), &agg)
+        );
+
+        assert_eq!(
+            Err(Error::PointSetCountMismatch { n: 9, expected: 10 }),
+            s.aggregate_proofs(&mut Transcript::new(b"aggregate"), &items(), &proofs[..9])
+                .map(|_| ())
+        );
+    }
+}
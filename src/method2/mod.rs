@@ -3,10 +3,14 @@ use crate::{
     traits::{Committer, PolyMultiProofNoPrecomp},
 };
 use ark_poly::{univariate::DensePolynomial, DenseUVPolynomial, Polynomial};
-use ark_std::{One, UniformRand};
+use ark_serialize::{
+    CanonicalDeserialize, CanonicalSerialize, Compress, Read, SerializationError, Valid, Validate,
+    Write,
+};
+use ark_std::{One, UniformRand, Zero};
 use merlin::Transcript;
 use std::{
-    ops::{Div, Mul, Sub},
+    ops::{Mul, Sub},
     usize,
 };
 
@@ -14,14 +18,17 @@ use ark_ec::{pairing::Pairing, AffineRepr, CurveGroup};
 use ark_std::rand::RngCore;
 
 use crate::{
-    get_challenge, get_field_size, transcribe_generic, transcribe_points_and_evals,
-    Commitment,
+    check_eval_shape, check_polys_fit, evals_all_zero, get_challenge, get_field_size,
+    transcribe_generic, transcribe_header, transcribe_points_and_evals, Commitment,
+    METHOD2_TRANSCRIPT_TAG,
 };
 
 use crate::{
-    gen_curve_powers, gen_powers, linear_combination, poly_div_q_r, vanishing_polynomial, Error,
+    gen_curve_powers, gen_powers, gen_powers_small, linear_combination, poly_div_q_r,
+    vanishing_polynomial, Error,
 };
 
+pub mod aggregate;
 pub mod precompute;
 
 #[derive(Clone, Debug)]
@@ -32,20 +39,174 @@ pub struct M2NoPrecomp<E: Pairing> {
 }
 
 impl<E: Pairing> M2NoPrecomp<E> {
+    /// Given a commitment `old` to $f$, returns a commitment to $f + \delta x^{index}$ without
+    /// recommitting to the whole polynomial
+    pub fn update_commitment(
+        &self,
+        old: &Commitment<E>,
+        index: usize,
+        delta: E::ScalarField,
+    ) -> Result<Commitment<E>, Error> {
+        crate::update_commitment(&self.powers_of_g1, old, index, delta)
+    }
+
+    /// Commits to the polynomial whose coefficients are the concatenation of `chunks`. Only one
+    /// chunk is held in memory at a time.
+    pub fn commit_streaming(
+        &self,
+        chunks: impl Iterator<Item = Vec<E::ScalarField>>,
+    ) -> Result<Commitment<E>, Error> {
+        let res = crate::curve_msm_streaming::<E::G1>(&self.powers_of_g1, chunks)?;
+        Ok(Commitment(res.into_affine()))
+    }
+
+    /// Commits to the polynomial whose coefficients are yielded by `coeffs`, such as while
+    /// deserializing them from disk, without collecting them first. Returns the first error
+    /// `coeffs` yields, if any.
+    pub fn commit_try_iter(
+        &self,
+        coeffs: impl Iterator<Item = Result<E::ScalarField, Error>>,
+    ) -> Result<Commitment<E>, Error> {
+        let res = crate::curve_msm_try_iter::<E::G1>(&self.powers_of_g1, coeffs)?;
+        Ok(Commitment(res.into_affine()))
+    }
+
+    /// Builds the setup from existing powers, such as from a trusted setup ceremony. Only the
+    /// first two g2 powers are used.
     pub fn new_from_powers(g1: &Vec<E::G1Affine>, g2: &Vec<E::G2Affine>) -> Result<Self, Error> {
+        crate::check_powers_len(g1.len(), g2.len())?;
         Ok(Self {
             powers_of_g1: g1.clone(),
-            g2: *g2.get(0).ok_or(Error::NotEnoughG2Powers)?,
-            g2x: *g2.get(1).ok_or(Error::NotEnoughG2Powers)?,
+            g2: g2[0],
+            g2x: g2[1],
         })
     }
+
+    /// The g1 generator raised to the powers of the secret
+    pub fn powers_of_g1(&self) -> &[E::G1Affine] {
+        &self.powers_of_g1
+    }
+
+    /// The g2 generator
+    pub fn g2(&self) -> E::G2Affine {
+        self.g2
+    }
+
+    /// The g2 generator raised to the secret $x$
+    pub fn g2x(&self) -> E::G2Affine {
+        self.g2x
+    }
+
+    /// The most points that can be opened at. Verifying only needs $g_2$ and $g_2^x$, so unlike
+    /// method 1 there is no limit from the setup.
+    pub fn max_points(&self) -> usize {
+        usize::MAX
+    }
 }
 
 #[derive(Clone, Debug)]
 pub struct Proof<E: Pairing>(E::G1Affine, E::G1Affine);
 
+impl<E: Pairing> Proof<E> {
+    /// The number of bytes a proof serializes to, without needing a proof at hand
+    pub fn size_in_bytes(compress: Compress) -> usize {
+        2 * E::G1Affine::generator().serialized_size(compress)
+    }
+
+    /// Whether the proofs are the same group elements, regardless of how the points are represented
+    pub fn eq_point(&self, other: &Self) -> bool {
+        self.0.into_group() == other.0.into_group() && self.1.into_group() == other.1.into_group()
+    }
+
+    /// $W_1$, the commitment to the quotient of the combined polynomial by the vanishing
+    /// polynomial
+    pub fn w1(&self) -> E::G1Affine {
+        self.0
+    }
+
+    /// $W_2$, the commitment to the quotient of the linearized polynomial by $X - z$
+    pub fn w2(&self) -> E::G1Affine {
+        self.1
+    }
+
+    /// The compressed serialization, 96 bytes for BLS12-381
+    pub fn to_compressed_bytes(&self) -> Vec<u8> {
+        crate::to_compressed_bytes(self)
+    }
+
+    /// Reads a proof from exactly its compressed serialization. Errors with
+    /// [`Error::InvalidPoint`] if its points aren't on the curve or in the prime order subgroup,
+    /// and [`Error::MalformedProof`] for anything else.
+    pub fn from_compressed_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        crate::from_exact_compressed(bytes, Error::MalformedProof)
+    }
+}
+
+impl<E: Pairing> std::fmt::Display for Proof<E> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        crate::fmt_compressed_hex(f, "Proof", self)
+    }
+}
+
+impl<E: Pairing> CanonicalSerialize for Proof<E> {
+    fn serialize_with_mode<W: Write>(
+        &self,
+        mut writer: W,
+        compress: Compress,
+    ) -> Result<(), SerializationError> {
+        self.0.serialize_with_mode(&mut writer, compress)?;
+        self.1.serialize_with_mode(&mut writer, compress)
+    }
+
+    fn serialized_size(&self, compress: Compress) -> usize {
+        self.0.serialized_size(compress) + self.1.serialized_size(compress)
+    }
+}
+
+impl<E: Pairing> Valid for Proof<E> {
+    fn check(&self) -> Result<(), SerializationError> {
+        self.0.check()?;
+        self.1.check()
+    }
+}
+
+impl<E: Pairing> CanonicalDeserialize for Proof<E> {
+    fn deserialize_with_mode<R: Read>(
+        mut reader: R,
+        compress: Compress,
+        validate: Validate,
+    ) -> Result<Self, SerializationError> {
+        let w_1 = E::G1Affine::deserialize_with_mode(&mut reader, compress, validate)?;
+        let w_2 = E::G1Affine::deserialize_with_mode(&mut reader, compress, validate)?;
+        Ok(Proof(w_1, w_2))
+    }
+}
+
+/// The prover's state between the two rounds of [`M2NoPrecomp::open_round1`] and
+/// [`M2NoPrecomp::open_round2`]. Besides `W1`, which is sent to the verifier, it keeps the
+/// combined polynomial $f = \sum_i \gamma^i f_i$, its quotient $h$ and remainder by the vanishing
+/// polynomial, and the vanishing polynomial itself. None of these but `W1` should be revealed.
+#[derive(Clone, Debug)]
+pub struct OpenRound1<E: Pairing> {
+    w_1: E::G1Affine,
+    gamma_fis: DensePolynomial<E::ScalarField>,
+    h: DensePolynomial<E::ScalarField>,
+    gamma_ris_over_zs: DensePolynomial<E::ScalarField>,
+    vp: DensePolynomial<E::ScalarField>,
+}
+
+impl<E: Pairing> OpenRound1<E> {
+    /// The commitment to the quotient $h$, for the verifier
+    pub fn w1(&self) -> E::G1Affine {
+        self.w_1
+    }
+}
+
 impl<E: Pairing> M2NoPrecomp<E> {
-    fn open_with_vanishing_poly(
+    /// Same as [`PolyMultiProofNoPrecomp::open`], but takes the vanishing polynomial of `points`,
+    /// so it can be computed once, or in closed form with
+    /// [`vanishing_polynomial_for_domain`](crate::vanishing_polynomial_for_domain)
+    pub fn open_with_vanishing_poly(
         &self,
         transcript: &mut Transcript,
         evals: &[impl AsRef<[E::ScalarField]>],
@@ -53,36 +214,193 @@ impl<E: Pairing> M2NoPrecomp<E> {
         points: &[E::ScalarField],
         vp: &DensePolynomial<E::ScalarField>,
     ) -> Result<Proof<E>, Error> {
+        self.open_with_intermediates(transcript, evals, polys, points, vp)
+            .map(|(proof, _, _)| proof)
+    }
+
+    /// Opens, additionally returning the polynomials `h` and `l_quotient` that `W1` and `W2`
+    /// commit to
+    fn open_with_intermediates(
+        &self,
+        transcript: &mut Transcript,
+        evals: &[impl AsRef<[E::ScalarField]>],
+        polys: &[impl AsRef<[E::ScalarField]>],
+        points: &[E::ScalarField],
+        vp: &DensePolynomial<E::ScalarField>,
+    ) -> Result<
+        (
+            Proof<E>,
+            DensePolynomial<E::ScalarField>,
+            DensePolynomial<E::ScalarField>,
+        ),
+        Error,
+    > {
+        check_eval_shape(evals, polys.len(), points.len())?;
+        check_polys_fit(polys, self.powers_of_g1.len())?;
         let field_size_bytes = get_field_size::<E::ScalarField>();
+        transcribe_header(transcript, METHOD2_TRANSCRIPT_TAG);
         transcribe_points_and_evals(transcript, points, evals, field_size_bytes)?;
 
         let gamma = get_challenge(transcript, b"open gamma", field_size_bytes);
+        let round1 = self.open_round1(polys, vp, gamma)?;
+        let chal_z = z_challenge::<E>(transcript, &round1.w_1)?;
+        let (proof, l_quotient) = self.round2(&round1, chal_z)?;
+        Ok((proof, round1.h, l_quotient))
+    }
 
+    /// The first round of the interactive protocol: given the verifier's challenge `gamma`,
+    /// commits to the quotient of $\sum_i \gamma^i f_i$ by the vanishing polynomial `vp` of the
+    /// points as `W1`, which goes to the verifier before it picks `z` for
+    /// [`M2NoPrecomp::open_round2`].
+    ///
+    /// `open` is this composed with round 2, with `gamma` and `z` drawn from the transcript, so
+    /// these are only for running the protocol interactively or with a custom Fiat-Shamir. The
+    /// challenges must be random and unknown to the prover until it has sent what comes before
+    /// them, otherwise the proof is unsound.
+    pub fn open_round1(
+        &self,
+        polys: &[impl AsRef<[E::ScalarField]>],
+        vp: &DensePolynomial<E::ScalarField>,
+        gamma: E::ScalarField,
+    ) -> Result<OpenRound1<E>, Error> {
         let gammas = gen_powers::<E::ScalarField>(gamma, self.powers_of_g1.len());
-        let gamma_fis = linear_combination::<E::ScalarField>(polys, &gammas)
-            .ok_or(Error::NoPolynomialsGiven)?;
-        let gamma_fis_poly = DensePolynomial::from_coefficients_vec(gamma_fis);
+        let gamma_fis = linear_combination::<E::ScalarField>(polys, &gammas)?;
+        self.round1_combined(DensePolynomial::from_coefficients_vec(gamma_fis), vp)
+    }
 
-        let (h, gamma_ris_over_zs) = poly_div_q_r((&gamma_fis_poly).into(), (vp).into())?;
+    /// Round 1 with $\sum_i \gamma^i f_i$ already computed, so openings at several point sets
+    /// can share it
+    fn round1_combined(
+        &self,
+        gamma_fis: DensePolynomial<E::ScalarField>,
+        vp: &DensePolynomial<E::ScalarField>,
+    ) -> Result<OpenRound1<E>, Error> {
+        let (h, gamma_ris_over_zs) = poly_div_q_r((&gamma_fis).into(), (vp).into())?;
 
         let w_1 = crate::curve_msm::<E::G1>(&self.powers_of_g1, &h)?.into_affine();
+        Ok(OpenRound1 {
+            w_1,
+            gamma_fis,
+            h: DensePolynomial::from_coefficients_vec(h),
+            gamma_ris_over_zs: DensePolynomial::from_coefficients_vec(gamma_ris_over_zs),
+            vp: vp.clone(),
+        })
+    }
 
-        transcribe_generic(transcript, b"open W1", &w_1)?;
-        let chal_z = get_challenge(transcript, b"open z", field_size_bytes);
+    /// The second round of the interactive protocol: given the state from
+    /// [`M2NoPrecomp::open_round1`] and the verifier's challenge `z`, finishes the proof with
+    /// `W2`
+    pub fn open_round2(
+        &self,
+        round1: &OpenRound1<E>,
+        z: E::ScalarField,
+    ) -> Result<Proof<E>, Error> {
+        self.round2(round1, z).map(|(proof, _)| proof)
+    }
 
-        let gamma_ri_z = DensePolynomial::from_coefficients_vec(gamma_ris_over_zs)
-            .mul(vp)
-            .evaluate(&chal_z);
+    /// Round 2, additionally returning the polynomial `l_quotient` that `W2` commits to
+    fn round2(
+        &self,
+        round1: &OpenRound1<E>,
+        chal_z: E::ScalarField,
+    ) -> Result<(Proof<E>, DensePolynomial<E::ScalarField>), Error> {
+        let vp = &round1.vp;
+        let gamma_ri_z = round1.gamma_ris_over_zs.clone().mul(vp).evaluate(&chal_z);
 
-        let f_z = gamma_fis_poly.sub(&DensePolynomial::from_coefficients_vec(vec![gamma_ri_z])); // XXX
-        let l = f_z.sub(&DensePolynomial::from_coefficients_vec(h).mul(vp.evaluate(&chal_z)));
+        // XXX
+        let f_z = &round1.gamma_fis - &DensePolynomial::from_coefficients_vec(vec![gamma_ri_z]);
+        let l = f_z.sub(&(&round1.h * vp.evaluate(&chal_z)));
 
         let x_minus_z =
             DensePolynomial::from_coefficients_vec(vec![-chal_z, E::ScalarField::one()]);
-        let l_quotient = l.div(&x_minus_z);
+        // l vanishes at z by construction, so the division must be exact
+        let (l_quotient, l_rem) = poly_div_q_r((&l).into(), (&x_minus_z).into())?;
+        debug_assert!(l_rem.is_empty(), "l(z) should be zero");
+        let l_quotient = DensePolynomial::from_coefficients_vec(l_quotient);
 
         let w_2 = crate::curve_msm::<E::G1>(&self.powers_of_g1, &l_quotient)?.into_affine();
-        Ok(Proof(w_1, w_2))
+        Ok((Proof(round1.w_1, w_2), l_quotient))
+    }
+
+    /// Same as [`PolyMultiProofNoPrecomp::open`], but also returns the polynomials `h` and
+    /// `l_quotient` so tests can inspect the prover's intermediate values.
+    #[cfg(feature = "debug")]
+    pub fn open_debug(
+        &self,
+        transcript: &mut Transcript,
+        evals: &[impl AsRef<[E::ScalarField]>],
+        polys: &[impl AsRef<[E::ScalarField]>],
+        points: &[E::ScalarField],
+    ) -> Result<
+        (
+            Proof<E>,
+            DensePolynomial<E::ScalarField>,
+            DensePolynomial<E::ScalarField>,
+        ),
+        Error,
+    > {
+        let vp = vanishing_polynomial(points);
+        self.open_with_intermediates(transcript, evals, polys, points, &vp)
+    }
+
+    /// Returns $e(f, g_2) / e(W_2, g_2^{x - z})$ from the verifier's pairing check, which is one
+    /// exactly when the proof is valid. This exposes verifier internals, and is only meant for
+    /// diagnosing why a proof fails to verify.
+    #[cfg(feature = "debug")]
+    pub fn verify_residual(
+        &self,
+        transcript: &mut Transcript,
+        commits: &[Commitment<E>],
+        points: &[E::ScalarField],
+        evals: &[impl AsRef<[E::ScalarField]>],
+        proof: &Proof<E>,
+    ) -> Result<E::TargetField, Error> {
+        let vp = vanishing_polynomial(points);
+        let lag_ctx = LagrangeInterpContext::new_from_points(points)?;
+        let (f, chal_z) =
+            self.verify_terms(transcript, commits, points, evals, &proof.0, &lag_ctx, &vp)?;
+        let x_minus_z = self.g2x.into_group() - self.g2.into_group().mul(&chal_z);
+        Ok(E::multi_pairing(
+            [f, -proof.1.into_group()],
+            [self.g2.into_group(), x_minus_z],
+        )
+        .0)
+    }
+
+    /// Same as [`PolyMultiProofNoPrecomp::verify`], but takes the vanishing polynomial of
+    /// `points`, which must be the one the prover opened with.
+    ///
+    /// `vp` is only checked to have one degree per point, erroring with
+    /// [`Error::VanishingPolyIncorrectDegree`] otherwise, not to vanish on `points`. Verifying
+    /// against any other polynomial is unsound, so `vp` must be computed by the verifier from the
+    /// points, e.g. with [`vanishing_polynomial`](crate::vanishing_polynomial), and never taken
+    /// from the prover.
+    pub fn verify_with_vanishing_poly(
+        &self,
+        transcript: &mut Transcript,
+        commits: impl AsRef<[Commitment<E>]>,
+        points: &[E::ScalarField],
+        evals: &[impl AsRef<[E::ScalarField]>],
+        proof: &Proof<E>,
+        vp: &DensePolynomial<E::ScalarField>,
+    ) -> Result<bool, Error> {
+        if vp.degree() != points.len() {
+            return Err(Error::VanishingPolyIncorrectDegree {
+                degree: vp.degree(),
+                expected: points.len(),
+            });
+        }
+        crate::traits::check_points(commits.as_ref(), proof)?;
+        let lag_ctx = LagrangeInterpContext::new_from_points(points)?;
+        self.verify_with_lag_ctx_vanishing_poly(
+            transcript,
+            commits.as_ref(),
+            points,
+            evals,
+            proof,
+            &lag_ctx,
+            vp,
+        )
     }
 
     fn verify_with_lag_ctx_vanishing_poly(
@@ -95,38 +413,244 @@ impl<E: Pairing> M2NoPrecomp<E> {
         lag_ctx: &LagrangeInterpContext<E::ScalarField>,
         vp: &DensePolynomial<E::ScalarField>,
     ) -> Result<bool, Error> {
+        let (f, chal_z) =
+            self.verify_terms(transcript, commits, points, evals, &proof.0, lag_ctx, vp)?;
+        let x_minus_z = self.g2x.into_group() - self.g2.into_group().mul(&chal_z);
+        // If the challenge is the secret x, the right hand side is trivially the identity
+        if x_minus_z.is_zero() {
+            return Err(Error::PointIsSecret);
+        }
+        Ok(E::pairing(f, self.g2) == E::pairing(proof.1, x_minus_z))
+    }
+
+    /// Computes the G1 point `f` and challenge `z` such that the proof is valid iff
+    /// $e(f, g_2) = e(W_2, g_2^{x - z})$
+    fn verify_terms(
+        &self,
+        transcript: &mut Transcript,
+        commits: &[Commitment<E>],
+        points: &[E::ScalarField],
+        evals: &[impl AsRef<[E::ScalarField]>],
+        w_1: &E::G1Affine,
+        lag_ctx: &LagrangeInterpContext<E::ScalarField>,
+        vp: &DensePolynomial<E::ScalarField>,
+    ) -> Result<(E::G1, E::ScalarField), Error> {
+        check_eval_shape(evals, commits.len(), points.len())?;
         let field_size_bytes = get_field_size::<E::ScalarField>();
+        transcribe_header(transcript, METHOD2_TRANSCRIPT_TAG);
         transcribe_points_and_evals(transcript, points, evals, field_size_bytes)?;
 
         let gamma = get_challenge(transcript, b"open gamma", field_size_bytes);
-        transcribe_generic(transcript, b"open W1", &proof.0)?;
-        let chal_z = get_challenge(transcript, b"open z", field_size_bytes);
+        let chal_z = z_challenge::<E>(transcript, w_1)?;
+
+        let gammas = gen_powers_small(gamma, evals.len());
+        let gamma_cm_pt = crate::gamma_commit_combo(commits, gammas.as_ref())?;
+        let f = self.f_term(gamma_cm_pt, evals, &gammas, w_1, chal_z, lag_ctx, vp)?;
+        Ok((f, chal_z))
+    }
 
+    /// The `f` of [`M2NoPrecomp::verify_terms`] once the challenges are known, with the
+    /// commitments already combined into `gamma_cm_pt`
+    #[allow(clippy::too_many_arguments)]
+    fn f_term(
+        &self,
+        gamma_cm_pt: E::G1,
+        evals: &[impl AsRef<[E::ScalarField]>],
+        gammas: &[E::ScalarField],
+        w_1: &E::G1Affine,
+        chal_z: E::ScalarField,
+        lag_ctx: &LagrangeInterpContext<E::ScalarField>,
+        vp: &DensePolynomial<E::ScalarField>,
+    ) -> Result<E::G1, Error> {
         let zeros_z = vp.evaluate(&chal_z);
 
-        // Get the r_i polynomials with lagrange interp. These could be precomputed.
-        let gammas = gen_powers(gamma, evals.len());
         // Get the gamma^i r_i polynomials with lagrange interp. This does both the lagrange interp
         // and the gamma mul in one step so we can just lagrange interp once.
-        let gamma_ris = lag_ctx.lagrange_interp_linear_combo(evals, &gammas)?.coeffs;
-        let gamma_ris_z = DensePolynomial::from_coefficients_vec(gamma_ris).evaluate(&chal_z);
-        let gamma_ris_z_pt = self.powers_of_g1[0].mul(gamma_ris_z);
+        // If all the evals are zero, so are the r_i, and we can skip the interp.
+        let gamma_ris_z_pt = if evals_all_zero(evals) {
+            E::G1::zero()
+        } else {
+            let gamma_ris = lag_ctx.lagrange_interp_linear_combo(evals, gammas)?.coeffs;
+            let gamma_ris_z = DensePolynomial::from_coefficients_vec(gamma_ris).evaluate(&chal_z);
+            self.powers_of_g1[0].mul(gamma_ris_z)
+        };
+        Ok(gamma_cm_pt - gamma_ris_z_pt - w_1.mul(zeros_z))
+    }
+
+    /// Opens `polys` at each of `point_sets`, with `evals_per_set[i]` the evals at
+    /// `point_sets[i]`, returning one proof per set. Every set and its evals go in `transcript`
+    /// before a single gamma is drawn, so $\sum_i \gamma^i f_i$ is computed once and only the
+    /// division and the two msms are done per set. Each set then draws its own `z` on a fork of
+    /// `transcript`, a clone with the set's index appended as `open many set`, so the proofs are
+    /// deterministic per set. They share gamma, so they must be checked together with
+    /// [`M2NoPrecomp::verify_many`].
+    pub fn open_many<T: AsRef<[E::ScalarField]>, S: AsRef<[T]>>(
+        &self,
+        transcript: &mut Transcript,
+        evals_per_set: &[S],
+        polys: &[impl AsRef<[E::ScalarField]>],
+        point_sets: &[&[E::ScalarField]],
+    ) -> Result<Vec<Proof<E>>, Error> {
+        check_set_count(evals_per_set.len(), point_sets.len())?;
+        for (evals, points) in evals_per_set.iter().zip(point_sets) {
+            check_eval_shape(evals.as_ref(), polys.len(), points.len())?;
+        }
+        check_polys_fit(polys, self.powers_of_g1.len())?;
+        let gamma = transcribe_many::<E, T, S>(transcript, evals_per_set, point_sets)?;
+        let gammas = gen_powers(gamma, polys.len());
+        let gamma_fis = DensePolynomial::from_coefficients_vec(linear_combination(polys, &gammas)?);
+        point_sets
+            .iter()
+            .enumerate()
+            .map(|(i, points)| {
+                let round1 =
+                    self.round1_combined(gamma_fis.clone(), &vanishing_polynomial(points))?;
+                let chal_z = z_challenge::<E>(&mut fork_for_set(transcript, i), &round1.w_1)?;
+                self.open_round2(&round1, chal_z)
+            })
+            .collect()
+    }
+
+    /// Verifies proofs from [`M2NoPrecomp::open_many`], returning true only if every one of them
+    /// is valid. `transcript` must be in the same state as the one given to `open_many`. The
+    /// commitments are combined with gamma once, and all the sets are checked with a random
+    /// linear combination in one multi-pairing.
+    pub fn verify_many<T: AsRef<[E::ScalarField]>, S: AsRef<[T]>>(
+        &self,
+        transcript: &mut Transcript,
+        commits: impl AsRef<[Commitment<E>]>,
+        evals_per_set: &[S],
+        point_sets: &[&[E::ScalarField]],
+        proofs: &[Proof<E>],
+        rng: &mut impl RngCore,
+    ) -> Result<bool, Error> {
+        let commits = commits.as_ref();
+        check_set_count(evals_per_set.len(), point_sets.len())?;
+        check_set_count(proofs.len(), point_sets.len())?;
+        for (evals, points) in evals_per_set.iter().zip(point_sets) {
+            check_eval_shape(evals.as_ref(), commits.len(), points.len())?;
+        }
+        Commitment::<E>::batch_check(commits.iter()).map_err(|_| Error::InvalidPoint)?;
+        Proof::<E>::batch_check(proofs.iter()).map_err(|_| Error::InvalidPoint)?;
+        let gamma = transcribe_many::<E, T, S>(transcript, evals_per_set, point_sets)?;
+        let gammas = gen_powers(gamma, commits.len());
+        let gamma_cm_pt = crate::gamma_commit_combo(commits, &gammas)?;
 
-        // Then do a single msm of the gammas and commitments
-        let cms = commits.iter().map(|i| i.0).collect::<Vec<_>>();
-        let gamma_cm_pt = crate::curve_msm::<E::G1>(&cms, gammas.as_ref())?;
+        // Each set checks e(f + z W_2, g2) = e(W_2, g2^x), as in verify_heterogeneous
+        let mut lhs = E::G1::zero();
+        let mut rhs = E::G1::zero();
+        for (i, ((evals, points), proof)) in
+            evals_per_set.iter().zip(point_sets).zip(proofs).enumerate()
+        {
+            let vp = vanishing_polynomial(points);
+            let lag_ctx = LagrangeInterpContext::new_from_points(points)?;
+            let chal_z = z_challenge::<E>(&mut fork_for_set(transcript, i), &proof.0)?;
+            let f = self.f_term(
+                gamma_cm_pt,
+                evals.as_ref(),
+                &gammas,
+                &proof.0,
+                chal_z,
+                &lag_ctx,
+                &vp,
+            )?;
+            let r = E::ScalarField::rand(rng);
+            lhs += (f + proof.1.mul(chal_z)).mul(r);
+            rhs += proof.1.mul(r);
+        }
+        Ok(E::multi_pairing([lhs, -rhs], [self.g2, self.g2x]).is_zero())
+    }
 
-        let f = gamma_cm_pt - gamma_ris_z_pt - proof.0.mul(zeros_z);
+    /// Verifies a batch of unrelated openings, each with their own commitments, points, and
+    /// evals, with a single multi-pairing. Returns the index of the first invalid item, or `None`
+    /// if all are valid. Batches smaller than [`DEFAULT_BATCH_THRESHOLD`] are verified one by one.
+    pub fn verify_heterogeneous<T: AsRef<[E::ScalarField]>>(
+        &self,
+        items: &[VerifyItem<E, T>],
+        rng: &mut impl RngCore,
+    ) -> Result<Option<usize>, Error> {
+        self.verify_heterogeneous_with_threshold(items, rng, DEFAULT_BATCH_THRESHOLD)
+    }
 
-        let x_minus_z = self.g2x.into_group() - self.g2.into_group().mul(&chal_z);
-        Ok(E::pairing(f, self.g2) == E::pairing(proof.1, x_minus_z))
+    /// Same as [`M2NoPrecomp::verify_heterogeneous`], but batches with fewer than
+    /// `batch_threshold` items are verified one by one with their own pairings
+    pub fn verify_heterogeneous_with_threshold<T: AsRef<[E::ScalarField]>>(
+        &self,
+        items: &[VerifyItem<E, T>],
+        rng: &mut impl RngCore,
+        batch_threshold: usize,
+    ) -> Result<Option<usize>, Error> {
+        if items.len() < batch_threshold {
+            return self.first_invalid(items);
+        }
+        // Each item checks e(f + z W_2, g2) = e(W_2, g2^x), so a random linear combination of
+        // the items only needs two pairings
+        let mut lhs = E::G1::zero();
+        let mut rhs = E::G1::zero();
+        for item in items {
+            let vp = vanishing_polynomial(item.points);
+            let lag_ctx = LagrangeInterpContext::new_from_points(item.points)?;
+            let (f, chal_z) = self.verify_terms(
+                &mut item.transcript.clone(),
+                item.commits,
+                item.points,
+                item.evals,
+                &item.proof.0,
+                &lag_ctx,
+                &vp,
+            )?;
+            let r = E::ScalarField::rand(rng);
+            lhs += (f + item.proof.1.mul(chal_z)).mul(r);
+            rhs += item.proof.1.mul(r);
+        }
+        if E::multi_pairing([lhs, -rhs], [self.g2, self.g2x]).is_zero() {
+            return Ok(None);
+        }
+        // The batch failed, so at least one item is invalid. Find it.
+        self.first_invalid(items)
     }
+
+    fn first_invalid<T: AsRef<[E::ScalarField]>>(
+        &self,
+        items: &[VerifyItem<E, T>],
+    ) -> Result<Option<usize>, Error> {
+        for (i, item) in items.iter().enumerate() {
+            if !self.verify(
+                &mut item.transcript.clone(),
+                item.commits,
+                item.points,
+                item.evals,
+                item.proof,
+            )? {
+                return Ok(Some(i));
+            }
+        }
+        Ok(None)
+    }
+}
+
+/// The smallest batch [`M2NoPrecomp::verify_heterogeneous`] combines into one multi-pairing.
+/// Batching costs two G1 scalar multiplications per item but saves two pairings per item, so it
+/// already pays off at two items in the `heterogeneous_threshold` bench. Only a single proof is
+/// verified on its own.
+pub const DEFAULT_BATCH_THRESHOLD: usize = 2;
+
+/// One opening in a call to [`M2NoPrecomp::verify_heterogeneous`]. `transcript` should be in the
+/// same state as the transcript given to `open`.
+pub struct VerifyItem<'a, E: Pairing, T: AsRef<[E::ScalarField]>> {
+    pub transcript: Transcript,
+    pub commits: &'a [Commitment<E>],
+    pub points: &'a [E::ScalarField],
+    pub evals: &'a [T],
+    pub proof: &'a Proof<E>,
 }
 
 impl<E: Pairing> Committer<E> for M2NoPrecomp<E> {
-    fn commit(&self, poly: impl AsRef<[E::ScalarField]>) -> Result<Commitment<E>, Error> {
-        let res = crate::curve_msm::<E::G1>(&self.powers_of_g1, poly.as_ref())?;
-        Ok(Commitment(res.into_affine()))
+    fn commit_projective(&self, poly: impl AsRef<[E::ScalarField]>) -> Result<E::G1, Error> {
+        crate::curve_msm::<E::G1>(&self.powers_of_g1, poly.as_ref())
+    }
+    fn max_coeffs(&self) -> usize {
+        self.powers_of_g1.len()
     }
 }
 
@@ -163,10 +687,10 @@ impl<E: Pairing> PolyMultiProofNoPrecomp<E> for M2NoPrecomp<E> {
         self.open_with_vanishing_poly(transcript, evals, polys, points, &vp)
     }
 
-    fn verify(
+    fn verify_unchecked(
         &self,
         transcript: &mut Transcript,
-        commits: &[Commitment<E>],
+        commits: impl AsRef<[Commitment<E>]>,
         points: &[E::ScalarField],
         evals: &[impl AsRef<[E::ScalarField]>],
         proof: &Proof<E>,
@@ -174,23 +698,87 @@ impl<E: Pairing> PolyMultiProofNoPrecomp<E> for M2NoPrecomp<E> {
         let vp = vanishing_polynomial(points);
         let lag_ctx = LagrangeInterpContext::new_from_points(points)?;
         self.verify_with_lag_ctx_vanishing_poly(
-            transcript, commits, points, evals, proof, &lag_ctx, &vp,
+            transcript,
+            commits.as_ref(),
+            points,
+            evals,
+            proof,
+            &lag_ctx,
+            &vp,
         )
     }
 }
 
+/// Absorbs every point set of [`M2NoPrecomp::open_many`] and its evals, and draws the shared
+/// gamma
+fn transcribe_many<E: Pairing, T: AsRef<[E::ScalarField]>, S: AsRef<[T]>>(
+    transcript: &mut Transcript,
+    evals_per_set: &[S],
+    point_sets: &[&[E::ScalarField]],
+) -> Result<E::ScalarField, Error> {
+    let field_size_bytes = get_field_size::<E::ScalarField>();
+    transcribe_header(transcript, METHOD2_TRANSCRIPT_TAG);
+    transcript.append_u64(b"open many sets", point_sets.len() as u64);
+    for (evals, points) in evals_per_set.iter().zip(point_sets) {
+        transcribe_points_and_evals(transcript, points, evals.as_ref(), field_size_bytes)?;
+    }
+    Ok(get_challenge(transcript, b"open gamma", field_size_bytes))
+}
+
+/// Absorbs `W1` and draws the evaluation challenge `z`
+fn z_challenge<E: Pairing>(
+    transcript: &mut Transcript,
+    w_1: &E::G1Affine,
+) -> Result<E::ScalarField, Error> {
+    transcribe_generic(transcript, b"open W1", w_1)?;
+    Ok(get_challenge(
+        transcript,
+        b"open z",
+        get_field_size::<E::ScalarField>(),
+    ))
+}
+
+/// The transcript set `index` of [`M2NoPrecomp::open_many`] draws its `z` from
+fn fork_for_set(transcript: &Transcript, index: usize) -> Transcript {
+    let mut fork = transcript.clone();
+    fork.append_u64(b"open many set", index as u64);
+    fork
+}
+
+fn check_set_count(n: usize, expected: usize) -> Result<(), Error> {
+    if n != expected {
+        return Err(Error::PointSetCountMismatch { n, expected });
+    }
+    if n == 0 {
+        return Err(Error::NoPointsGiven);
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
-    use super::M2NoPrecomp;
+    use super::{M2NoPrecomp, Proof, VerifyItem};
     use crate::{
         test_rng,
         traits::{Committer, PolyMultiProofNoPrecomp},
+        Error,
     };
     use ark_bls12_381::{Bls12_381, Fr};
     use ark_poly::{univariate::DensePolynomial, DenseUVPolynomial, Polynomial};
+    use ark_serialize::Compress;
     use ark_std::UniformRand;
     use merlin::Transcript;
 
+    #[test]
+    fn test_compressed_bytes_roundtrip() {
+        crate::test_helpers::check_compressed_bytes_roundtrip(
+            &M2NoPrecomp::<Bls12_381>::new(32, None, &mut test_rng()).unwrap(),
+            96,
+            Proof::to_compressed_bytes,
+            Proof::from_compressed_bytes,
+        );
+    }
+
     #[test]
     fn test_basic_open_works() {
         let s = M2NoPrecomp::<Bls12_381>::new(256, None, &mut test_rng()).unwrap();
@@ -220,4 +808,621 @@ mod tests {
             s.verify(&mut verify_transcript, &commits, &points, &evals, &open)
         );
     }
+
+    #[test]
+    fn test_bound_open_works() {
+        let s = M2NoPrecomp::<Bls12_381>::new(64, None, &mut test_rng()).unwrap();
+        let points = (0..4)
+            .map(|_| Fr::rand(&mut test_rng()))
+            .collect::<Vec<_>>();
+        let polys = (0..3)
+            .map(|_| DensePolynomial::<Fr>::rand(20, &mut test_rng()))
+            .collect::<Vec<_>>();
+        let evals: Vec<Vec<_>> = polys
+            .iter()
+            .map(|p| points.iter().map(|x| p.evaluate(x)).collect())
+            .collect();
+        let coeffs = polys.iter().map(|p| p.coeffs.clone()).collect::<Vec<_>>();
+        let commits = coeffs
+            .iter()
+            .map(|p| s.commit(p).expect("Commit failed"))
+            .collect::<Vec<_>>();
+        let open = s
+            .open_bound(
+                &mut Transcript::new(b"testing"),
+                &commits,
+                &evals,
+                &coeffs,
+                &points,
+            )
+            .expect("Open failed");
+        assert_eq!(
+            Ok(true),
+            s.verify_bound(
+                &mut Transcript::new(b"testing"),
+                &commits,
+                &points,
+                &evals,
+                &open
+            )
+        );
+        assert_eq!(
+            Ok(false),
+            s.verify(
+                &mut Transcript::new(b"testing"),
+                &commits,
+                &points,
+                &evals,
+                &open
+            )
+        );
+    }
+
+    #[test]
+    fn test_commit_lagrange() {
+        use crate::traits::EvalCommitter;
+        use ark_poly::{EvaluationDomain, GeneralEvaluationDomain};
+        let s = M2NoPrecomp::<Bls12_381>::new(64, None, &mut test_rng()).unwrap();
+        let domain = GeneralEvaluationDomain::<Fr>::new(32).unwrap();
+        let poly = DensePolynomial::<Fr>::rand(31, &mut test_rng());
+        let evals = domain.fft(&poly.coeffs);
+        assert_eq!(
+            s.commit(&poly.coeffs).unwrap().0,
+            s.commit_lagrange(&evals, &domain).unwrap().0
+        );
+        assert!(s.commit_lagrange(&evals[..31], &domain).is_err());
+    }
+
+    #[test]
+    fn test_domain_vanishing_poly() {
+        use ark_poly::{EvaluationDomain, GeneralEvaluationDomain};
+        let s = M2NoPrecomp::<Bls12_381>::new(64, None, &mut test_rng()).unwrap();
+        let domain = GeneralEvaluationDomain::<Fr>::new(16).unwrap();
+        let points = domain.elements().collect::<Vec<_>>();
+        let vp = crate::vanishing_polynomial_for_domain(&domain);
+        let polys = (0..3)
+            .map(|_| DensePolynomial::<Fr>::rand(40, &mut test_rng()))
+            .collect::<Vec<_>>();
+        let evals: Vec<Vec<_>> = polys
+            .iter()
+            .map(|p| points.iter().map(|x| p.evaluate(x)).collect())
+            .collect();
+        let coeffs = polys.iter().map(|p| p.coeffs.clone()).collect::<Vec<_>>();
+        let commits = s.commit_many(&coeffs).unwrap();
+        let open = s
+            .open_with_vanishing_poly(
+                &mut Transcript::new(b"testing"),
+                &evals,
+                &coeffs,
+                &points,
+                &vp,
+            )
+            .unwrap();
+        let general = s
+            .open(&mut Transcript::new(b"testing"), &evals, &coeffs, &points)
+            .unwrap();
+        assert!(open.eq_point(&general));
+        assert_eq!(
+            Ok(true),
+            s.verify_with_vanishing_poly(
+                &mut Transcript::new(b"testing"),
+                &commits,
+                &points,
+                &evals,
+                &open,
+                &vp
+            )
+        );
+        for (bad, degree) in [
+            (crate::vanishing_polynomial(&points[1..]), 15),
+            (DensePolynomial::from_coefficients_vec(vec![]), 0),
+        ] {
+            assert_eq!(
+                Err(Error::VanishingPolyIncorrectDegree {
+                    degree,
+                    expected: 16
+                }),
+                s.verify_with_vanishing_poly(
+                    &mut Transcript::new(b"testing"),
+                    &commits,
+                    &points,
+                    &evals,
+                    &open,
+                    &bad
+                )
+            );
+        }
+    }
+
+    #[test]
+    fn test_rounds_compose_to_open() {
+        use crate::{
+            get_challenge, get_field_size, transcribe_generic, transcribe_header,
+            transcribe_points_and_evals, METHOD2_TRANSCRIPT_TAG,
+        };
+        let s = M2NoPrecomp::<Bls12_381>::new(64, None, &mut test_rng()).unwrap();
+        let points = (0..8)
+            .map(|_| Fr::rand(&mut test_rng()))
+            .collect::<Vec<_>>();
+        let polys = (0..3)
+            .map(|_| DensePolynomial::<Fr>::rand(40, &mut test_rng()))
+            .collect::<Vec<_>>();
+        let evals: Vec<Vec<_>> = polys
+            .iter()
+            .map(|p| points.iter().map(|x| p.evaluate(x)).collect())
+            .collect();
+        let coeffs = polys.iter().map(|p| p.coeffs.clone()).collect::<Vec<_>>();
+        let open = s
+            .open(&mut Transcript::new(b"testing"), &evals, &coeffs, &points)
+            .unwrap();
+
+        let field_size = get_field_size::<Fr>();
+        let mut transcript = Transcript::new(b"testing");
+        transcribe_header(&mut transcript, METHOD2_TRANSCRIPT_TAG);
+        transcribe_points_and_evals(&mut transcript, &points, &evals, field_size).unwrap();
+        let gamma = get_challenge(&mut transcript, b"open gamma", field_size);
+        let vp = crate::vanishing_polynomial(&points);
+        let round1 = s.open_round1(&coeffs, &vp, gamma).unwrap();
+        assert_eq!(open.w1(), round1.w1());
+        transcribe_generic(&mut transcript, b"open W1", &round1.w1()).unwrap();
+        let z = get_challenge(&mut transcript, b"open z", field_size);
+        assert!(open.eq_point(&s.open_round2(&round1, z).unwrap()));
+    }
+
+    #[test]
+    fn test_reconstruct_from_getters() {
+        let s = M2NoPrecomp::<Bls12_381>::new(64, None, &mut test_rng()).unwrap();
+        let copy = M2NoPrecomp::new_from_powers(&s.powers_of_g1().to_vec(), &vec![s.g2(), s.g2x()])
+            .unwrap();
+        let poly = DensePolynomial::<Fr>::rand(40, &mut test_rng());
+        assert_eq!(
+            s.commit(&poly.coeffs).unwrap().0,
+            copy.commit(&poly.coeffs).unwrap().0
+        );
+
+        let g1s = s.powers_of_g1().to_vec();
+        assert_eq!(
+            Err(Error::EmptyPowers),
+            M2NoPrecomp::<Bls12_381>::new_from_powers(&vec![], &vec![s.g2(), s.g2x()]).map(|_| ())
+        );
+        assert_eq!(
+            Err(Error::EmptyPowers),
+            M2NoPrecomp::<Bls12_381>::new_from_powers(&g1s, &vec![]).map(|_| ())
+        );
+        assert_eq!(
+            Err(Error::NotEnoughG2Powers { needed: 2, have: 1 }),
+            M2NoPrecomp::<Bls12_381>::new_from_powers(&g1s, &vec![s.g2()]).map(|_| ())
+        );
+    }
+
+    #[test]
+    fn test_proof_size() {
+        assert_eq!(96, Proof::<Bls12_381>::size_in_bytes(Compress::Yes));
+        assert_eq!(192, Proof::<Bls12_381>::size_in_bytes(Compress::No));
+    }
+
+    #[test]
+    fn test_context_binding() {
+        let s = M2NoPrecomp::<Bls12_381>::new(64, None, &mut test_rng()).unwrap();
+        let points = (0..4)
+            .map(|_| Fr::rand(&mut test_rng()))
+            .collect::<Vec<_>>();
+        let polys = (0..3)
+            .map(|_| DensePolynomial::<Fr>::rand(20, &mut test_rng()))
+            .collect::<Vec<_>>();
+        let evals: Vec<Vec<_>> = polys
+            .iter()
+            .map(|p| points.iter().map(|x| p.evaluate(x)).collect())
+            .collect();
+        let coeffs = polys.iter().map(|p| p.coeffs.clone()).collect::<Vec<_>>();
+        let commits = coeffs
+            .iter()
+            .map(|p| s.commit(p).expect("Commit failed"))
+            .collect::<Vec<_>>();
+        let open = s
+            .open_with_context(
+                &mut Transcript::new(b"testing"),
+                b"block 100",
+                &evals,
+                &coeffs,
+                &points,
+            )
+            .expect("Open failed");
+        for (context, expected) in [(&b"block 100"[..], true), (&b"block 101"[..], false)] {
+            assert_eq!(
+                Ok(expected),
+                s.verify_with_context(
+                    &mut Transcript::new(b"testing"),
+                    context,
+                    &commits,
+                    &points,
+                    &evals,
+                    &open
+                )
+            );
+        }
+    }
+
+    #[test]
+    fn test_zero_evals_fast_path() {
+        let s = M2NoPrecomp::<Bls12_381>::new(64, None, &mut test_rng()).unwrap();
+        let points = (0..4)
+            .map(|_| Fr::rand(&mut test_rng()))
+            .collect::<Vec<_>>();
+        let vp = crate::vanishing_polynomial(&points);
+        // Multiples of the vanishing polynomial are zero on every point
+        let polys = (0..3)
+            .map(|_| &DensePolynomial::<Fr>::rand(20, &mut test_rng()) * &vp)
+            .collect::<Vec<_>>();
+        let evals = vec![vec![Fr::from(0u64); 4]; 3];
+        let coeffs = polys.iter().map(|p| p.coeffs.clone()).collect::<Vec<_>>();
+        let commits = coeffs
+            .iter()
+            .map(|p| s.commit(p).expect("Commit failed"))
+            .collect::<Vec<_>>();
+        let open = s
+            .open(&mut Transcript::new(b"testing"), &evals, &coeffs, &points)
+            .expect("Open failed");
+        assert_eq!(
+            Ok(true),
+            s.verify(
+                &mut Transcript::new(b"testing"),
+                &commits,
+                &points,
+                &evals,
+                &open
+            )
+        );
+        // The fast path gives the same point as interpolating the zero evals
+        {
+            use ark_ec::AffineRepr;
+            let lag_ctx = crate::lagrange::LagrangeInterpContext::new_from_points(&points).unwrap();
+            let gammas = crate::gen_powers(Fr::rand(&mut test_rng()), 3);
+            let chal_z = Fr::rand(&mut test_rng());
+            let gamma_cm_pt = commits[0].0.into_group();
+            let general = lag_ctx
+                .lagrange_interp_linear_combo(&evals, &gammas)
+                .unwrap()
+                .evaluate(&chal_z);
+            assert_eq!(
+                gamma_cm_pt - s.powers_of_g1[0] * general - open.0 * vp.evaluate(&chal_z),
+                s.f_term(gamma_cm_pt, &evals, &gammas, &open.0, chal_z, &lag_ctx, &vp)
+                    .unwrap()
+            );
+        }
+        // The fast path must still reject evals that aren't actually zero
+        let polys = (0..3)
+            .map(|_| DensePolynomial::<Fr>::rand(20, &mut test_rng()))
+            .collect::<Vec<_>>();
+        let commits = polys
+            .iter()
+            .map(|p| s.commit(&p.coeffs).expect("Commit failed"))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            Ok(false),
+            s.verify(
+                &mut Transcript::new(b"testing"),
+                &commits,
+                &points,
+                &evals,
+                &open
+            )
+        );
+    }
+
+    #[test]
+    fn test_open_empty_polys_errors() {
+        crate::test_helpers::check_open_empty_polys_errors(
+            &M2NoPrecomp::<Bls12_381>::new(64, None, &mut test_rng()).unwrap(),
+        );
+    }
+
+    #[test]
+    fn test_transposed_evals_errors() {
+        crate::test_helpers::check_transposed_evals_errors(
+            &M2NoPrecomp::<Bls12_381>::new(64, None, &mut test_rng()).unwrap(),
+        );
+    }
+
+    #[test]
+    fn test_open_empty_points_errors() {
+        let s = M2NoPrecomp::<Bls12_381>::new(64, None, &mut test_rng()).unwrap();
+        let coeffs = vec![vec![Fr::from(1u64); 8]];
+        let evals: Vec<Vec<Fr>> = vec![vec![]];
+        assert_eq!(
+            Err(Error::NoPointsGiven),
+            s.open(&mut Transcript::new(b"testing"), &evals, &coeffs, &[])
+                .map(|p| p.0)
+        );
+    }
+
+    #[test]
+    fn test_verify_heterogeneous() {
+        let s = M2NoPrecomp::<Bls12_381>::new(64, None, &mut test_rng()).unwrap();
+        let cases = (0..4)
+            .map(|i| {
+                let points = (0..i + 2)
+                    .map(|_| Fr::rand(&mut test_rng()))
+                    .collect::<Vec<_>>();
+                let polys = (0..i + 1)
+                    .map(|_| DensePolynomial::<Fr>::rand(30, &mut test_rng()))
+                    .collect::<Vec<_>>();
+                let evals: Vec<Vec<_>> = polys
+                    .iter()
+                    .map(|p| points.iter().map(|x| p.evaluate(x)).collect())
+                    .collect();
+                let coeffs = polys.iter().map(|p| p.coeffs.clone()).collect::<Vec<_>>();
+                let commits = coeffs
+                    .iter()
+                    .map(|p| s.commit(p).expect("Commit failed"))
+                    .collect::<Vec<_>>();
+                let proof = s
+                    .open(&mut Transcript::new(b"testing"), &evals, &coeffs, &points)
+                    .expect("Open failed");
+                (points, evals, commits, proof)
+            })
+            .collect::<Vec<_>>();
+        let mut items = cases
+            .iter()
+            .map(|(points, evals, commits, proof)| VerifyItem {
+                transcript: Transcript::new(b"testing"),
+                commits: &commits[..],
+                points: &points[..],
+                evals: &evals[..],
+                proof,
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(Ok(None), s.verify_heterogeneous(&items, &mut test_rng()));
+
+        items[2].proof = &cases[1].3;
+        assert_eq!(Ok(Some(2)), s.verify_heterogeneous(&items, &mut test_rng()));
+        // Unbatched verification gives the same answers
+        assert_eq!(
+            Ok(Some(2)),
+            s.verify_heterogeneous_with_threshold(&items, &mut test_rng(), usize::MAX)
+        );
+        assert_eq!(
+            Ok(None),
+            s.verify_heterogeneous_with_threshold(&items[..2], &mut test_rng(), usize::MAX)
+        );
+    }
+
+    #[cfg(feature = "debug")]
+    #[test]
+    fn test_open_debug_matches_open() {
+        let s = M2NoPrecomp::<Bls12_381>::new(256, None, &mut test_rng()).unwrap();
+        let points = (0..30)
+            .map(|_| Fr::rand(&mut test_rng()))
+            .collect::<Vec<_>>();
+        let polys = (0..20)
+            .map(|_| DensePolynomial::<Fr>::rand(50, &mut test_rng()))
+            .collect::<Vec<_>>();
+        let evals: Vec<Vec<_>> = polys
+            .iter()
+            .map(|p| points.iter().map(|x| p.evaluate(x)).collect())
+            .collect();
+        let coeffs = polys.iter().map(|p| p.coeffs.clone()).collect::<Vec<_>>();
+        let open = s
+            .open(&mut Transcript::new(b"testing"), &evals, &coeffs, &points)
+            .expect("Open failed");
+        let (debug_open, h, l_quotient) = s
+            .open_debug(&mut Transcript::new(b"testing"), &evals, &coeffs, &points)
+            .expect("Open failed");
+        assert_eq!(open.0, debug_open.0);
+        assert_eq!(open.1, debug_open.1);
+        assert_eq!(s.commit(&h.coeffs).unwrap().0, debug_open.0);
+        assert_eq!(s.commit(&l_quotient.coeffs).unwrap().0, debug_open.1);
+    }
+
+    #[cfg(feature = "debug")]
+    #[test]
+    fn test_verify_residual() {
+        use ark_ff::One;
+        let s = M2NoPrecomp::<Bls12_381>::new(64, None, &mut test_rng()).unwrap();
+        let points = (0..8)
+            .map(|_| Fr::rand(&mut test_rng()))
+            .collect::<Vec<_>>();
+        let poly = DensePolynomial::<Fr>::rand(40, &mut test_rng());
+        let mut evals = vec![points.iter().map(|x| poly.evaluate(x)).collect::<Vec<_>>()];
+        let commits = vec![s.commit(&poly.coeffs).unwrap()];
+        let open = s
+            .open(
+                &mut Transcript::new(b"testing"),
+                &evals,
+                &[&poly.coeffs],
+                &points,
+            )
+            .unwrap();
+        let residual = s
+            .verify_residual(
+                &mut Transcript::new(b"testing"),
+                &commits,
+                &points,
+                &evals,
+                &open,
+            )
+            .unwrap();
+        assert!(residual.is_one());
+
+        evals[0][3] += Fr::one();
+        let residual = s
+            .verify_residual(
+                &mut Transcript::new(b"testing"),
+                &commits,
+                &points,
+                &evals,
+                &open,
+            )
+            .unwrap();
+        assert!(!residual.is_one());
+    }
+
+    #[test]
+    fn test_verify_at_positions() {
+        let s = M2NoPrecomp::<Bls12_381>::new(64, None, &mut test_rng()).unwrap();
+        let row = (0..16)
+            .map(|_| Fr::rand(&mut test_rng()))
+            .collect::<Vec<_>>();
+        let poly = DensePolynomial::<Fr>::rand(40, &mut test_rng());
+        let commits = vec![s.commit(&poly.coeffs).unwrap()];
+        let positions = [9, 2, 14];
+        let evals = vec![positions
+            .iter()
+            .map(|&i| poly.evaluate(&row[i]))
+            .collect::<Vec<_>>()];
+        let open = s
+            .open_at_positions(
+                &mut Transcript::new(b"testing"),
+                &evals,
+                &[&poly.coeffs],
+                &row,
+                &positions,
+            )
+            .unwrap();
+        let verify = |positions: &[usize]| {
+            s.verify_at_positions(
+                &mut Transcript::new(b"testing"),
+                &commits,
+                &row,
+                positions,
+                &evals,
+                &open,
+            )
+        };
+        assert_eq!(Ok(true), verify(&positions));
+        assert_eq!(Ok(false), verify(&[9, 14, 2]));
+        assert_eq!(
+            Err(Error::PositionOutOfRange {
+                position: 16,
+                n: 16
+            }),
+            verify(&[9, 2, 16])
+        );
+        assert_eq!(
+            Err(Error::DuplicatePosition { position: 9 }),
+            verify(&[9, 2, 9])
+        );
+    }
+
+    #[test]
+    fn test_capacity() {
+        let s = M2NoPrecomp::<Bls12_381>::new(16, None, &mut test_rng()).unwrap();
+        assert_eq!(15, s.max_poly_degree());
+        assert_eq!(usize::MAX, s.max_points());
+
+        let poly = DensePolynomial::<Fr>::rand(s.max_poly_degree() + 1, &mut test_rng());
+        let expected = Err(Error::PolynomialTooLarge {
+            n_coeffs: 17,
+            expected_max: 16,
+        });
+        assert_eq!(expected, s.commit(&poly.coeffs).map(|_| ()));
+        let points = [Fr::from(1u64), Fr::from(2u64)];
+        let evals = vec![points.iter().map(|x| poly.evaluate(x)).collect::<Vec<_>>()];
+        assert_eq!(
+            expected,
+            s.open(
+                &mut Transcript::new(b"testing"),
+                &evals,
+                &[&poly.coeffs],
+                &points
+            )
+            .map(|_| ())
+        );
+
+        // Exactly at the limit still commits, opens and verifies
+        let poly = DensePolynomial::<Fr>::rand(s.max_poly_degree(), &mut test_rng());
+        assert_eq!(16, poly.coeffs.len());
+        let evals = vec![points.iter().map(|x| poly.evaluate(x)).collect::<Vec<_>>()];
+        let commit = s.commit(&poly.coeffs).unwrap();
+        let proof = s
+            .open(
+                &mut Transcript::new(b"testing"),
+                &evals,
+                &[&poly.coeffs],
+                &points,
+            )
+            .unwrap();
+        assert_eq!(
+            Ok(true),
+            s.verify(
+                &mut Transcript::new(b"testing"),
+                [commit],
+                &points,
+                &evals,
+                &proof
+            )
+        );
+    }
+
+    #[test]
+    fn test_open_many() {
+        let s = M2NoPrecomp::<Bls12_381>::new(32, None, &mut test_rng()).unwrap();
+        let points = (0..7)
+            .map(|_| Fr::rand(&mut test_rng()))
+            .collect::<Vec<_>>();
+        let point_sets = [&points[..3], &points[3..4], &points[4..]];
+        let polys = (0..3)
+            .map(|_| DensePolynomial::<Fr>::rand(20, &mut test_rng()).coeffs)
+            .collect::<Vec<_>>();
+        let evals_per_set = point_sets
+            .iter()
+            .map(|set| {
+                polys
+                    .iter()
+                    .map(|p| crate::multi_evaluate(p, set))
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        let commits = s.commit_many(&polys).unwrap();
+        let verify = |evals: &[Vec<Vec<Fr>>], proofs: &[Proof<Bls12_381>]| {
+            s.verify_many(
+                &mut Transcript::new(b"testing"),
+                &commits,
+                evals,
+                &point_sets,
+                proofs,
+                &mut test_rng(),
+            )
+        };
+
+        let proofs = s
+            .open_many(
+                &mut Transcript::new(b"testing"),
+                &evals_per_set,
+                &polys,
+                &point_sets,
+            )
+            .unwrap();
+        assert_eq!(3, proofs.len());
+        assert_eq!(Ok(true), verify(&evals_per_set, &proofs));
+
+        // Each set's proof is the same as running the rounds with the shared gamma and the set's
+        // fork of the transcript
+        let mut transcript = Transcript::new(b"testing");
+        let gamma =
+            super::transcribe_many::<Bls12_381, _, _>(&mut transcript, &evals_per_set, &point_sets)
+                .unwrap();
+        for (i, proof) in proofs.iter().enumerate() {
+            let vp = crate::vanishing_polynomial(point_sets[i]);
+            let round1 = s.open_round1(&polys, &vp, gamma).unwrap();
+            let z = super::z_challenge::<Bls12_381>(
+                &mut super::fork_for_set(&transcript, i),
+                &round1.w1(),
+            )
+            .unwrap();
+            assert!(s.open_round2(&round1, z).unwrap().eq_point(proof));
+        }
+
+        // Proofs for different sets aren't interchangeable
+        let mut swapped = proofs.clone();
+        swapped.swap(0, 2);
+        assert_eq!(Ok(false), verify(&evals_per_set, &swapped));
+        let mut wrong_evals = evals_per_set.clone();
+        wrong_evals[1][2][0] += Fr::from(1u64);
+        assert_eq!(Ok(false), verify(&wrong_evals, &proofs));
+        assert_eq!(
+            Err(Error::PointSetCountMismatch { n: 2, expected: 3 }),
+            verify(&evals_per_set, &proofs[..2])
+        );
+    }
 }
@@ -26,6 +26,9 @@ impl<E: Pairing> M2Precomp<E> {
         inner: super::M2NoPrecomp<E>,
         point_sets: Vec<Vec<E::ScalarField>>,
     ) -> Result<Self, Error> {
+        if point_sets.iter().any(|ps| ps.is_empty()) {
+            return Err(Error::NoPointsGiven);
+        }
         let vanishing_polys = cfg_iter!(point_sets)
             .map(|(_, ps)| vanishing_polynomial(ps))
             .collect();
@@ -43,11 +46,14 @@ impl<E: Pairing> M2Precomp<E> {
 }
 
 impl<E: Pairing> Committer<E> for M2Precomp<E> {
-    fn commit(
+    fn commit_projective(
         &self,
         poly: impl AsRef<[<E as Pairing>::ScalarField]>,
-    ) -> Result<Commitment<E>, Error> {
-        self.inner.commit(poly)
+    ) -> Result<E::G1, Error> {
+        self.inner.commit_projective(poly)
+    }
+    fn max_coeffs(&self) -> usize {
+        self.inner.max_coeffs()
     }
 }
 
@@ -69,27 +75,41 @@ impl<E: Pairing> PolyMultiProof<E> for M2Precomp<E> {
         polys: &[impl AsRef<[E::ScalarField]>],
         point_set_index: usize,
     ) -> Result<Proof<E>, Error> {
+        let points =
+            self.point_sets
+                .get(point_set_index)
+                .ok_or(Error::PointSetIndexOutOfRange {
+                    index: point_set_index,
+                    n: self.point_sets.len(),
+                })?;
         self.inner.open_with_vanishing_poly(
             transcript,
             evals,
             polys,
-            &self.point_sets[point_set_index],
+            points,
             &self.vanishing_polys[point_set_index],
         )
     }
 
-    fn verify(
+    fn verify_unchecked(
         &self,
         transcript: &mut Transcript,
-        commits: &[Commitment<E>],
+        commits: impl AsRef<[Commitment<E>]>,
         point_set_index: usize,
         evals: &[impl AsRef<[E::ScalarField]>],
         proof: &Proof<E>,
     ) -> Result<bool, Error> {
+        let points =
+            self.point_sets
+                .get(point_set_index)
+                .ok_or(Error::PointSetIndexOutOfRange {
+                    index: point_set_index,
+                    n: self.point_sets.len(),
+                })?;
         self.inner.verify_with_lag_ctx_vanishing_poly(
             transcript,
-            commits,
-            &self.point_sets[point_set_index],
+            commits.as_ref(),
+            points,
             evals,
             proof,
             &self.lagrange_ctxs[point_set_index],
@@ -103,7 +123,8 @@ mod tests {
     use super::M2Precomp;
     use crate::{
         test_rng,
-        traits::{Committer, PolyMultiProof},
+        traits::{Committer, PolyMultiProof, PolyMultiProofNoPrecomp},
+        Error,
     };
     use ark_bls12_381::{Bls12_381, Fr};
     use ark_poly::{univariate::DensePolynomial, DenseUVPolynomial, Polynomial};
@@ -138,5 +159,68 @@ mod tests {
             Ok(true),
             s.verify(&mut transcript, &commits, 0, &evals, &open)
         );
+
+        assert_eq!(
+            Some(Error::PointSetIndexOutOfRange { index: 1, n: 1 }),
+            s.open(&mut Transcript::new(b"testing"), &evals, &coeffs, 1)
+                .err()
+        );
+        assert_eq!(
+            Err(Error::PointSetIndexOutOfRange { index: 1, n: 1 }),
+            s.verify(&mut Transcript::new(b"testing"), &commits, 1, &evals, &open)
+        );
+    }
+
+    #[test]
+    fn test_matches_no_precomp() {
+        let point_sets = (0..3)
+            .map(|_| {
+                (0..8)
+                    .map(|_| Fr::rand(&mut test_rng()))
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        let s = M2Precomp::<Bls12_381>::new(64, point_sets.clone(), &mut test_rng())
+            .expect("Failed to construct");
+        let polys = (0..4)
+            .map(|_| DensePolynomial::<Fr>::rand(40, &mut test_rng()))
+            .collect::<Vec<_>>();
+        let coeffs = polys.iter().map(|p| p.coeffs.clone()).collect::<Vec<_>>();
+        let commits = s.commit_many(&coeffs).unwrap();
+        for (i, points) in point_sets.iter().enumerate() {
+            let evals: Vec<Vec<_>> = polys
+                .iter()
+                .map(|p| points.iter().map(|x| p.evaluate(x)).collect())
+                .collect();
+            let open = s
+                .open(&mut Transcript::new(b"testing"), &evals, &coeffs, i)
+                .expect("Open failed");
+            let expected = s
+                .inner
+                .open(&mut Transcript::new(b"testing"), &evals, &coeffs, points)
+                .expect("Open failed");
+            assert!(open.eq_point(&expected));
+            // Each verifies the other's proofs
+            assert_eq!(
+                Ok(true),
+                s.verify(
+                    &mut Transcript::new(b"testing"),
+                    &commits,
+                    i,
+                    &evals,
+                    &expected
+                )
+            );
+            assert_eq!(
+                Ok(true),
+                s.inner.verify(
+                    &mut Transcript::new(b"testing"),
+                    &commits,
+                    points,
+                    &evals,
+                    &open
+                )
+            );
+        }
     }
 }
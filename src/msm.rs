@@ -0,0 +1,129 @@
+//! Backends for the msms against a setup's powers, which dominate `commit` and `open` for large
+//! polynomials.
+//!
+//! A backend is built once from the powers, so it can prepare them however it likes, e.g. convert
+//! them for blst, and is then only given scalars. [`ArkworksMsm`] is the portable CPU
+//! implementation, and the blst setup uses its own blst backend by default.
+//!
+//! This crate ships no GPU backend and has no `gpu` feature. A GPU implementation has to live in
+//! a separate crate, implementing [`MsmBackend`] and being passed to the blst setup's
+//! `M1NoPrecomp::new_with_backend`.
+
+use ark_ec::{pairing::Pairing, CurveGroup};
+use ark_serialize::CanonicalDeserialize;
+
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+use crate::{cfg_iter, curve_msm, get_field_size, Error};
+
+/// Computes the msms against a setup's powers
+pub trait MsmBackend<E: Pairing>: Send + Sync {
+    /// The msm of `scalars` with the first `scalars.len()` g1 powers, erroring with
+    /// [`Error::PolynomialTooLarge`] if there are more scalars than powers
+    fn msm_g1(&self, scalars: &[E::ScalarField]) -> Result<E::G1, Error>;
+
+    /// Same as [`MsmBackend::msm_g1`], with the g2 powers
+    fn msm_g2(&self, scalars: &[E::ScalarField]) -> Result<E::G2, Error>;
+
+    /// [`MsmBackend::msm_g1`] of scalars given as their concatenated compressed
+    /// serializations, for backends that consume bytes and can skip converting the scalars
+    /// again when the same ones are used for many msms
+    fn msm_g1_serialized(&self, scalars: &[u8]) -> Result<E::G1, Error> {
+        let scalars = scalars
+            .chunks(get_field_size::<E::ScalarField>())
+            .map(E::ScalarField::deserialize_compressed)
+            .collect::<Result<Vec<_>, _>>()?;
+        self.msm_g1(&scalars)
+    }
+
+    /// [`MsmBackend::msm_g1`] of each row, for backends that can share work between rows
+    fn msm_g1_many(&self, rows: &[&[E::ScalarField]]) -> Result<Vec<E::G1>, Error> {
+        cfg_iter!(rows).map(|(_, row)| self.msm_g1(row)).collect()
+    }
+
+    /// The msm of `scalars` with `bases` that aren't the powers, such as the commitments a
+    /// verifier combines
+    fn msm_g1_with_bases(
+        &self,
+        bases: &[E::G1Affine],
+        scalars: &[E::ScalarField],
+    ) -> Result<E::G1, Error> {
+        curve_msm::<E::G1>(bases, scalars)
+    }
+}
+
+/// Msms with arkworks' variable base msm
+#[derive(Clone, Debug)]
+pub struct ArkworksMsm<E: Pairing> {
+    powers_of_g1: Vec<E::G1Affine>,
+    powers_of_g2: Vec<E::G2Affine>,
+}
+
+impl<E: Pairing> ArkworksMsm<E> {
+    pub fn new(powers_of_g1: Vec<E::G1Affine>, powers_of_g2: Vec<E::G2Affine>) -> Self {
+        Self {
+            powers_of_g1,
+            powers_of_g2,
+        }
+    }
+
+    /// Same as [`ArkworksMsm::new`], normalizing the powers first
+    pub fn from_projective(powers_of_g1: &[E::G1], powers_of_g2: &[E::G2]) -> Self {
+        Self::new(
+            E::G1::normalize_batch(powers_of_g1),
+            E::G2::normalize_batch(powers_of_g2),
+        )
+    }
+}
+
+impl<E: Pairing> MsmBackend<E> for ArkworksMsm<E> {
+    fn msm_g1(&self, scalars: &[E::ScalarField]) -> Result<E::G1, Error> {
+        curve_msm::<E::G1>(&self.powers_of_g1, scalars)
+    }
+
+    fn msm_g2(&self, scalars: &[E::ScalarField]) -> Result<E::G2, Error> {
+        curve_msm::<E::G2>(&self.powers_of_g2, scalars)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ArkworksMsm, MsmBackend};
+    use crate::{gen_curve_powers, gen_powers, test_rng, Error};
+    use ark_bls12_381::{Bls12_381, Fr, G1Projective, G2Projective};
+    use ark_ec::VariableBaseMSM;
+    use ark_std::UniformRand;
+
+    #[test]
+    fn test_arkworks_msm() {
+        let powers = gen_powers(Fr::rand(&mut test_rng()), 16);
+        let g1s = gen_curve_powers::<G1Projective>(&powers, &mut test_rng());
+        let g2s = gen_curve_powers::<G2Projective>(&powers[..4], &mut test_rng());
+        let backend = ArkworksMsm::<Bls12_381>::new(g1s.clone(), g2s.clone());
+        let scalars = (0..10)
+            .map(|_| Fr::rand(&mut test_rng()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            G1Projective::msm(&g1s[..10], &scalars).unwrap(),
+            backend.msm_g1(&scalars).unwrap()
+        );
+        assert_eq!(
+            G2Projective::msm(&g2s, &scalars[..4]).unwrap(),
+            backend.msm_g2(&scalars[..4]).unwrap()
+        );
+        let many = backend.msm_g1_many(&[&scalars[..3], &scalars[..]]).unwrap();
+        assert_eq!(backend.msm_g1(&scalars[..3]).unwrap(), many[0]);
+        assert_eq!(backend.msm_g1(&scalars).unwrap(), many[1]);
+        assert_eq!(
+            G1Projective::msm(&g1s[4..8], &scalars[..4]).unwrap(),
+            backend
+                .msm_g1_with_bases(&g1s[4..8], &scalars[..4])
+                .unwrap()
+        );
+        assert!(matches!(
+            backend.msm_g2(&scalars),
+            Err(Error::PolynomialTooLarge { .. })
+        ));
+    }
+}
@@ -0,0 +1,192 @@
+use std::cmp::min;
+
+use ark_ff::{FftField, Field};
+use ark_poly::{univariate::DensePolynomial, DenseUVPolynomial, Polynomial};
+use ark_std::Zero;
+
+use crate::vanishing_polynomial;
+
+/// Below this many points, a subtree just evaluates its remainder at each point directly
+const LEAF_SIZE: usize = 32;
+
+/// Evaluates the polynomial with coefficients `poly` at every point in `points`, returning the
+/// evals in the same order as the points.
+///
+/// This uses a subproduct tree: `poly` is reduced mod the vanishing polynomial of all the points,
+/// then recursively mod the vanishing polynomials of each half. With fft multiplication and
+/// newton iteration for the divisions, this takes $O(n \log^2 n)$ field operations for $n$ points,
+/// plus one reduction of `poly`, instead of the $O(nd)$ of evaluating a degree $d$ polynomial at
+/// each point separately.
+pub fn multi_evaluate<F: FftField>(poly: &[F], points: &[F]) -> Vec<F> {
+    let poly = DensePolynomial::from_coefficients_slice(poly);
+    if points.len() <= LEAF_SIZE {
+        return points.iter().map(|x| poly.evaluate(x)).collect();
+    }
+    let tree = SubproductTree::new(points);
+    let mut out = Vec::with_capacity(points.len());
+    tree.evaluate(&poly, &mut out);
+    out
+}
+
+/// The vanishing polynomials of the points, then of each half of them, and so on down to leaves
+/// of at most [`LEAF_SIZE`] points. Only the leaves keep their points.
+#[derive(Debug, Clone)]
+pub(crate) struct SubproductTree<F: FftField> {
+    vp: DensePolynomial<F>,
+    node: Node<F>,
+}
+
+#[derive(Debug, Clone)]
+enum Node<F: FftField> {
+    Leaf(Vec<F>),
+    Branch(Box<SubproductTree<F>>, Box<SubproductTree<F>>),
+}
+
+impl<F: FftField> SubproductTree<F> {
+    pub(crate) fn new(points: &[F]) -> Self {
+        if points.len() <= LEAF_SIZE {
+            return Self {
+                vp: vanishing_polynomial(points),
+                node: Node::Leaf(points.to_vec()),
+            };
+        }
+        let (l, r) = points.split_at(points.len() / 2);
+        let left = Self::new(l);
+        let right = Self::new(r);
+        Self {
+            vp: &left.vp * &right.vp,
+            node: Node::Branch(Box::new(left), Box::new(right)),
+        }
+    }
+
+    /// The vanishing polynomial of all the points
+    pub(crate) fn vanishing_poly(&self) -> &DensePolynomial<F> {
+        &self.vp
+    }
+
+    /// The number of points under this node, which is the degree of their vanishing polynomial
+    fn n_points(&self) -> usize {
+        self.vp.degree()
+    }
+
+    pub(crate) fn evaluate(&self, poly: &DensePolynomial<F>, out: &mut Vec<F>) {
+        // The remainder agrees with poly on every point under this node
+        let rem = fast_rem(poly, &self.vp);
+        match &self.node {
+            Node::Leaf(points) => out.extend(points.iter().map(|x| rem.evaluate(x))),
+            Node::Branch(left, right) => {
+                left.evaluate(&rem, out);
+                right.evaluate(&rem, out);
+            }
+        }
+    }
+
+    /// Computes $\sum_j c_j \prod_{k \neq j} (X - x_k)$, which is the interpolation of $y_j$ when
+    /// $c_j = y_j w_j$ for the barycentric weights $w_j$. Each node combines its children as
+    /// $r_l m_r + r_r m_l$, where $m$ are their vanishing polynomials.
+    pub(crate) fn linear_combination(&self, scalars: &[F]) -> DensePolynomial<F> {
+        match &self.node {
+            Node::Leaf(points) => {
+                let mut coeffs = vec![F::zero(); points.len()];
+                for (x, c) in points.iter().zip(scalars) {
+                    let basis = crate::div_by_linear(&self.vp.coeffs, *x);
+                    for (a, b) in coeffs.iter_mut().zip(basis) {
+                        *a += b * c;
+                    }
+                }
+                DensePolynomial::from_coefficients_vec(coeffs)
+            }
+            Node::Branch(left, right) => {
+                let (l, r) = scalars.split_at(left.n_points());
+                &(&left.linear_combination(l) * &right.vp)
+                    + &(&right.linear_combination(r) * &left.vp)
+            }
+        }
+    }
+}
+
+/// Computes `a mod b` for monic `b` by computing the quotient of the reversed polynomials as a
+/// power series
+fn fast_rem<F: FftField>(a: &DensePolynomial<F>, b: &DensePolynomial<F>) -> DensePolynomial<F> {
+    if a.is_zero() || a.degree() < b.degree() {
+        return a.clone();
+    }
+    let q_len = a.degree() - b.degree() + 1;
+    let rev_a = a
+        .coeffs
+        .iter()
+        .rev()
+        .take(q_len)
+        .cloned()
+        .collect::<Vec<_>>();
+    let rev_b = b.coeffs.iter().rev().cloned().collect::<Vec<_>>();
+    let rev_b_inv = inverse_series(&rev_b, q_len);
+
+    let mut rev_q = truncate(
+        &DensePolynomial::from_coefficients_vec(rev_a) * &rev_b_inv,
+        q_len,
+    )
+    .coeffs;
+    rev_q.resize(q_len, F::zero());
+    rev_q.reverse();
+    let q = DensePolynomial::from_coefficients_vec(rev_q);
+    a - &(&q * b)
+}
+
+/// Computes $f^{-1} \bmod X^k$ with newton iteration
+fn inverse_series<F: FftField>(f: &[F], k: usize) -> DensePolynomial<F> {
+    let f0_inv = f[0].inverse().expect("Divisor must be monic");
+    let mut g = DensePolynomial::from_coefficients_vec(vec![f0_inv]);
+    let mut len = 1;
+    while len < k {
+        len = min(2 * len, k);
+        let f_trunc = DensePolynomial::from_coefficients_slice(&f[..min(len, f.len())]);
+        // g <- g (2 - f g) mod X^len
+        let mut two_minus_fg = truncate(&f_trunc * &g, len).coeffs;
+        two_minus_fg.iter_mut().for_each(|c| *c = -*c);
+        if two_minus_fg.is_empty() {
+            two_minus_fg.push(F::zero());
+        }
+        two_minus_fg[0] += F::from(2u64);
+        g = truncate(
+            &g * &DensePolynomial::from_coefficients_vec(two_minus_fg),
+            len,
+        );
+    }
+    g
+}
+
+fn truncate<F: FftField>(mut p: DensePolynomial<F>, len: usize) -> DensePolynomial<F> {
+    p.coeffs.truncate(len);
+    DensePolynomial::from_coefficients_vec(p.coeffs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::multi_evaluate;
+    use crate::test_rng;
+    use ark_bls12_381::Fr;
+    use ark_poly::{univariate::DensePolynomial, DenseUVPolynomial, Polynomial};
+    use ark_std::UniformRand;
+
+    #[test]
+    fn test_matches_naive() {
+        for (degree, n_points) in [(100, 300), (300, 100), (5, 70)] {
+            let poly = DensePolynomial::<Fr>::rand(degree, &mut test_rng());
+            let points = (0..n_points)
+                .map(|_| Fr::rand(&mut test_rng()))
+                .collect::<Vec<_>>();
+            let expected = points.iter().map(|x| poly.evaluate(x)).collect::<Vec<_>>();
+            assert_eq!(expected, multi_evaluate(&poly.coeffs, &points));
+        }
+    }
+
+    #[test]
+    fn test_edge_cases() {
+        let poly = DensePolynomial::<Fr>::rand(10, &mut test_rng());
+        assert!(multi_evaluate(&poly.coeffs, &[]).is_empty());
+        let x = Fr::rand(&mut test_rng());
+        assert_eq!(vec![poly.evaluate(&x)], multi_evaluate(&poly.coeffs, &[x]));
+        assert_eq!(vec![Fr::from(0u64); 40], multi_evaluate(&[], &vec![x; 40]));
+    }
+}
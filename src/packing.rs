@@ -0,0 +1,123 @@
+//! Packing many small polynomials into one, so they can be committed to with a single msm and
+//! a single commitment.
+//!
+//! The polynomials $f_0, \ldots, f_{m-1}$ are laid out at `block_size` offsets as
+//! $P(X) = \sum_i X^{i \cdot b} f_i(X)$ for block size $b$. Each $f_i$ must have at most $b$
+//! coefficients, and $P$ has up to $m \cdot b$, which must fit the setup.
+//!
+//! The commitment is to $P$ only. Opening $P$ at $z$ gives $\sum_i z^{i \cdot b} f_i(z)$, not the
+//! individual $f_i(z)$, and nothing here proves that each block really has degree less than $b$.
+//! A prover could move coefficients between neighbouring blocks without changing the commitment,
+//! so protocols that need to open the $f_i$ separately also need a degree bound proof per block.
+
+use ark_ec::pairing::Pairing;
+use ark_ff::Field;
+
+use crate::{traits::Committer, Commitment, Error};
+
+/// Where a polynomial ended up in a packed polynomial
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PackedSlot {
+    /// The index of the polynomial's constant coefficient
+    pub offset: usize,
+    /// The number of coefficients of the polynomial
+    pub len: usize,
+}
+
+impl PackedSlot {
+    /// The polynomial's coefficients within `packed`
+    pub fn coeffs<'a, F>(&self, packed: &'a [F]) -> Option<&'a [F]> {
+        packed.get(self.offset..self.offset + self.len)
+    }
+
+    /// The factor $z^{\mathrm{offset}}$ that the polynomial's evaluation at $z$ is scaled by in
+    /// the packed polynomial's evaluation
+    pub fn shift<F: Field>(&self, z: F) -> F {
+        z.pow([self.offset as u64])
+    }
+}
+
+/// Lays out `polys` at multiples of `block_size`, returning the packed coefficients and each
+/// polynomial's slot
+pub fn pack<F: Field>(
+    polys: &[impl AsRef<[F]>],
+    block_size: usize,
+) -> Result<(Vec<F>, Vec<PackedSlot>), Error> {
+    if polys.is_empty() {
+        return Err(Error::NoPolynomialsGiven);
+    }
+    let mut packed = vec![F::zero(); polys.len() * block_size];
+    let slots = polys
+        .iter()
+        .enumerate()
+        .map(|(i, poly)| {
+            let poly = poly.as_ref();
+            if poly.len() > block_size {
+                return Err(Error::PolynomialTooLarge {
+                    n_coeffs: poly.len(),
+                    expected_max: block_size,
+                });
+            }
+            let offset = i * block_size;
+            packed[offset..offset + poly.len()].copy_from_slice(poly);
+            Ok(PackedSlot {
+                offset,
+                len: poly.len(),
+            })
+        })
+        .collect::<Result<Vec<_>, Error>>()?;
+    Ok((packed, slots))
+}
+
+/// Packs `polys` with [`pack`] and commits to the result once
+pub fn commit_packed<E: Pairing>(
+    committer: &impl Committer<E>,
+    polys: &[impl AsRef<[E::ScalarField]>],
+    block_size: usize,
+) -> Result<(Commitment<E>, Vec<PackedSlot>), Error> {
+    let (packed, slots) = pack(polys, block_size)?;
+    Ok((committer.commit(&packed)?, slots))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{commit_packed, pack};
+    use crate::{
+        method1::M1NoPrecomp,
+        test_rng,
+        traits::{Committer, PolyMultiProofNoPrecomp},
+    };
+    use ark_bls12_381::{Bls12_381, Fr};
+    use ark_poly::{univariate::DensePolynomial, DenseUVPolynomial, Polynomial};
+    use ark_std::UniformRand;
+
+    #[test]
+    fn test_commit_packed() {
+        let s = M1NoPrecomp::<Bls12_381>::new(64, None, &mut test_rng()).unwrap();
+        let polys = (0..5)
+            .map(|i| {
+                (0..3 + i)
+                    .map(|_| Fr::rand(&mut test_rng()))
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        let (commit, slots) = commit_packed(&s, &polys, 8).unwrap();
+        let (packed, _) = pack(&polys, 8).unwrap();
+        assert_eq!(40, packed.len());
+        assert_eq!(s.commit(&packed).unwrap().0, commit.0);
+
+        let z = Fr::rand(&mut test_rng());
+        let mut expected = Fr::from(0u64);
+        for (poly, slot) in polys.iter().zip(&slots) {
+            assert_eq!(Some(&poly[..]), slot.coeffs(&packed));
+            expected += slot.shift(z) * DensePolynomial::from_coefficients_slice(poly).evaluate(&z);
+        }
+        assert_eq!(
+            expected,
+            DensePolynomial::from_coefficients_vec(packed).evaluate(&z)
+        );
+
+        assert!(commit_packed(&s, &polys, 4).is_err());
+        assert!(commit_packed(&s, &polys, 16).is_err());
+    }
+}
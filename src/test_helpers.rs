@@ -0,0 +1,190 @@
+//! Checks shared by the tests of the different setups
+
+use ark_bls12_381::{Bls12_381, Fr, G1Affine};
+use ark_poly::{univariate::DensePolynomial, DenseUVPolynomial, Polynomial};
+use ark_std::UniformRand;
+use merlin::Transcript;
+
+use crate::{
+    test_rng,
+    traits::{Committer, PolyMultiProofNoPrecomp},
+    Commitment, Error,
+};
+
+/// Opening no polynomials, or only empty ones, errors
+pub(crate) fn check_open_empty_polys_errors<P: PolyMultiProofNoPrecomp<Bls12_381>>(s: &P) {
+    let points = vec![Fr::from(1u64), Fr::from(2u64)];
+    let no_polys: Vec<Vec<Fr>> = vec![];
+    assert_eq!(
+        Err(Error::NoPolynomialsGiven),
+        s.open(
+            &mut Transcript::new(b"testing"),
+            &no_polys,
+            &no_polys,
+            &points
+        )
+        .map(|_| ())
+    );
+    let evals = vec![vec![Fr::from(0u64); 2]; 2];
+    let empty_polys: Vec<Vec<Fr>> = vec![vec![]; 2];
+    assert_eq!(
+        Err(Error::EmptyPolynomials),
+        s.open(
+            &mut Transcript::new(b"testing"),
+            &evals,
+            &empty_polys,
+            &points
+        )
+        .map(|_| ())
+    );
+}
+
+/// Evals given per point rather than per polynomial are caught by both open and verify
+pub(crate) fn check_transposed_evals_errors<P>(s: &P)
+where
+    P: PolyMultiProofNoPrecomp<Bls12_381> + Committer<Bls12_381>,
+{
+    let points = (0..4)
+        .map(|_| Fr::rand(&mut test_rng()))
+        .collect::<Vec<_>>();
+    let polys = (0..3)
+        .map(|_| DensePolynomial::<Fr>::rand(30, &mut test_rng()).coeffs)
+        .collect::<Vec<_>>();
+    let evals = polys
+        .iter()
+        .map(|p| crate::multi_evaluate(p, &points))
+        .collect::<Vec<_>>();
+    let transposed = (0..points.len())
+        .map(|j| evals.iter().map(|e| e[j]).collect::<Vec<_>>())
+        .collect::<Vec<_>>();
+    let expected = || {
+        Err(Error::EvalsIncorrectSize {
+            poly: 0,
+            n: 3,
+            expected: 4,
+        })
+    };
+
+    assert_eq!(
+        expected(),
+        s.open(
+            &mut Transcript::new(b"testing"),
+            &transposed,
+            &polys,
+            &points
+        )
+        .map(|_| ())
+    );
+    let commits = s.commit_many(&polys).unwrap();
+    let proof = s
+        .open(&mut Transcript::new(b"testing"), &evals, &polys, &points)
+        .unwrap();
+    assert_eq!(
+        expected(),
+        s.verify(
+            &mut Transcript::new(b"testing"),
+            &commits,
+            &points,
+            &transposed,
+            &proof,
+        )
+    );
+    // Dropping a row is caught too, rather than checked against the first two commitments
+    assert_eq!(
+        Err(Error::EvalsIncorrectSize {
+            poly: 2,
+            n: 0,
+            expected: 4,
+        }),
+        s.verify(
+            &mut Transcript::new(b"testing"),
+            &commits,
+            &points,
+            &evals[..2],
+            &proof,
+        )
+    );
+    // And so is an extra one, which no commitment expects evals for
+    let mut extra = evals.clone();
+    extra.push(evals[0].clone());
+    assert_eq!(
+        Err(Error::EvalsIncorrectSize {
+            poly: 3,
+            n: 4,
+            expected: 0,
+        }),
+        s.verify(
+            &mut Transcript::new(b"testing"),
+            &commits,
+            &points,
+            &extra,
+            &proof,
+        )
+    );
+}
+
+/// Commitments and proofs survive their compressed bytes, and truncated or padded bytes are
+/// malformed. `proof_len` is the expected length of the proof's bytes.
+pub(crate) fn check_compressed_bytes_roundtrip<P>(
+    s: &P,
+    proof_len: usize,
+    to_bytes: fn(&P::Proof) -> Vec<u8>,
+    from_bytes: fn(&[u8]) -> Result<P::Proof, Error>,
+) where
+    P: PolyMultiProofNoPrecomp<Bls12_381> + Committer<Bls12_381>,
+{
+    let points = (0..4)
+        .map(|_| Fr::rand(&mut test_rng()))
+        .collect::<Vec<_>>();
+    let poly = DensePolynomial::<Fr>::rand(20, &mut test_rng());
+    let evals = vec![points.iter().map(|x| poly.evaluate(x)).collect::<Vec<_>>()];
+    let commit = s.commit(&poly.coeffs).unwrap();
+    let open = s
+        .open(
+            &mut Transcript::new(b"testing"),
+            &evals,
+            &[&poly.coeffs],
+            &points,
+        )
+        .unwrap();
+
+    let commit_bytes = commit.to_compressed_bytes();
+    let proof_bytes = to_bytes(&open);
+    assert_eq!(48, commit_bytes.len());
+    assert_eq!(proof_len, proof_bytes.len());
+    let commit = Commitment::<Bls12_381>::from_compressed_bytes(&commit_bytes).unwrap();
+    let open = from_bytes(&proof_bytes).unwrap();
+    assert_eq!(
+        Ok(true),
+        s.verify(
+            &mut Transcript::new(b"testing"),
+            [commit],
+            &points,
+            &evals,
+            &open
+        )
+    );
+
+    assert_eq!(
+        Err(Error::MalformedProof),
+        from_bytes(&proof_bytes[1..]).map(|_| ())
+    );
+    let mut trailing = proof_bytes.clone();
+    trailing.push(0);
+    assert_eq!(
+        Err(Error::MalformedProof),
+        from_bytes(&trailing).map(|_| ())
+    );
+    assert_eq!(
+        Err(Error::MalformedCommitment { index: 0 }),
+        Commitment::<Bls12_381>::from_compressed_bytes(&commit_bytes[..47]).map(|_| ())
+    );
+}
+
+/// An on-curve point outside the prime order subgroup, almost any x with a y will do
+pub(crate) fn non_subgroup_g1() -> G1Affine {
+    (1u64..)
+        .filter_map(|x| G1Affine::get_point_from_x_unchecked(x.into(), false))
+        .find(|p| !p.is_in_correct_subgroup_assuming_on_curve())
+        .unwrap()
+}
@@ -1,15 +1,124 @@
-use ark_ec::pairing::Pairing;
+use ark_ec::{pairing::Pairing, CurveGroup};
+use ark_ff::FftField;
+use ark_poly::{EvaluationDomain, GeneralEvaluationDomain, Radix2EvaluationDomain};
+use ark_serialize::{CanonicalDeserialize, Valid};
 use ark_std::rand::RngCore;
 use merlin::Transcript;
 
-use crate::{Commitment, Error};
+use crate::{
+    from_exact_compressed, transcribe_bound_commits, transcribe_context, transcript::PmpTranscript,
+    transcript_state_digest, Commitment, Error,
+};
 
 pub trait Committer<E: Pairing> {
-    fn commit(&self, poly: impl AsRef<[E::ScalarField]>) -> Result<Commitment<E>, Error>;
+    fn commit(&self, poly: impl AsRef<[E::ScalarField]>) -> Result<Commitment<E>, Error> {
+        Ok(Commitment(self.commit_projective(poly)?.into_affine()))
+    }
+
+    /// Same as [`Committer::commit`], but returns the projective point, skipping the
+    /// normalization when the commitment is only going to be used in more group arithmetic
+    fn commit_projective(&self, poly: impl AsRef<[E::ScalarField]>) -> Result<E::G1, Error>;
+
+    /// Commits to each of `polys`, normalizing all the commitments together
+    fn commit_many(
+        &self,
+        polys: &[impl AsRef<[E::ScalarField]> + Sync],
+    ) -> Result<Vec<Commitment<E>>, Error> {
+        let projective = polys
+            .iter()
+            .map(|p| self.commit_projective(p))
+            .collect::<Result<Vec<_>, Error>>()?;
+        Ok(E::G1::normalize_batch(&projective)
+            .into_iter()
+            .map(Commitment)
+            .collect())
+    }
+
+    /// The largest degree of polynomial that can be committed to
+    fn max_poly_degree(&self) -> usize {
+        self.max_coeffs().saturating_sub(1)
+    }
+
+    /// The most coefficients a committed polynomial can have, which is zero for a setup with no
+    /// powers. Defaults to `usize::MAX` for committers that don't report a limit, in which case
+    /// an oversized polynomial is only caught by `commit` itself.
+    fn max_coeffs(&self) -> usize {
+        usize::MAX
+    }
+
+    /// Whether a polynomial with `n_coeffs` coefficients can be committed to
+    fn fits(&self, n_coeffs: usize) -> bool {
+        n_coeffs <= self.max_coeffs()
+    }
+
+    /// Recomputes the commitment to `poly` and its evaluations at `points`, erroring if they
+    /// don't match `commitment` and `evals`. Useful for catching misaligned `evals` and `polys`
+    /// before opening, rather than getting a proof that fails to verify.
+    #[cfg(feature = "debug")]
+    fn debug_check_consistency(
+        &self,
+        poly: impl AsRef<[E::ScalarField]>,
+        commitment: &Commitment<E>,
+        points: &[E::ScalarField],
+        evals: &[E::ScalarField],
+    ) -> Result<(), Error> {
+        use ark_poly::{univariate::DensePolynomial, DenseUVPolynomial, Polynomial};
+
+        if points.len() != evals.len() {
+            return Err(Error::EvalsIncorrectSize {
+                poly: 0,
+                n: evals.len(),
+                expected: points.len(),
+            });
+        }
+        if self.commit(poly.as_ref())?.0 != commitment.0 {
+            return Err(Error::CommitmentMismatch);
+        }
+        let poly = DensePolynomial::from_coefficients_slice(poly.as_ref());
+        for (index, (x, y)) in points.iter().zip(evals).enumerate() {
+            if poly.evaluate(x) != *y {
+                return Err(Error::EvalMismatch { index });
+            }
+        }
+        Ok(())
+    }
+
+    /// Same as [`EvalCommitter::commit_lagrange`] over a radix 2 domain
+    fn commit_from_evals(
+        &self,
+        evals: &[E::ScalarField],
+        domain: &Radix2EvaluationDomain<E::ScalarField>,
+    ) -> Result<Commitment<E>, Error>
+    where
+        Self: Sized,
+    {
+        self.commit_lagrange(evals, &GeneralEvaluationDomain::Radix2(*domain))
+    }
+}
+
+/// Committing to polynomials given in evaluation form. Every [`Committer`] gets this for free.
+pub trait EvalCommitter<E: Pairing>: Committer<E> {
+    /// Commits to the polynomial whose evaluations over the elements of `domain` are `evals`
+    fn commit_lagrange(
+        &self,
+        evals: &[E::ScalarField],
+        domain: &GeneralEvaluationDomain<E::ScalarField>,
+    ) -> Result<Commitment<E>, Error> {
+        if evals.len() != domain.size() {
+            return Err(Error::EvalsIncorrectSize {
+                poly: 0,
+                n: evals.len(),
+                expected: domain.size(),
+            });
+        }
+        self.commit(domain.ifft(evals))
+    }
 }
 
+impl<E: Pairing, C: Committer<E>> EvalCommitter<E> for C {}
+
 pub trait PolyMultiProof<E: Pairing>: Sized {
-    type Proof: Clone;
+    type Proof: Clone + Valid;
 
     fn new(
         max_coeffs: usize,
@@ -17,6 +126,9 @@ pub trait PolyMultiProof<E: Pairing>: Sized {
         r: &mut impl RngCore,
     ) -> Result<Self, Error>;
 
+    /// `evals` and `polys` can be slices of anything that borrows as a slice of scalars, such as
+    /// `&[Vec<F>]` or `&[&[F]]`. A window into a larger grid of evaluations can be passed by
+    /// borrowing the relevant part of each row, without copying any scalars.
     fn open(
         &self,
         transcript: &mut Transcript,
@@ -25,21 +137,209 @@ pub trait PolyMultiProof<E: Pairing>: Sized {
         point_set_index: usize,
     ) -> Result<Self::Proof, Error>;
 
+    /// Same as `open`, but each polynomial is given by its evaluations over the elements of
+    /// `domain`, as in [`EvalCommitter::commit_lagrange`], and is interpolated before opening
+    fn open_evals(
+        &self,
+        transcript: &mut Transcript,
+        evals: &[impl AsRef<[E::ScalarField]>],
+        domain_evals: &[impl AsRef<[E::ScalarField]>],
+        domain: &GeneralEvaluationDomain<E::ScalarField>,
+        point_set_index: usize,
+    ) -> Result<Self::Proof, Error> {
+        let polys = interpolate_over_domain(domain_evals, domain)?;
+        self.open(transcript, evals, &polys, point_set_index)
+    }
+
+    /// Checks that the commitments and the proof are valid points in the prime order subgroup,
+    /// erroring with [`Error::InvalidPoint`] if not, then verifies the opening
     fn verify(
         &self,
         transcript: &mut Transcript,
-        commits: &[Commitment<E>],
+        commits: impl AsRef<[Commitment<E>]>,
+        point_set_index: usize,
+        evals: &[impl AsRef<[E::ScalarField]>],
+        proof: &Self::Proof,
+    ) -> Result<bool, Error> {
+        check_points(commits.as_ref(), proof)?;
+        self.verify_unchecked(transcript, commits, point_set_index, evals, proof)
+    }
+
+    /// Same as `verify`, but skips the curve and subgroup checks on the commitments and proof.
+    /// This is only sound when every point has already been validated, e.g. by deserializing it
+    /// with [`Validate::Yes`](ark_serialize::Validate::Yes), and must not be used on untrusted
+    /// points otherwise.
+    fn verify_unchecked(
+        &self,
+        transcript: &mut Transcript,
+        commits: impl AsRef<[Commitment<E>]>,
         point_set_index: usize,
         evals: &[impl AsRef<[E::ScalarField]>],
         proof: &Self::Proof,
     ) -> Result<bool, Error>;
+
+    /// Same as `open`, on a fresh transcript from a
+    /// [`ProofTranscript`](crate::transcript::ProofTranscript), so the opening always starts from
+    /// the pinned label and domain
+    fn open_pinned(
+        &self,
+        mut transcript: PmpTranscript,
+        evals: &[impl AsRef<[E::ScalarField]>],
+        polys: &[impl AsRef<[E::ScalarField]>],
+        point_set_index: usize,
+    ) -> Result<Self::Proof, Error> {
+        self.open(transcript.inner_mut(), evals, polys, point_set_index)
+    }
+
+    /// Verifies a proof made with `open_pinned` on a transcript from the same factory
+    fn verify_pinned(
+        &self,
+        mut transcript: PmpTranscript,
+        commits: impl AsRef<[Commitment<E>]>,
+        point_set_index: usize,
+        evals: &[impl AsRef<[E::ScalarField]>],
+        proof: &Self::Proof,
+    ) -> Result<bool, Error> {
+        self.verify(
+            transcript.inner_mut(),
+            commits,
+            point_set_index,
+            evals,
+            proof,
+        )
+    }
+
+    /// Same as `verify`, but takes each commitment as its compressed bytes and the proof as its
+    /// compressed serialization, as received over the network. Both are decoded with the subgroup
+    /// checks. Malformed input is [`Error::MalformedCommitment`] or [`Error::MalformedProof`],
+    /// while a well formed but invalid proof is `Ok(false)`.
+    fn verify_from_bytes(
+        &self,
+        transcript: &mut Transcript,
+        commits: &[impl AsRef<[u8]>],
+        point_set_index: usize,
+        evals: &[impl AsRef<[E::ScalarField]>],
+        proof: &[u8],
+    ) -> Result<bool, Error>
+    where
+        Self::Proof: CanonicalDeserialize,
+    {
+        let (commits, proof) = decode_commits_and_proof::<E, Self::Proof>(commits, proof)?;
+        self.verify_unchecked(transcript, commits, point_set_index, evals, &proof)
+    }
+
+    /// Same as [`PolyMultiProof::open`], but first absorbs `commits` into the transcript so the
+    /// challenges are bound to the commitments. Proofs made this way must be checked with
+    /// [`PolyMultiProof::verify_bound`].
+    fn open_bound(
+        &self,
+        transcript: &mut Transcript,
+        commits: &[Commitment<E>],
+        evals: &[impl AsRef<[E::ScalarField]>],
+        polys: &[impl AsRef<[E::ScalarField]>],
+        point_set_index: usize,
+    ) -> Result<Self::Proof, Error> {
+        transcribe_bound_commits(transcript, commits)?;
+        self.open(transcript, evals, polys, point_set_index)
+    }
+
+    /// Verifies a proof made with [`PolyMultiProof::open_bound`]
+    fn verify_bound(
+        &self,
+        transcript: &mut Transcript,
+        commits: &[Commitment<E>],
+        point_set_index: usize,
+        evals: &[impl AsRef<[E::ScalarField]>],
+        proof: &Self::Proof,
+    ) -> Result<bool, Error> {
+        transcribe_bound_commits(transcript, commits)?;
+        self.verify(transcript, commits, point_set_index, evals, proof)
+    }
+
+    /// Same as [`PolyMultiProof::open`], but first absorbs `context` into the transcript, binding
+    /// the proof to e.g. a session id or block height. Proofs made this way must be checked with
+    /// [`PolyMultiProof::verify_with_context`] and the same `context`. Distinct contexts also
+    /// domain separate instances embedded in one larger protocol.
+    fn open_with_context(
+        &self,
+        transcript: &mut Transcript,
+        context: &[u8],
+        evals: &[impl AsRef<[E::ScalarField]>],
+        polys: &[impl AsRef<[E::ScalarField]>],
+        point_set_index: usize,
+    ) -> Result<Self::Proof, Error> {
+        transcribe_context(transcript, context);
+        self.open(transcript, evals, polys, point_set_index)
+    }
+
+    /// Verifies a proof made with [`PolyMultiProof::open_with_context`]
+    fn verify_with_context(
+        &self,
+        transcript: &mut Transcript,
+        context: &[u8],
+        commits: &[Commitment<E>],
+        point_set_index: usize,
+        evals: &[impl AsRef<[E::ScalarField]>],
+        proof: &Self::Proof,
+    ) -> Result<bool, Error> {
+        transcribe_context(transcript, context);
+        self.verify(transcript, commits, point_set_index, evals, proof)
+    }
+
+    /// Same as [`PolyMultiProof::open`], but also returns a digest of the transcript after the last
+    /// challenge. Comparing it with the digest from [`PolyMultiProof::verify_with_digest`] shows
+    /// whether the prover and verifier derived the same challenges.
+    fn open_with_digest(
+        &self,
+        transcript: &mut Transcript,
+        evals: &[impl AsRef<[E::ScalarField]>],
+        polys: &[impl AsRef<[E::ScalarField]>],
+        point_set_index: usize,
+    ) -> Result<(Self::Proof, [u8; 32]), Error> {
+        let proof = self.open(transcript, evals, polys, point_set_index)?;
+        Ok((proof, transcript_state_digest(transcript)))
+    }
+
+    /// Same as [`PolyMultiProof::verify`], but also returns a digest of the transcript after the
+    /// last challenge
+    fn verify_with_digest(
+        &self,
+        transcript: &mut Transcript,
+        commits: &[Commitment<E>],
+        point_set_index: usize,
+        evals: &[impl AsRef<[E::ScalarField]>],
+        proof: &Self::Proof,
+    ) -> Result<(bool, [u8; 32]), Error> {
+        let valid = self.verify(transcript, commits, point_set_index, evals, proof)?;
+        Ok((valid, transcript_state_digest(transcript)))
+    }
+
+    /// Same as [`PolyMultiProof::verify`], but an invalid proof is
+    /// [`Error::VerificationFailed`]
+    fn verify_strict(
+        &self,
+        transcript: &mut Transcript,
+        commits: &[Commitment<E>],
+        point_set_index: usize,
+        evals: &[impl AsRef<[E::ScalarField]>],
+        proof: &Self::Proof,
+    ) -> Result<(), Error> {
+        if self.verify(transcript, commits, point_set_index, evals, proof)? {
+            Ok(())
+        } else {
+            Err(Error::VerificationFailed)
+        }
+    }
 }
 
 pub trait PolyMultiProofNoPrecomp<E: Pairing>: Sized {
-    type Proof: Clone;
+    type Proof: Clone + Valid;
 
     fn new(max_coeffs: usize, max_pts: Option<usize>, r: &mut impl RngCore) -> Result<Self, Error>;
 
+    /// `evals` and `polys` can be slices of anything that borrows as a slice of scalars, such as
+    /// `&[Vec<F>]` or `&[&[F]]`. A window into a larger grid of evaluations can be passed by
+    /// borrowing the relevant part of each row, without copying any scalars.
     fn open(
         &self,
         transcript: &mut Transcript,
@@ -48,12 +348,357 @@ pub trait PolyMultiProofNoPrecomp<E: Pairing>: Sized {
         points: &[E::ScalarField],
     ) -> Result<Self::Proof, Error>;
 
+    /// Same as `open`, but each polynomial is given by its evaluations over the elements of
+    /// `domain`, as in [`EvalCommitter::commit_lagrange`], and is interpolated before opening
+    fn open_evals(
+        &self,
+        transcript: &mut Transcript,
+        evals: &[impl AsRef<[E::ScalarField]>],
+        domain_evals: &[impl AsRef<[E::ScalarField]>],
+        domain: &GeneralEvaluationDomain<E::ScalarField>,
+        points: &[E::ScalarField],
+    ) -> Result<Self::Proof, Error> {
+        let polys = interpolate_over_domain(domain_evals, domain)?;
+        self.open(transcript, evals, &polys, points)
+    }
+
+    /// Checks that the commitments and the proof are valid points in the prime order subgroup,
+    /// erroring with [`Error::InvalidPoint`] if not, then verifies the opening
     fn verify(
         &self,
         transcript: &mut Transcript,
-        commits: &[Commitment<E>],
+        commits: impl AsRef<[Commitment<E>]>,
+        points: &[E::ScalarField],
+        evals: &[impl AsRef<[E::ScalarField]>],
+        proof: &Self::Proof,
+    ) -> Result<bool, Error> {
+        check_points(commits.as_ref(), proof)?;
+        self.verify_unchecked(transcript, commits, points, evals, proof)
+    }
+
+    /// Same as `verify`, but skips the curve and subgroup checks on the commitments and proof.
+    /// This is only sound when every point has already been validated, e.g. by deserializing it
+    /// with [`Validate::Yes`](ark_serialize::Validate::Yes), and must not be used on untrusted
+    /// points otherwise.
+    fn verify_unchecked(
+        &self,
+        transcript: &mut Transcript,
+        commits: impl AsRef<[Commitment<E>]>,
         points: &[E::ScalarField],
         evals: &[impl AsRef<[E::ScalarField]>],
         proof: &Self::Proof,
     ) -> Result<bool, Error>;
+
+    /// Same as `open`, on a fresh transcript from a
+    /// [`ProofTranscript`](crate::transcript::ProofTranscript), so the opening always starts from
+    /// the pinned label and domain
+    fn open_pinned(
+        &self,
+        mut transcript: PmpTranscript,
+        evals: &[impl AsRef<[E::ScalarField]>],
+        polys: &[impl AsRef<[E::ScalarField]>],
+        points: &[E::ScalarField],
+    ) -> Result<Self::Proof, Error> {
+        self.open(transcript.inner_mut(), evals, polys, points)
+    }
+
+    /// Verifies a proof made with `open_pinned` on a transcript from the same factory
+    fn verify_pinned(
+        &self,
+        mut transcript: PmpTranscript,
+        commits: impl AsRef<[Commitment<E>]>,
+        points: &[E::ScalarField],
+        evals: &[impl AsRef<[E::ScalarField]>],
+        proof: &Self::Proof,
+    ) -> Result<bool, Error> {
+        self.verify(transcript.inner_mut(), commits, points, evals, proof)
+    }
+
+    /// Same as `verify`, but takes each commitment as its compressed bytes and the proof as its
+    /// compressed serialization, as received over the network. Both are decoded with the subgroup
+    /// checks. Malformed input is [`Error::MalformedCommitment`] or [`Error::MalformedProof`],
+    /// while a well formed but invalid proof is `Ok(false)`.
+    fn verify_from_bytes(
+        &self,
+        transcript: &mut Transcript,
+        commits: &[impl AsRef<[u8]>],
+        points: &[E::ScalarField],
+        evals: &[impl AsRef<[E::ScalarField]>],
+        proof: &[u8],
+    ) -> Result<bool, Error>
+    where
+        Self::Proof: CanonicalDeserialize,
+    {
+        let (commits, proof) = decode_commits_and_proof::<E, Self::Proof>(commits, proof)?;
+        self.verify_unchecked(transcript, commits, points, evals, &proof)
+    }
+
+    /// Evaluates each of `polys` at `points` with [`multi_evaluate`](crate::multi_evaluate) and
+    /// opens them, returning the evals alongside the proof, so they can't disagree with `polys`
+    fn evaluate_and_open(
+        &self,
+        transcript: &mut Transcript,
+        polys: &[impl AsRef<[E::ScalarField]>],
+        points: &[E::ScalarField],
+    ) -> Result<(Vec<Vec<E::ScalarField>>, Self::Proof), Error> {
+        let evals = polys
+            .iter()
+            .map(|p| crate::multi_evaluate(p.as_ref(), points))
+            .collect::<Vec<_>>();
+        let proof = self.open(transcript, &evals, polys, points)?;
+        Ok((evals, proof))
+    }
+
+    /// Same as [`PolyMultiProofNoPrecomp::open`], but first absorbs `commits` into the transcript
+    /// so the challenges are bound to the commitments. Proofs made this way must be checked with
+    /// [`PolyMultiProofNoPrecomp::verify_bound`].
+    fn open_bound(
+        &self,
+        transcript: &mut Transcript,
+        commits: &[Commitment<E>],
+        evals: &[impl AsRef<[E::ScalarField]>],
+        polys: &[impl AsRef<[E::ScalarField]>],
+        points: &[E::ScalarField],
+    ) -> Result<Self::Proof, Error> {
+        transcribe_bound_commits(transcript, commits)?;
+        self.open(transcript, evals, polys, points)
+    }
+
+    /// Verifies a proof made with [`PolyMultiProofNoPrecomp::open_bound`]
+    fn verify_bound(
+        &self,
+        transcript: &mut Transcript,
+        commits: &[Commitment<E>],
+        points: &[E::ScalarField],
+        evals: &[impl AsRef<[E::ScalarField]>],
+        proof: &Self::Proof,
+    ) -> Result<bool, Error> {
+        transcribe_bound_commits(transcript, commits)?;
+        self.verify(transcript, commits, points, evals, proof)
+    }
+
+    /// Same as [`PolyMultiProofNoPrecomp::open`], but first absorbs `context` into the transcript,
+    /// binding the proof to e.g. a session id or block height. Proofs made this way must be checked
+    /// with [`PolyMultiProofNoPrecomp::verify_with_context`] and the same `context`. Distinct
+    /// contexts also domain separate instances embedded in one larger protocol, which otherwise
+    /// draw the same challenges for the same inputs.
+    fn open_with_context(
+        &self,
+        transcript: &mut Transcript,
+        context: &[u8],
+        evals: &[impl AsRef<[E::ScalarField]>],
+        polys: &[impl AsRef<[E::ScalarField]>],
+        points: &[E::ScalarField],
+    ) -> Result<Self::Proof, Error> {
+        transcribe_context(transcript, context);
+        self.open(transcript, evals, polys, points)
+    }
+
+    /// Verifies a proof made with [`PolyMultiProofNoPrecomp::open_with_context`]
+    fn verify_with_context(
+        &self,
+        transcript: &mut Transcript,
+        context: &[u8],
+        commits: &[Commitment<E>],
+        points: &[E::ScalarField],
+        evals: &[impl AsRef<[E::ScalarField]>],
+        proof: &Self::Proof,
+    ) -> Result<bool, Error> {
+        transcribe_context(transcript, context);
+        self.verify(transcript, commits, points, evals, proof)
+    }
+
+    /// Same as [`PolyMultiProofNoPrecomp::open`], but also returns a digest of the transcript after
+    /// the last challenge. Comparing it with the digest from
+    /// [`PolyMultiProofNoPrecomp::verify_with_digest`] shows whether the prover and verifier
+    /// derived the same challenges.
+    fn open_with_digest(
+        &self,
+        transcript: &mut Transcript,
+        evals: &[impl AsRef<[E::ScalarField]>],
+        polys: &[impl AsRef<[E::ScalarField]>],
+        points: &[E::ScalarField],
+    ) -> Result<(Self::Proof, [u8; 32]), Error> {
+        let proof = self.open(transcript, evals, polys, points)?;
+        Ok((proof, transcript_state_digest(transcript)))
+    }
+
+    /// Same as [`PolyMultiProofNoPrecomp::verify`], but also returns a digest of the transcript
+    /// after the last challenge
+    fn verify_with_digest(
+        &self,
+        transcript: &mut Transcript,
+        commits: &[Commitment<E>],
+        points: &[E::ScalarField],
+        evals: &[impl AsRef<[E::ScalarField]>],
+        proof: &Self::Proof,
+    ) -> Result<(bool, [u8; 32]), Error> {
+        let valid = self.verify(transcript, commits, points, evals, proof)?;
+        Ok((valid, transcript_state_digest(transcript)))
+    }
+
+    /// Same as [`PolyMultiProofNoPrecomp::verify`], but an invalid proof is
+    /// [`Error::VerificationFailed`]
+    fn verify_strict(
+        &self,
+        transcript: &mut Transcript,
+        commits: &[Commitment<E>],
+        points: &[E::ScalarField],
+        evals: &[impl AsRef<[E::ScalarField]>],
+        proof: &Self::Proof,
+    ) -> Result<(), Error> {
+        if self.verify(transcript, commits, points, evals, proof)? {
+            Ok(())
+        } else {
+            Err(Error::VerificationFailed)
+        }
+    }
+
+    /// Same as [`PolyMultiProofNoPrecomp::open`], but opens at `points[i]` for each `i` in
+    /// `positions`, such as the sampled cells of a row. `evals` are at those positions only.
+    fn open_at_positions(
+        &self,
+        transcript: &mut Transcript,
+        evals: &[impl AsRef<[E::ScalarField]>],
+        polys: &[impl AsRef<[E::ScalarField]>],
+        points: &[E::ScalarField],
+        positions: &[usize],
+    ) -> Result<Self::Proof, Error> {
+        let selected = select_positions(points, positions)?;
+        self.open(transcript, evals, polys, &selected)
+    }
+
+    /// Verifies a proof made with [`PolyMultiProofNoPrecomp::open_at_positions`]. Errors if a
+    /// position is out of range for `points` or appears more than once.
+    fn verify_at_positions(
+        &self,
+        transcript: &mut Transcript,
+        commits: &[Commitment<E>],
+        points: &[E::ScalarField],
+        positions: &[usize],
+        evals: &[impl AsRef<[E::ScalarField]>],
+        proof: &Self::Proof,
+    ) -> Result<bool, Error> {
+        let selected = select_positions(points, positions)?;
+        self.verify(transcript, commits, &selected, evals, proof)
+    }
+
+    /// Same as [`PolyMultiProofNoPrecomp::open`], but only opens `polys[i]` for each `i` in
+    /// `indices`, so a proof can cover some of the committed polynomials without rearranging
+    /// them. `evals` has an entry per polynomial, like `polys`, and only the selected ones are
+    /// used.
+    fn open_subset(
+        &self,
+        transcript: &mut Transcript,
+        indices: &[usize],
+        evals: &[impl AsRef<[E::ScalarField]>],
+        polys: &[impl AsRef<[E::ScalarField]>],
+        points: &[E::ScalarField],
+    ) -> Result<Self::Proof, Error> {
+        let evals = select_indices(evals, indices)?;
+        let polys = select_indices(polys, indices)?;
+        self.open(transcript, &evals, &polys, points)
+    }
+
+    /// Verifies a proof made with [`PolyMultiProofNoPrecomp::open_subset`] against the same
+    /// `indices` into `commits` and `evals`. Errors if an index is out of range or appears more
+    /// than once.
+    fn verify_subset(
+        &self,
+        transcript: &mut Transcript,
+        indices: &[usize],
+        commits: &[Commitment<E>],
+        points: &[E::ScalarField],
+        evals: &[impl AsRef<[E::ScalarField]>],
+        proof: &Self::Proof,
+    ) -> Result<bool, Error> {
+        let commits = select_indices(commits, indices)?
+            .into_iter()
+            .map(|c| Commitment(c.0))
+            .collect::<Vec<_>>();
+        let evals = select_indices(evals, indices)?;
+        self.verify(transcript, commits, points, &evals, proof)
+    }
+}
+
+/// Borrows `items[i]` for each `i` in `indices`, which must be in range and distinct
+fn select_indices<'a, T>(items: &'a [T], indices: &[usize]) -> Result<Vec<&'a T>, Error> {
+    let mut seen = vec![false; items.len()];
+    indices
+        .iter()
+        .map(|&i| {
+            let seen = seen.get_mut(i).ok_or(Error::PolyIndexOutOfRange {
+                index: i,
+                n: items.len(),
+            })?;
+            if std::mem::replace(seen, true) {
+                return Err(Error::DuplicatePolyIndex { index: i });
+            }
+            Ok(&items[i])
+        })
+        .collect()
+}
+
+/// Picks out `points[i]` for each `i` in `positions`, which must be in range and distinct
+fn select_positions<F: Copy>(points: &[F], positions: &[usize]) -> Result<Vec<F>, Error> {
+    let mut seen = vec![false; points.len()];
+    positions
+        .iter()
+        .map(|&i| {
+            let seen = seen.get_mut(i).ok_or(Error::PositionOutOfRange {
+                position: i,
+                n: points.len(),
+            })?;
+            if std::mem::replace(seen, true) {
+                return Err(Error::DuplicatePosition { position: i });
+            }
+            Ok(points[i])
+        })
+        .collect()
+}
+
+fn interpolate_over_domain<F: FftField>(
+    domain_evals: &[impl AsRef<[F]>],
+    domain: &GeneralEvaluationDomain<F>,
+) -> Result<Vec<Vec<F>>, Error> {
+    domain_evals
+        .iter()
+        .enumerate()
+        .map(|(poly, e)| {
+            if e.as_ref().len() != domain.size() {
+                return Err(Error::EvalsIncorrectSize {
+                    poly,
+                    n: e.as_ref().len(),
+                    expected: domain.size(),
+                });
+            }
+            Ok(domain.ifft(e.as_ref()))
+        })
+        .collect()
+}
+
+/// Decodes compressed commitments and a compressed proof, validating every point. Trailing bytes
+/// after any of them make the input malformed.
+fn decode_commits_and_proof<E: Pairing, P: CanonicalDeserialize>(
+    commits: &[impl AsRef<[u8]>],
+    proof: &[u8],
+) -> Result<(Vec<Commitment<E>>, P), Error> {
+    let commits = commits
+        .iter()
+        .enumerate()
+        .map(|(index, bytes)| {
+            from_exact_compressed(bytes.as_ref(), Error::MalformedCommitment { index })
+        })
+        .collect::<Result<Vec<_>, Error>>()?;
+    let proof = from_exact_compressed(proof, Error::MalformedProof)?;
+    Ok((commits, proof))
+}
+
+pub(crate) fn check_points<E: Pairing>(
+    commits: &[Commitment<E>],
+    proof: &impl Valid,
+) -> Result<(), Error> {
+    Commitment::<E>::batch_check(commits.iter())
+        .and_then(|_| proof.check())
+        .map_err(|_| Error::InvalidPoint)
 }
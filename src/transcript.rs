@@ -0,0 +1,110 @@
+//! A factory for the transcripts that openings start from, so the prover and verifier can't
+//! start from differently labeled merlin transcripts and end up deriving different challenges.
+//! The transcripts it makes can only be used through `open_pinned` and `verify_pinned`, which
+//! take them fresh, so nothing else can be absorbed before the opening.
+
+use merlin::Transcript;
+
+/// The label every [`PmpTranscript`] is created with, before its domain is absorbed
+pub const PMP_TRANSCRIPT_LABEL: &[u8] = b"poly-multiproof";
+
+/// Creates the [`PmpTranscript`]s of one protocol. Define it once, e.g. as a `const`, and use it
+/// on both the prover and verifier side.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ProofTranscript {
+    domain: &'static [u8],
+}
+
+impl ProofTranscript {
+    pub const fn new(domain: &'static [u8]) -> Self {
+        Self { domain }
+    }
+
+    pub fn domain(&self) -> &'static [u8] {
+        self.domain
+    }
+
+    /// A fresh transcript labeled [`PMP_TRANSCRIPT_LABEL`] that has absorbed the domain as
+    /// `pmp domain`, ready to be passed to `open_pinned` or `verify_pinned`
+    pub fn transcript(&self) -> PmpTranscript {
+        let mut transcript = Transcript::new(PMP_TRANSCRIPT_LABEL);
+        transcript.append_message(b"pmp domain", self.domain);
+        PmpTranscript(transcript)
+    }
+}
+
+/// A fresh merlin transcript that can only be created by a [`ProofTranscript`]. It can't be
+/// appended to directly, so the only messages it absorbs are the ones `open_pinned` and
+/// `verify_pinned` absorb, in the documented order.
+#[derive(Clone)]
+pub struct PmpTranscript(Transcript);
+
+impl PmpTranscript {
+    /// The underlying merlin transcript, for APIs that take one directly. Nothing stops it from
+    /// absorbing other messages once unwrapped.
+    pub fn into_inner(self) -> Transcript {
+        self.0
+    }
+
+    pub(crate) fn inner_mut(&mut self) -> &mut Transcript {
+        &mut self.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ProofTranscript;
+    use crate::{
+        method1::M1NoPrecomp,
+        test_rng,
+        traits::{Committer, PolyMultiProofNoPrecomp},
+    };
+    use ark_bls12_381::{Bls12_381, Fr};
+    use ark_poly::{univariate::DensePolynomial, DenseUVPolynomial, Polynomial};
+    use ark_std::UniformRand;
+    use merlin::Transcript;
+
+    const TRANSCRIPT: ProofTranscript = ProofTranscript::new(b"testing");
+
+    #[test]
+    fn test_factories_cross_verify() {
+        let s = M1NoPrecomp::<Bls12_381>::new(32, 4.into(), &mut test_rng()).unwrap();
+        let points = (0..4)
+            .map(|_| Fr::rand(&mut test_rng()))
+            .collect::<Vec<_>>();
+        let poly = DensePolynomial::<Fr>::rand(20, &mut test_rng());
+        let evals = vec![points.iter().map(|x| poly.evaluate(x)).collect::<Vec<_>>()];
+        let commits = vec![s.commit(&poly.coeffs).unwrap()];
+        let open = s
+            .open_pinned(TRANSCRIPT.transcript(), &evals, &[&poly.coeffs], &points)
+            .unwrap();
+
+        let verify = |factory: ProofTranscript| {
+            s.verify_pinned(factory.transcript(), &commits, &points, &evals, &open)
+        };
+        assert_eq!(Ok(true), verify(TRANSCRIPT));
+        assert_eq!(Ok(false), verify(ProofTranscript::new(b"other")));
+        // The same as opening on the unwrapped transcript
+        assert_eq!(
+            Ok(true),
+            s.verify(
+                &mut TRANSCRIPT.transcript().into_inner(),
+                &commits,
+                &points,
+                &evals,
+                &open
+            )
+        );
+        // A raw transcript with the domain as its label isn't the same
+        assert_eq!(
+            Ok(false),
+            s.verify(
+                &mut Transcript::new(b"testing"),
+                &commits,
+                &points,
+                &evals,
+                &open
+            )
+        );
+    }
+}
@@ -0,0 +1,50 @@
+//! Only built with `--no-default-features`, to check that the generic methods work without blst,
+//! e.g. `cargo test --no-default-features --test no_blst`
+#![cfg(not(feature = "blst"))]
+
+use ark_bls12_381::{Bls12_381, Fr};
+use ark_poly::{univariate::DensePolynomial, DenseUVPolynomial, Polynomial};
+use ark_std::{test_rng, UniformRand};
+use merlin::Transcript;
+use poly_multiproof::{
+    method1::M1NoPrecomp,
+    method2::M2NoPrecomp,
+    traits::{Committer, PolyMultiProofNoPrecomp},
+};
+
+fn round_trip<P: PolyMultiProofNoPrecomp<Bls12_381> + Committer<Bls12_381>>(s: &P) {
+    let rng = &mut test_rng();
+    let points = (0..4).map(|_| Fr::rand(rng)).collect::<Vec<_>>();
+    let polys = (0..3)
+        .map(|_| DensePolynomial::<Fr>::rand(30, rng))
+        .collect::<Vec<_>>();
+    let evals = polys
+        .iter()
+        .map(|p| points.iter().map(|x| p.evaluate(x)).collect::<Vec<_>>())
+        .collect::<Vec<_>>();
+    let coeffs = polys.iter().map(|p| &p.coeffs).collect::<Vec<_>>();
+    let commits = s.commit_many(&coeffs).unwrap();
+    let proof = s
+        .open(&mut Transcript::new(b"no blst"), &evals, &coeffs, &points)
+        .unwrap();
+    assert_eq!(
+        Ok(true),
+        s.verify(
+            &mut Transcript::new(b"no blst"),
+            &commits,
+            &points,
+            &evals,
+            &proof
+        )
+    );
+}
+
+#[test]
+fn test_method2_round_trip() {
+    round_trip(&M2NoPrecomp::<Bls12_381>::new(32, None, &mut test_rng()).unwrap());
+}
+
+#[test]
+fn test_method1_round_trip() {
+    round_trip(&M1NoPrecomp::<Bls12_381>::new(32, None, &mut test_rng()).unwrap());
+}
@@ -0,0 +1,112 @@
+//! The generic methods on curves other than BLS12-381, which only the blst backend is tied to
+
+use ark_ec::pairing::Pairing;
+use ark_poly::{univariate::DensePolynomial, DenseUVPolynomial, Polynomial};
+use ark_std::{test_rng, UniformRand};
+use merlin::Transcript;
+use poly_multiproof::{
+    method1::{precompute::M1Precomp, M1NoPrecomp},
+    method2::M2NoPrecomp,
+    traits::{Committer, PolyMultiProof, PolyMultiProofNoPrecomp},
+};
+
+fn round_trip<E: Pairing, P: PolyMultiProofNoPrecomp<E> + Committer<E>>() {
+    let rng = &mut test_rng();
+    let s = P::new(32, None, rng).unwrap();
+    let points = (0..4)
+        .map(|_| E::ScalarField::rand(rng))
+        .collect::<Vec<_>>();
+    let polys = (0..3)
+        .map(|_| DensePolynomial::<E::ScalarField>::rand(30, rng))
+        .collect::<Vec<_>>();
+    let mut evals = polys
+        .iter()
+        .map(|p| points.iter().map(|x| p.evaluate(x)).collect::<Vec<_>>())
+        .collect::<Vec<_>>();
+    let coeffs = polys.iter().map(|p| &p.coeffs).collect::<Vec<_>>();
+    let commits = s.commit_many(&coeffs).unwrap();
+    let proof = s
+        .open(
+            &mut Transcript::new(b"other curves"),
+            &evals,
+            &coeffs,
+            &points,
+        )
+        .unwrap();
+    assert_eq!(
+        Ok(true),
+        s.verify(
+            &mut Transcript::new(b"other curves"),
+            &commits,
+            &points,
+            &evals,
+            &proof
+        )
+    );
+    evals[1][2] += E::ScalarField::from(1u64);
+    assert_eq!(
+        Ok(false),
+        s.verify(
+            &mut Transcript::new(b"other curves"),
+            &commits,
+            &points,
+            &evals,
+            &proof
+        )
+    );
+}
+
+fn precomp_round_trip<E: Pairing>() {
+    let rng = &mut test_rng();
+    let point_sets = (0..2)
+        .map(|_| {
+            (0..4)
+                .map(|_| E::ScalarField::rand(rng))
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+    let s = M1Precomp::<E>::new(32, point_sets.clone(), rng).unwrap();
+    let polys = (0..3)
+        .map(|_| DensePolynomial::<E::ScalarField>::rand(30, rng))
+        .collect::<Vec<_>>();
+    let evals = polys
+        .iter()
+        .map(|p| {
+            point_sets[1]
+                .iter()
+                .map(|x| p.evaluate(x))
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+    let coeffs = polys.iter().map(|p| &p.coeffs).collect::<Vec<_>>();
+    let commits = s.commit_many(&coeffs).unwrap();
+    let proof = s
+        .open(&mut Transcript::new(b"other curves"), &evals, &coeffs, 1)
+        .unwrap();
+    assert_eq!(
+        Ok(true),
+        s.verify(
+            &mut Transcript::new(b"other curves"),
+            &commits,
+            1,
+            &evals,
+            &proof
+        )
+    );
+}
+
+#[test]
+fn test_bn254() {
+    use ark_bn254::Bn254;
+    round_trip::<Bn254, M1NoPrecomp<Bn254>>();
+    round_trip::<Bn254, M2NoPrecomp<Bn254>>();
+    precomp_round_trip::<Bn254>();
+}
+
+#[test]
+fn test_bls12_377() {
+    use ark_bls12_377::Bls12_377;
+    round_trip::<Bls12_377, M1NoPrecomp<Bls12_377>>();
+    round_trip::<Bls12_377, M2NoPrecomp<Bls12_377>>();
+    precomp_round_trip::<Bls12_377>();
+}